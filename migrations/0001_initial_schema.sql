@@ -0,0 +1,368 @@
+-- 初始表结构。用旧版 schema.sql 建过库的数据库也可以直接执行：
+-- 建表和建索引都带 IF NOT EXISTS，种子数据只补缺失的行，旧库缺少的列由 0002 补齐，
+-- 旧版图片表和缺少的唯一约束由 0004 处理（图片表的内容哈希索引也在 0004 中建）
+
+-- 创建用户表
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY,
+    username VARCHAR(50) NOT NULL UNIQUE,
+    email VARCHAR(100) NOT NULL UNIQUE,
+    password_hash VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 创建索引
+CREATE INDEX IF NOT EXISTS idx_users_username ON users(username);
+CREATE INDEX IF NOT EXISTS idx_users_email ON users(email);
+
+-- 创建文章表
+CREATE TABLE IF NOT EXISTS articles (
+    id UUID PRIMARY KEY,
+    title TEXT NOT NULL,
+    content TEXT,
+    content_html TEXT, -- 清洗后的 HTML 正文
+    summary TEXT, -- 抽取式摘要，列表接口默认返回它而不是正文
+    url TEXT NOT NULL UNIQUE,
+    source_name TEXT,
+    published_at TIMESTAMP WITH TIME ZONE,
+    crawled_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    categories TEXT[],
+    source_category VARCHAR(50), -- 新闻源栏目给出的类别
+    feature_vector BYTEA,
+    like_count INTEGER DEFAULT 0,
+    comment_count INTEGER DEFAULT 0,
+    favorite_count INTEGER DEFAULT 0,
+    -- 文章状态：隐藏和删除的文章不出现在列表、检索和推荐中；删除为软删除，行仍保留
+    updated_at TIMESTAMP WITH TIME ZONE, -- 最近一次修改标题或正文的时间
+    hidden_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE
+);
+
+-- 创建文章索引
+CREATE INDEX IF NOT EXISTS idx_articles_published_at ON articles(published_at);
+CREATE INDEX IF NOT EXISTS idx_articles_source_name ON articles(source_name);
+CREATE INDEX IF NOT EXISTS idx_articles_crawled_at ON articles(crawled_at);
+-- 文章列表按最新排序和键集翻页（见 article_queries::ArticleFilter::order_by）
+CREATE INDEX IF NOT EXISTS idx_articles_newest ON articles((COALESCE(published_at, crawled_at)) DESC, id DESC);
+
+-- 创建文章图片表（文件按内容 SHA-256 存储，多篇文章可共用同一文件）
+CREATE TABLE IF NOT EXISTS news_images (
+    id SERIAL PRIMARY KEY,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL, -- 在文章中的顺序，从 0 开始
+    source_url TEXT NOT NULL, -- 原始图片地址
+    caption TEXT,
+    content_hash CHAR(64) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    width INTEGER,
+    height INTEGER,
+    storage_path TEXT NOT NULL, -- 存储键（相对于 STORAGE_BACKEND 的根），旧数据为 storage/img/ 开头的本地路径
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(article_id, position)
+);
+
+-- 下载后未通过校验的图片（非图片内容、过大、无法解码等）
+CREATE TABLE IF NOT EXISTS rejected_images (
+    id SERIAL PRIMARY KEY,
+    article_id UUID REFERENCES articles(id) ON DELETE CASCADE,
+    source_url TEXT NOT NULL,
+    reason VARCHAR(50) NOT NULL, -- 'too_large', 'not_an_image', 'undecodable', 'dimensions_too_large', 'too_small'
+    detail TEXT,
+    declared_content_type VARCHAR(100), -- 响应头中声明的类型
+    byte_size BIGINT, -- 超过上限时为空
+    rejected_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_rejected_images_rejected_at ON rejected_images(rejected_at);
+
+-- 编辑维护的类别表。articles.categories、article_category_labels 和 source_category 中保存的都是 name_zh
+CREATE TABLE IF NOT EXISTS categories (
+    id SERIAL PRIMARY KEY,
+    slug VARCHAR(50) NOT NULL UNIQUE, -- 英文标识，用于 URL
+    name_zh VARCHAR(50) NOT NULL UNIQUE,
+    name_en VARCHAR(100) NOT NULL,
+    parent_id INTEGER REFERENCES categories(id) ON DELETE SET NULL,
+    position INTEGER NOT NULL DEFAULT 0, -- 同级类别的显示顺序
+    icon VARCHAR(50), -- 前端图标的 CSS 类
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 类别别名，例如“财经”指向“经济”；解析类别名称时与 name_zh / name_en / slug 一起匹配
+CREATE TABLE IF NOT EXISTS category_aliases (
+    alias VARCHAR(50) PRIMARY KEY,
+    category_id INTEGER NOT NULL REFERENCES categories(id) ON DELETE CASCADE
+);
+
+INSERT INTO categories (slug, name_zh, name_en, position, icon)
+SELECT s.slug, s.name_zh, s.name_en, s.position, s.icon FROM (VALUES
+    ('politics', '政治', 'Politics', 1, 'fas fa-landmark'),
+    ('economy', '经济', 'Economy', 2, 'fas fa-chart-line'),
+    ('technology', '科技', 'Technology', 3, 'fas fa-microchip'),
+    ('sports', '体育', 'Sports', 4, 'fas fa-futbol'),
+    ('entertainment', '娱乐', 'Entertainment', 5, 'fas fa-film'),
+    ('health', '健康', 'Health', 6, 'fas fa-heartbeat'),
+    ('education', '教育', 'Education', 7, 'fas fa-graduation-cap'),
+    ('environment', '环境', 'Environment', 8, 'fas fa-leaf'),
+    ('world', '国际', 'World', 9, 'fas fa-globe'),
+    ('society', '社会', 'Society', 10, 'fas fa-users'),
+    ('other', '其他', 'Other', 99, 'fas fa-tag')
+) AS s(slug, name_zh, name_en, position, icon)
+WHERE NOT EXISTS (SELECT 1 FROM categories c WHERE c.slug = s.slug OR c.name_zh = s.name_zh);
+
+INSERT INTO categories (slug, name_zh, name_en, parent_id, position, icon)
+SELECT 'military', '军事', 'Military', id, 1, 'fas fa-shield-alt' FROM categories WHERE slug = 'world'
+    AND NOT EXISTS (SELECT 1 FROM categories c WHERE c.slug = 'military' OR c.name_zh = '军事');
+
+INSERT INTO category_aliases (alias, category_id)
+SELECT a.alias, c.id FROM (VALUES
+    ('财经', 'economy'),
+    ('金融', 'economy'),
+    ('时政', 'politics'),
+    ('国内', 'society'),
+    ('地方', 'society'),
+    ('文娱', 'entertainment'),
+    ('国际新闻', 'world'),
+    ('未分类', 'other')
+) AS a(alias, slug) JOIN categories c ON c.slug = a.slug
+WHERE NOT EXISTS (SELECT 1 FROM category_aliases e WHERE e.alias = a.alias);
+
+-- 文章类别标注：人工标注用于训练分类器，分类器结果附带置信度
+CREATE TABLE IF NOT EXISTS article_category_labels (
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    category VARCHAR(50) NOT NULL,
+    source VARCHAR(20) NOT NULL, -- 'manual', 'classifier'
+    confidence DOUBLE PRECISION, -- 人工标注为空
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (article_id, category, source)
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_category_labels_source ON article_category_labels(source);
+
+-- 文章修订历史：每次重新抓取或管理员编辑改动了标题或正文时，保存被替换掉的旧版本
+CREATE TABLE IF NOT EXISTS article_revisions (
+    id SERIAL PRIMARY KEY,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    content TEXT,
+    content_html TEXT,
+    summary TEXT,
+    source VARCHAR(20) NOT NULL, -- 'crawl', 'admin'：是哪种修改替换了这个版本
+    edited_by UUID REFERENCES users(id) ON DELETE SET NULL, -- 管理员编辑时为编辑者
+    revised_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_revisions_article ON article_revisions(article_id, revised_at DESC);
+
+-- 文章标签：入库时抽取的关键词和人名、机构、地名，用于按标签浏览和推荐
+CREATE TABLE IF NOT EXISTS article_tags (
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    tag VARCHAR(100) NOT NULL,
+    kind VARCHAR(20) NOT NULL, -- 'keyword', 'person', 'organization', 'place'
+    weight DOUBLE PRECISION NOT NULL, -- 同一种类内归一化到 (0, 1]
+    PRIMARY KEY (article_id, tag, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_tags_tag ON article_tags(tag);
+
+-- 全文检索的倒排索引：标题和正文经 jieba 搜索模式分词后的词频，标题中的词按更高权重计数
+CREATE TABLE IF NOT EXISTS article_search_docs (
+    article_id UUID PRIMARY KEY REFERENCES articles(id) ON DELETE CASCADE,
+    length INTEGER NOT NULL -- 加权后的总词数，BM25 按它做文档长度归一化
+);
+
+CREATE TABLE IF NOT EXISTS article_search_terms (
+    term VARCHAR(100) NOT NULL,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    tf INTEGER NOT NULL, -- 加权后的词频
+    PRIMARY KEY (term, article_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_article_search_terms_article_id ON article_search_terms(article_id);
+
+-- 搜索日志，用于统计热门搜索词；不记录用户和来源地址
+CREATE TABLE IF NOT EXISTS search_query_log (
+    id BIGSERIAL PRIMARY KEY,
+    query VARCHAR(100) NOT NULL, -- 合并空白并转为小写
+    result_count INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_query_log_created_at ON search_query_log(created_at);
+
+-- 创建用户互动表（点赞、收藏等）
+CREATE TABLE IF NOT EXISTS user_interactions (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    interaction_type VARCHAR(20) NOT NULL, -- 'like', 'favorite', 'share'
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, article_id, interaction_type)
+);
+
+-- 创建用户互动索引
+CREATE INDEX IF NOT EXISTS idx_user_interactions_user_id ON user_interactions(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_interactions_article_id ON user_interactions(article_id);
+CREATE INDEX IF NOT EXISTS idx_user_interactions_type ON user_interactions(interaction_type);
+CREATE INDEX IF NOT EXISTS idx_user_interactions_user_updated ON user_interactions(user_id, updated_at DESC, id DESC);
+
+-- 创建评论表
+CREATE TABLE IF NOT EXISTS comments (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    username VARCHAR(50) NOT NULL,
+    content TEXT NOT NULL,
+    parent_comment_id UUID REFERENCES comments(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- 创建评论索引
+CREATE INDEX IF NOT EXISTS idx_comments_article_id ON comments(article_id);
+CREATE INDEX IF NOT EXISTS idx_comments_user_id ON comments(user_id);
+CREATE INDEX IF NOT EXISTS idx_comments_parent_id ON comments(parent_comment_id);
+CREATE INDEX IF NOT EXISTS idx_comments_created_at ON comments(created_at);
+CREATE INDEX IF NOT EXISTS idx_comments_article_created ON comments(article_id, created_at DESC, id DESC);
+CREATE INDEX IF NOT EXISTS idx_comments_user_created ON comments(user_id, created_at DESC, id DESC);
+
+-- 创建用户阅读历史表
+CREATE TABLE IF NOT EXISTS reading_history (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    read_duration INTEGER DEFAULT 0, -- 阅读时长（秒）
+    read_percentage FLOAT DEFAULT 0.0, -- 阅读百分比
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, article_id)
+);
+
+-- 创建阅读历史索引
+CREATE INDEX IF NOT EXISTS idx_reading_history_user_id ON reading_history(user_id);
+CREATE INDEX IF NOT EXISTS idx_reading_history_article_id ON reading_history(article_id);
+CREATE INDEX IF NOT EXISTS idx_reading_history_created_at ON reading_history(created_at);
+
+-- 创建用户收藏夹表
+CREATE TABLE IF NOT EXISTS user_favorites (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    folder_name VARCHAR(100) DEFAULT 'default',
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, article_id)
+);
+
+-- 创建收藏夹索引
+CREATE INDEX IF NOT EXISTS idx_user_favorites_user_id ON user_favorites(user_id);
+CREATE INDEX IF NOT EXISTS idx_user_favorites_article_id ON user_favorites(article_id);
+CREATE INDEX IF NOT EXISTS idx_user_favorites_folder ON user_favorites(folder_name);
+CREATE INDEX IF NOT EXISTS idx_user_favorites_user_created ON user_favorites(user_id, created_at DESC, id DESC);
+
+-- 创建用户反馈表（感兴趣 / 不感兴趣 / 一般），每个用户对每篇文章保留最近一次
+CREATE TABLE IF NOT EXISTS feedback (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+    feedback_type VARCHAR(20) NOT NULL, -- 'interested', 'not_interested', 'neutral'
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(user_id, article_id)
+);
+
+-- 创建反馈索引
+CREATE INDEX IF NOT EXISTS idx_feedback_article_id ON feedback(article_id);
+CREATE INDEX IF NOT EXISTS idx_feedback_user_created ON feedback(user_id, created_at DESC);
+
+-- 创建触发器函数来更新文章统计数据
+CREATE OR REPLACE FUNCTION update_article_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'user_interactions' THEN
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'like' AND NEW.is_active = TRUE THEN
+            UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'like' THEN
+            IF OLD.is_active = FALSE AND NEW.is_active = TRUE THEN
+                UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+            ELSIF OLD.is_active = TRUE AND NEW.is_active = FALSE THEN
+                UPDATE articles SET like_count = like_count - 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'like' AND OLD.is_active = TRUE THEN
+            UPDATE articles SET like_count = like_count - 1 WHERE id = OLD.article_id;
+        END IF;
+
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'favorite' AND NEW.is_active = TRUE THEN
+            UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'favorite' THEN
+            IF OLD.is_active = FALSE AND NEW.is_active = TRUE THEN
+                UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+            ELSIF OLD.is_active = TRUE AND NEW.is_active = FALSE THEN
+                UPDATE articles SET favorite_count = favorite_count - 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'favorite' AND OLD.is_active = TRUE THEN
+            UPDATE articles SET favorite_count = favorite_count - 1 WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    IF TG_TABLE_NAME = 'comments' THEN
+        IF TG_OP = 'INSERT' THEN
+            UPDATE articles SET comment_count = comment_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'DELETE' THEN
+            UPDATE articles SET comment_count = comment_count - 1 WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+-- 创建触发器（先删除再创建，已用旧版 schema.sql 建库时可重复执行；
+-- 触发函数用 EXECUTE PROCEDURE 调用，openGauss 不认新写法）
+DROP TRIGGER IF EXISTS trigger_update_article_stats_interactions ON user_interactions;
+CREATE TRIGGER trigger_update_article_stats_interactions
+    AFTER INSERT OR UPDATE OR DELETE ON user_interactions
+    FOR EACH ROW EXECUTE PROCEDURE update_article_stats();
+
+DROP TRIGGER IF EXISTS trigger_update_article_stats_comments ON comments;
+CREATE TRIGGER trigger_update_article_stats_comments
+    AFTER INSERT OR DELETE ON comments
+    FOR EACH ROW EXECUTE PROCEDURE update_article_stats();
+
+-- 创建爬虫运行记录表
+CREATE TABLE IF NOT EXISTS crawl_runs (
+    id UUID PRIMARY KEY,
+    triggered_by VARCHAR(20) NOT NULL DEFAULT 'schedule', -- 'schedule', 'manual'
+    status VARCHAR(20) NOT NULL DEFAULT 'running', -- 'running', 'completed', 'failed'
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    finished_at TIMESTAMP WITH TIME ZONE,
+    urls_found INTEGER NOT NULL DEFAULT 0,
+    articles_inserted INTEGER NOT NULL DEFAULT 0,
+    articles_updated INTEGER NOT NULL DEFAULT 0, -- 已有文章内容有变化并已更新
+    duplicates INTEGER NOT NULL DEFAULT 0,
+    parse_failures INTEGER NOT NULL DEFAULT 0,
+    http_errors INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawl_runs_started_at ON crawl_runs(started_at);
+
+-- 创建爬虫分源统计表（每次运行、每个列表页选择器一行）
+CREATE TABLE IF NOT EXISTS crawl_source_stats (
+    id UUID PRIMARY KEY,
+    run_id UUID NOT NULL REFERENCES crawl_runs(id) ON DELETE CASCADE,
+    source_name TEXT NOT NULL,
+    listing_url TEXT NOT NULL,
+    link_selector TEXT NOT NULL,
+    started_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    finished_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    urls_found INTEGER NOT NULL DEFAULT 0,
+    articles_inserted INTEGER NOT NULL DEFAULT 0,
+    articles_updated INTEGER NOT NULL DEFAULT 0, -- 已有文章内容有变化并已更新
+    duplicates INTEGER NOT NULL DEFAULT 0,
+    parse_failures INTEGER NOT NULL DEFAULT 0,
+    http_errors INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawl_source_stats_run_id ON crawl_source_stats(run_id);
+CREATE INDEX IF NOT EXISTS idx_crawl_source_stats_source ON crawl_source_stats(source_name, listing_url, link_selector, started_at);
@@ -0,0 +1,24 @@
+-- 补齐旧版 schema.sql 建库后陆续增加的列；新建的数据库在 0001 中已经有这些列，这里不做任何改动。
+-- openGauss 的 ALTER TABLE 加列不支持 IF NOT EXISTS，所以先查 information_schema 再加列
+CREATE OR REPLACE FUNCTION add_column_if_missing(p_table TEXT, p_column TEXT, p_definition TEXT)
+RETURNS VOID AS $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = p_table AND column_name = p_column
+    ) THEN
+        EXECUTE 'ALTER TABLE ' || quote_ident(p_table) || ' ADD COLUMN ' || quote_ident(p_column) || ' ' || p_definition;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT add_column_if_missing('articles', 'content_html', 'TEXT');
+SELECT add_column_if_missing('articles', 'source_category', 'VARCHAR(50)');
+SELECT add_column_if_missing('articles', 'summary', 'TEXT');
+SELECT add_column_if_missing('articles', 'updated_at', 'TIMESTAMP WITH TIME ZONE');
+SELECT add_column_if_missing('articles', 'hidden_at', 'TIMESTAMP WITH TIME ZONE');
+SELECT add_column_if_missing('articles', 'deleted_at', 'TIMESTAMP WITH TIME ZONE');
+SELECT add_column_if_missing('crawl_runs', 'articles_updated', 'INTEGER NOT NULL DEFAULT 0');
+SELECT add_column_if_missing('crawl_source_stats', 'articles_updated', 'INTEGER NOT NULL DEFAULT 0');
+
+DROP FUNCTION add_column_if_missing(TEXT, TEXT, TEXT);
@@ -0,0 +1,100 @@
+-- 文章的点赞、收藏、评论计数由触发器在互动、收藏、评论变化的同一事务中维护。
+-- 收藏有两个入口（收藏夹 user_favorites 和 favorite 类型的互动），favorite_count 为通过任一入口收藏了文章的用户数；
+-- 之前的触发器没有处理收藏夹。两处收藏在同一条语句中被级联删除时（如删除用户）会多减一次，
+-- 计数有偏差时用 recount-articles 子命令按明细表重算
+
+UPDATE articles SET like_count = 0 WHERE like_count IS NULL;
+UPDATE articles SET comment_count = 0 WHERE comment_count IS NULL;
+UPDATE articles SET favorite_count = 0 WHERE favorite_count IS NULL;
+ALTER TABLE articles ALTER COLUMN like_count SET NOT NULL;
+ALTER TABLE articles ALTER COLUMN comment_count SET NOT NULL;
+ALTER TABLE articles ALTER COLUMN favorite_count SET NOT NULL;
+
+CREATE OR REPLACE FUNCTION update_article_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'user_interactions' THEN
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'like' AND NEW.is_active = TRUE THEN
+            UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'like' THEN
+            IF OLD.is_active = FALSE AND NEW.is_active = TRUE THEN
+                UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+            ELSIF OLD.is_active = TRUE AND NEW.is_active = FALSE THEN
+                UPDATE articles SET like_count = GREATEST(like_count - 1, 0) WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'like' AND OLD.is_active = TRUE THEN
+            UPDATE articles SET like_count = GREATEST(like_count - 1, 0) WHERE id = OLD.article_id;
+        END IF;
+
+        -- 收藏夹里已有这篇文章时，用户已经计入 favorite_count
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'favorite' AND NEW.is_active = TRUE THEN
+            IF NOT EXISTS (SELECT 1 FROM user_favorites WHERE user_id = NEW.user_id AND article_id = NEW.article_id) THEN
+                UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'favorite' AND OLD.is_active <> NEW.is_active THEN
+            IF NOT EXISTS (SELECT 1 FROM user_favorites WHERE user_id = NEW.user_id AND article_id = NEW.article_id) THEN
+                IF NEW.is_active THEN
+                    UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+                ELSE
+                    UPDATE articles SET favorite_count = GREATEST(favorite_count - 1, 0) WHERE id = NEW.article_id;
+                END IF;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'favorite' AND OLD.is_active = TRUE THEN
+            IF NOT EXISTS (SELECT 1 FROM user_favorites WHERE user_id = OLD.user_id AND article_id = OLD.article_id) THEN
+                UPDATE articles SET favorite_count = GREATEST(favorite_count - 1, 0) WHERE id = OLD.article_id;
+            END IF;
+        END IF;
+    END IF;
+
+    -- 已有有效的 favorite 互动时，用户已经计入 favorite_count
+    IF TG_TABLE_NAME = 'user_favorites' THEN
+        IF TG_OP = 'INSERT' THEN
+            IF NOT EXISTS (
+                SELECT 1 FROM user_interactions
+                WHERE user_id = NEW.user_id AND article_id = NEW.article_id AND interaction_type = 'favorite' AND is_active = TRUE
+            ) THEN
+                UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' THEN
+            IF NOT EXISTS (
+                SELECT 1 FROM user_interactions
+                WHERE user_id = OLD.user_id AND article_id = OLD.article_id AND interaction_type = 'favorite' AND is_active = TRUE
+            ) THEN
+                UPDATE articles SET favorite_count = GREATEST(favorite_count - 1, 0) WHERE id = OLD.article_id;
+            END IF;
+        END IF;
+    END IF;
+
+    -- 删除评论时级联删除的回复也会逐行触发
+    IF TG_TABLE_NAME = 'comments' THEN
+        IF TG_OP = 'INSERT' THEN
+            UPDATE articles SET comment_count = comment_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'DELETE' THEN
+            UPDATE articles SET comment_count = GREATEST(comment_count - 1, 0) WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS trigger_update_article_stats_favorites ON user_favorites;
+CREATE TRIGGER trigger_update_article_stats_favorites
+    AFTER INSERT OR DELETE ON user_favorites
+    FOR EACH ROW EXECUTE PROCEDURE update_article_stats();
+
+-- 按明细表重算一次，修正之前累积的偏差（与 interaction_queries::recount_article_counters 相同）
+UPDATE articles SET
+    like_count = (
+        SELECT COUNT(*) FROM user_interactions i
+        WHERE i.article_id = articles.id AND i.interaction_type = 'like' AND i.is_active = TRUE
+    ),
+    favorite_count = (
+        SELECT COUNT(*) FROM (
+            SELECT f.user_id FROM user_favorites f WHERE f.article_id = articles.id
+            UNION
+            SELECT i.user_id FROM user_interactions i
+            WHERE i.article_id = articles.id AND i.interaction_type = 'favorite' AND i.is_active = TRUE
+        ) AS favorited
+    ),
+    comment_count = (SELECT COUNT(*) FROM comments c WHERE c.article_id = articles.id);
@@ -0,0 +1,83 @@
+-- 处理旧版 schema.sql 和 create_table_img.sql 建的库；新建的数据库在 0001 中已经是这个结构，这里只补建图片哈希索引。
+
+-- 旧版 news_images 以文章 URL 为主键、每篇文章一张图片，没有 content_hash 列，0001 的 CREATE TABLE IF NOT EXISTS 会跳过它。
+-- 旧图片没有原始地址和内容哈希，无法转换成新结构：整表改名为 news_images_legacy 保留，再建新表，之后的爬取会为已有文章补全图片。
+-- 主键索引和序列要一起改名，否则新表的同名对象会冲突
+CREATE OR REPLACE FUNCTION upgrade_legacy_news_images()
+RETURNS VOID AS $$
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_schema = current_schema() AND table_name = 'news_images'
+    ) AND NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'news_images' AND column_name = 'content_hash'
+    ) THEN
+        ALTER TABLE news_images RENAME TO news_images_legacy;
+        ALTER INDEX IF EXISTS news_images_pkey RENAME TO news_images_legacy_pkey;
+        ALTER SEQUENCE IF EXISTS news_images_id_seq RENAME TO news_images_legacy_id_seq;
+
+        CREATE TABLE news_images (
+            id SERIAL PRIMARY KEY,
+            article_id UUID NOT NULL REFERENCES articles(id) ON DELETE CASCADE,
+            position INTEGER NOT NULL, -- 在文章中的顺序，从 0 开始
+            source_url TEXT NOT NULL, -- 原始图片地址
+            caption TEXT,
+            content_hash CHAR(64) NOT NULL,
+            content_type VARCHAR(100) NOT NULL,
+            width INTEGER,
+            height INTEGER,
+            storage_path TEXT NOT NULL, -- 存储键（相对于 STORAGE_BACKEND 的根）
+            created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            UNIQUE(article_id, position)
+        );
+    END IF;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT upgrade_legacy_news_images();
+DROP FUNCTION upgrade_legacy_news_images();
+
+CREATE INDEX IF NOT EXISTS idx_news_images_content_hash ON news_images(content_hash);
+
+-- ON CONFLICT 依赖的唯一约束：早期建的表上可能没有，CREATE TABLE IF NOT EXISTS 不会补上。
+-- 已有同列（不带条件）的唯一索引时不做改动；否则先删除重复行（每组保留最后写入的一行）再加约束
+CREATE OR REPLACE FUNCTION add_unique_if_missing(p_table TEXT, p_columns TEXT[])
+RETURNS VOID AS $$
+DECLARE
+    v_match TEXT;
+BEGIN
+    IF EXISTS (
+        SELECT 1 FROM pg_index i
+        JOIN pg_class c ON c.oid = i.indrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = current_schema() AND c.relname = p_table
+          AND i.indisunique AND i.indpred IS NULL
+          AND (
+              SELECT array_agg(a.attname::TEXT ORDER BY a.attname::TEXT)
+              FROM pg_attribute a
+              WHERE a.attrelid = c.oid AND a.attnum = ANY(i.indkey)
+          ) = (SELECT array_agg(col ORDER BY col) FROM unnest(p_columns) AS col)
+    ) THEN
+        RETURN;
+    END IF;
+
+    SELECT string_agg('a.' || quote_ident(col) || ' = b.' || quote_ident(col), ' AND ')
+    INTO v_match
+    FROM unnest(p_columns) AS col;
+    EXECUTE 'DELETE FROM ' || quote_ident(p_table) || ' a USING ' || quote_ident(p_table) || ' b'
+        || ' WHERE ' || v_match || ' AND a.ctid < b.ctid';
+
+    EXECUTE 'ALTER TABLE ' || quote_ident(p_table)
+        || ' ADD CONSTRAINT ' || quote_ident(p_table || '_' || array_to_string(p_columns, '_') || '_key')
+        || ' UNIQUE (' || (SELECT string_agg(quote_ident(col), ', ') FROM unnest(p_columns) AS col) || ')';
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT add_unique_if_missing('articles', ARRAY['url']);
+SELECT add_unique_if_missing('user_interactions', ARRAY['user_id', 'article_id', 'interaction_type']);
+SELECT add_unique_if_missing('reading_history', ARRAY['user_id', 'article_id']);
+SELECT add_unique_if_missing('user_favorites', ARRAY['user_id', 'article_id']);
+SELECT add_unique_if_missing('feedback', ARRAY['user_id', 'article_id']);
+
+DROP FUNCTION add_unique_if_missing(TEXT, TEXT[]);
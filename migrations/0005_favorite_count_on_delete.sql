@@ -0,0 +1,77 @@
+-- 删除收藏时按明细表重算 favorite_count，而不是减一。
+-- 两处收藏在同一条语句中被级联删除时（如删除用户），行级触发器执行时两行都已删除，互相检查不到对方，0003 的触发器会多减一次；
+-- 重算的口径与 interaction_queries::recount_article_counters 相同：通过任一入口收藏了文章的用户数
+
+CREATE OR REPLACE FUNCTION count_article_favorites(p_article_id UUID)
+RETURNS INTEGER AS $$
+    SELECT COUNT(*)::INTEGER FROM (
+        SELECT f.user_id FROM user_favorites f WHERE f.article_id = p_article_id
+        UNION
+        SELECT i.user_id FROM user_interactions i
+        WHERE i.article_id = p_article_id AND i.interaction_type = 'favorite' AND i.is_active = TRUE
+    ) AS favorited;
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION update_article_stats()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_TABLE_NAME = 'user_interactions' THEN
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'like' AND NEW.is_active = TRUE THEN
+            UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'like' THEN
+            IF OLD.is_active = FALSE AND NEW.is_active = TRUE THEN
+                UPDATE articles SET like_count = like_count + 1 WHERE id = NEW.article_id;
+            ELSIF OLD.is_active = TRUE AND NEW.is_active = FALSE THEN
+                UPDATE articles SET like_count = GREATEST(like_count - 1, 0) WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'like' AND OLD.is_active = TRUE THEN
+            UPDATE articles SET like_count = GREATEST(like_count - 1, 0) WHERE id = OLD.article_id;
+        END IF;
+
+        -- 收藏夹里已有这篇文章时，用户已经计入 favorite_count
+        IF TG_OP = 'INSERT' AND NEW.interaction_type = 'favorite' AND NEW.is_active = TRUE THEN
+            IF NOT EXISTS (SELECT 1 FROM user_favorites WHERE user_id = NEW.user_id AND article_id = NEW.article_id) THEN
+                UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'UPDATE' AND NEW.interaction_type = 'favorite' AND OLD.is_active <> NEW.is_active THEN
+            IF NOT EXISTS (SELECT 1 FROM user_favorites WHERE user_id = NEW.user_id AND article_id = NEW.article_id) THEN
+                IF NEW.is_active THEN
+                    UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+                ELSE
+                    UPDATE articles SET favorite_count = GREATEST(favorite_count - 1, 0) WHERE id = NEW.article_id;
+                END IF;
+            END IF;
+        ELSIF TG_OP = 'DELETE' AND OLD.interaction_type = 'favorite' AND OLD.is_active = TRUE THEN
+            UPDATE articles SET favorite_count = count_article_favorites(OLD.article_id) WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    -- 已有有效的 favorite 互动时，用户已经计入 favorite_count
+    IF TG_TABLE_NAME = 'user_favorites' THEN
+        IF TG_OP = 'INSERT' THEN
+            IF NOT EXISTS (
+                SELECT 1 FROM user_interactions
+                WHERE user_id = NEW.user_id AND article_id = NEW.article_id AND interaction_type = 'favorite' AND is_active = TRUE
+            ) THEN
+                UPDATE articles SET favorite_count = favorite_count + 1 WHERE id = NEW.article_id;
+            END IF;
+        ELSIF TG_OP = 'DELETE' THEN
+            UPDATE articles SET favorite_count = count_article_favorites(OLD.article_id) WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    -- 删除评论时级联删除的回复也会逐行触发
+    IF TG_TABLE_NAME = 'comments' THEN
+        IF TG_OP = 'INSERT' THEN
+            UPDATE articles SET comment_count = comment_count + 1 WHERE id = NEW.article_id;
+        ELSIF TG_OP = 'DELETE' THEN
+            UPDATE articles SET comment_count = GREATEST(comment_count - 1, 0) WHERE id = OLD.article_id;
+        END IF;
+    END IF;
+
+    RETURN COALESCE(NEW, OLD);
+END;
+$$ LANGUAGE plpgsql;
+
+-- 修正已经多减的计数
+UPDATE articles SET favorite_count = count_article_favorites(id);
@@ -4,6 +4,7 @@ use crate::models::user::{CreateUserSchema, LoginUserSchema, TokenResponse};
 use crate::db::connection::DbPool;
 use crate::errors::ServiceError;
 use crate::errors::ErrorResponse;
+use crate::config::app_config::Config;
 use uuid::Uuid;
 
 // Placeholder for JWT extraction - in a real app, this would be middleware
@@ -42,6 +43,47 @@ impl actix_web::FromRequest for AuthenticatedUser {
     }
 }
 
+// Authenticated user whose username is listed in ADMIN_USERNAMES
+#[derive(Debug)]
+pub struct AdminUser {
+    pub user_id: Uuid,
+    pub username: String,
+}
+
+impl actix_web::FromRequest for AdminUser {
+    type Error = ServiceError;
+    type Future = futures_util::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        let token = req.headers()
+            .get("Authorization")
+            .and_then(|h| h.to_str().ok())
+            .and_then(|s| s.strip_prefix("Bearer "));
+
+        let Some(token) = token else {
+            return futures_util::future::ready(Err(ServiceError::Unauthorized("Missing or invalid Authorization header".to_string())));
+        };
+
+        let claims = match crate::utils::jwt::decode_jwt(token) {
+            Ok(token_data) => token_data.claims,
+            Err(e) => {
+                log::warn!("JWT verification failed: {}", e);
+                return futures_util::future::ready(Err(ServiceError::Unauthorized("Invalid token".to_string())));
+            }
+        };
+
+        let is_admin = req.app_data::<web::Data<Config>>()
+            .map(|config| config.admin_usernames.iter().any(|name| name == &claims.username))
+            .unwrap_or(false);
+
+        if is_admin {
+            futures_util::future::ready(Ok(AdminUser { user_id: claims.sub, username: claims.username }))
+        } else {
+            futures_util::future::ready(Err(ServiceError::Forbidden("Administrator privileges required".to_string())))
+        }
+    }
+}
+
 #[utoipa::path(
     post,
     path = "/api/v1/auth/register",
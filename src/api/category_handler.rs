@@ -0,0 +1,318 @@
+use actix_web::{get, post, put, web, HttpResponse, Responder};
+
+use crate::api::auth_handler::AdminUser;
+use crate::db::category_queries::{
+    find_category_by_id, insert_category, merge_categories, replace_category_aliases, set_manual_labels, update_category,
+};
+use crate::db::connection::DbPool;
+use crate::errors::{ErrorResponse, ServiceError};
+use crate::models::category::{
+    Category, CategoryNode, CreateCategorySchema, MergeCategoryReport, MergeCategorySchema, ReclassifyArticlesResponse,
+    ReclassifyArticlesSchema, UpdateCategorySchema,
+};
+use crate::services::category_service::{load_taxonomy, CategoryTaxonomy};
+
+// 一次批量重新分类最多处理的文章数
+const MAX_RECLASSIFY_ARTICLES: usize = 1000;
+
+fn validate_name(field: &str, value: &str) -> Result<String, ServiceError> {
+    let value = value.trim();
+    if value.is_empty() || value.chars().count() > 50 {
+        return Err(ServiceError::BadRequest(format!("{} must be 1-50 characters", field)));
+    }
+    Ok(value.to_string())
+}
+
+fn validate_slug(slug: &str) -> Result<String, ServiceError> {
+    let slug = validate_name("slug", slug)?;
+    if !slug.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        return Err(ServiceError::BadRequest("slug may only contain a-z, 0-9 and '-'".to_string()));
+    }
+    // 纯数字会与按 id 查找冲突
+    if slug.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ServiceError::BadRequest("slug must not be numeric".to_string()));
+    }
+    Ok(slug)
+}
+
+// 新的名称、slug 或别名不能指向其他类别
+fn ensure_available(taxonomy: &CategoryTaxonomy, names: &[&str], own_id: Option<i32>) -> Result<(), ServiceError> {
+    for name in names {
+        if let Some(existing) = taxonomy.resolve(name).filter(|c| Some(c.id) != own_id) {
+            return Err(ServiceError::Conflict(format!("{:?} already refers to category {}", name, existing.name_zh)));
+        }
+    }
+    Ok(())
+}
+
+fn resolve_category<'a>(taxonomy: &'a CategoryTaxonomy, name: &str) -> Result<&'a Category, ServiceError> {
+    taxonomy
+        .resolve(name)
+        .ok_or_else(|| ServiceError::NotFound(format!("Category {:?} not found", name)))
+}
+
+fn validate_aliases(aliases: &[String]) -> Result<Vec<String>, ServiceError> {
+    let mut validated: Vec<String> = Vec::new();
+    for alias in aliases {
+        let alias = validate_name("alias", alias)?;
+        if !validated.contains(&alias) {
+            validated.push(alias);
+        }
+    }
+    Ok(validated)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/categories",
+    responses(
+        (status = 200, description = "Category tree with aliases; top-level categories first", body = Vec<CategoryNode>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("")]
+pub async fn get_categories_handler(pool: web::Data<DbPool>) -> Result<impl Responder, ServiceError> {
+    let client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    Ok(HttpResponse::Ok().json(taxonomy.tree()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories",
+    request_body = CreateCategorySchema,
+    responses(
+        (status = 201, description = "Category created", body = Category),
+        (status = 400, description = "Invalid category", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 409, description = "Slug, name or alias already used by another category", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("")]
+pub async fn create_category_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    body: web::Json<CreateCategorySchema>,
+) -> Result<impl Responder, ServiceError> {
+    let slug = validate_slug(&body.slug)?;
+    let name_zh = validate_name("name_zh", &body.name_zh)?;
+    let name_en = validate_name("name_en", &body.name_en)?;
+    let aliases = validate_aliases(&body.aliases)?;
+
+    let mut client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let mut names = vec![slug.as_str(), name_zh.as_str(), name_en.as_str()];
+    names.extend(aliases.iter().map(String::as_str));
+    ensure_available(&taxonomy, &names, None)?;
+    let parent_id = match body.parent.as_deref() {
+        Some(parent) => Some(resolve_category(&taxonomy, parent)?.id),
+        None => None,
+    };
+
+    let id = insert_category(
+        &client,
+        &slug,
+        &name_zh,
+        &name_en,
+        parent_id,
+        body.position.unwrap_or(0),
+        body.icon.as_deref(),
+    )
+    .await?;
+    replace_category_aliases(&mut client, id, &aliases).await?;
+    let category = find_category_by_id(&client, id).await?
+        .ok_or_else(|| ServiceError::InternalServerError("Created category disappeared".to_string()))?;
+    Ok(HttpResponse::Created().json(category))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/categories/{category}",
+    params(
+        ("category" = String, Path, description = "Category id, slug, name or alias")
+    ),
+    request_body = UpdateCategorySchema,
+    responses(
+        (status = 200, description = "Category updated; renaming also updates the articles that carry it", body = Category),
+        (status = 400, description = "Invalid category or parent", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse),
+        (status = 409, description = "Name or alias already used by another category", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[put("/{category}")]
+pub async fn update_category_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    path: web::Path<String>,
+    body: web::Json<UpdateCategorySchema>,
+) -> Result<impl Responder, ServiceError> {
+    let mut client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let current = resolve_category(&taxonomy, &path)?;
+
+    let mut updated = current.clone();
+    if let Some(name_zh) = &body.name_zh {
+        updated.name_zh = validate_name("name_zh", name_zh)?;
+    }
+    if let Some(name_en) = &body.name_en {
+        updated.name_en = validate_name("name_en", name_en)?;
+    }
+    if let Some(position) = body.position {
+        updated.position = position;
+    }
+    if let Some(icon) = &body.icon {
+        updated.icon = Some(icon.trim().to_string()).filter(|icon| !icon.is_empty());
+    }
+    match body.parent.as_deref().map(str::trim) {
+        None => {}
+        Some("") => updated.parent_id = None,
+        Some(parent) => {
+            let parent = resolve_category(&taxonomy, parent)?;
+            if taxonomy.is_descendant(parent, current) {
+                return Err(ServiceError::BadRequest(format!(
+                    "{} cannot be moved under itself or one of its subcategories",
+                    current.name_zh
+                )));
+            }
+            updated.parent_id = Some(parent.id);
+        }
+    }
+    let aliases = body.aliases.as_deref().map(validate_aliases).transpose()?;
+
+    let mut names = vec![updated.name_zh.as_str(), updated.name_en.as_str()];
+    if let Some(aliases) = &aliases {
+        names.extend(aliases.iter().map(String::as_str));
+    }
+    ensure_available(&taxonomy, &names, Some(current.id))?;
+
+    update_category(&mut client, &updated, &current.name_zh).await?;
+    if let Some(aliases) = aliases {
+        replace_category_aliases(&mut client, current.id, &aliases).await?;
+    }
+    let category = find_category_by_id(&client, current.id).await?
+        .ok_or_else(|| ServiceError::NotFound("Category not found".to_string()))?;
+    Ok(HttpResponse::Ok().json(category))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/reclassify",
+    request_body = ReclassifyArticlesSchema,
+    responses(
+        (status = 200, description = "Articles reclassified; the new categories are recorded as manual labels for classifier training", body = ReclassifyArticlesResponse),
+        (status = 400, description = "Unknown category or too many articles", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/reclassify")]
+pub async fn reclassify_articles_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    body: web::Json<ReclassifyArticlesSchema>,
+) -> Result<impl Responder, ServiceError> {
+    if body.article_ids.is_empty() || body.article_ids.len() > MAX_RECLASSIFY_ARTICLES {
+        return Err(ServiceError::BadRequest(format!(
+            "article_ids must contain 1-{} articles",
+            MAX_RECLASSIFY_ARTICLES
+        )));
+    }
+
+    let client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let categories = resolve_article_categories(&taxonomy, &body.categories)?;
+
+    let mut updated = 0;
+    let mut not_found = Vec::new();
+    for &article_id in &body.article_ids {
+        if set_manual_labels(&client, article_id, &categories).await? {
+            updated += 1;
+        } else {
+            not_found.push(article_id);
+        }
+    }
+    log::info!("Reclassified {} articles as {}", updated, categories.join(", "));
+    Ok(HttpResponse::Ok().json(ReclassifyArticlesResponse { updated, not_found, categories }))
+}
+
+// 管理员给出的文章类别必须都在类别表中
+pub fn resolve_article_categories(taxonomy: &CategoryTaxonomy, names: &[String]) -> Result<Vec<String>, ServiceError> {
+    let (categories, unknown) = taxonomy.canonicalize(names.iter().map(String::as_str).filter(|name| !name.trim().is_empty()));
+    if !unknown.is_empty() {
+        return Err(ServiceError::BadRequest(format!("Unknown categories: {}", unknown.join(", "))));
+    }
+    if categories.is_empty() {
+        return Err(ServiceError::BadRequest("At least one category is required".to_string()));
+    }
+    Ok(categories)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/categories/{category}/merge",
+    params(
+        ("category" = String, Path, description = "Category to merge away (id, slug, name or alias)")
+    ),
+    request_body = MergeCategorySchema,
+    responses(
+        (status = 200, description = "Articles, labels, subcategories and aliases moved to the target; the merged name becomes an alias", body = MergeCategoryReport),
+        (status = 400, description = "Cannot merge a category into itself or its subcategory", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Category not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/{category}/merge")]
+pub async fn merge_category_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    path: web::Path<String>,
+    body: web::Json<MergeCategorySchema>,
+) -> Result<impl Responder, ServiceError> {
+    let mut client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let from = resolve_category(&taxonomy, &path)?.clone();
+    let into = resolve_category(&taxonomy, &body.into)?.clone();
+    if taxonomy.is_descendant(&into, &from) {
+        return Err(ServiceError::BadRequest(format!(
+            "{} cannot be merged into itself or one of its subcategories",
+            from.name_zh
+        )));
+    }
+
+    let (articles_updated, labels_moved, children_moved) = merge_categories(&mut client, &from, &into).await?;
+    log::info!("Merged category {} into {} ({} articles)", from.name_zh, into.name_zh, articles_updated);
+    Ok(HttpResponse::Ok().json(MergeCategoryReport {
+        from: from.name_zh,
+        into: into.name_zh,
+        articles_updated,
+        labels_moved,
+        children_moved,
+    }))
+}
+
+pub fn init_category_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/categories")
+            .service(get_categories_handler)
+            .service(create_category_handler)
+            .service(reclassify_articles_handler)
+            .service(update_category_handler)
+            .service(merge_category_handler)
+    );
+}
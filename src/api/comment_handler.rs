@@ -1,20 +1,14 @@
 use actix_web::{web, post, get, delete, HttpResponse, Responder};
 use uuid::Uuid;
-use serde::Deserialize;
 
 use crate::db::connection::DbPool;
 use crate::services::comment_service::{post_comment_service, get_comments_for_article_service, delete_comment_service, get_user_comments_service};
 use crate::models::article::{CreateCommentSchema, Comment}; // Ensure Comment model is correctly defined
+use crate::models::pagination::{Page, Pagination, PaginationQuery};
 use crate::errors::ServiceError;
 use crate::errors::ErrorResponse;
 use crate::api::auth_handler::AuthenticatedUser;
 
-#[derive(Deserialize)]
-pub struct CommentQuery {
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
-}
-
 #[utoipa::path(
     post,
     path = "/api/v1/comments",
@@ -46,10 +40,12 @@ pub async fn post_comment_handler(
     get,
     path = "/api/v1/comments/article/{article_id}",
     params(
-        ("article_id" = Uuid, Path, description = "ID of the article to retrieve comments for")
+        ("article_id" = Uuid, Path, description = "ID of the article to retrieve comments for"),
+        PaginationQuery
     ),
     responses(
-        (status = 200, description = "Comments for article retrieved successfully", body = Vec<Comment>),
+        (status = 200, description = "Comments for article retrieved successfully, newest first", body = Page<Comment>),
+        (status = 400, description = "Invalid pagination parameters or cursor", body = ErrorResponse),
         (status = 404, description = "Article not found or no comments found", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
@@ -59,8 +55,9 @@ pub async fn post_comment_handler(
 pub async fn get_comments_for_article_handler(
     pool: web::Data<DbPool>,
     article_id: web::Path<Uuid>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    match get_comments_for_article_service(&pool, article_id.into_inner()).await {
+    match get_comments_for_article_service(&pool, article_id.into_inner(), &pagination).await {
         Ok(comments) => Ok(HttpResponse::Ok().json(comments)),
         Err(e) => Err(e),
     }
@@ -69,12 +66,10 @@ pub async fn get_comments_for_article_handler(
 #[utoipa::path(
     get,
     path = "/api/v1/comments/users/comments",
-    params(
-        ("page" = Option<i64>, Query, description = "Page number"),
-        ("limit" = Option<i64>, Query, description = "Items per page")
-    ),
+    params(PaginationQuery),
     responses(
-        (status = 200, description = "User comments retrieved successfully", body = Vec<Comment>),
+        (status = 200, description = "User comments retrieved successfully, newest first", body = Page<Comment>),
+        (status = 400, description = "Invalid pagination parameters or cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -86,12 +81,9 @@ pub async fn get_comments_for_article_handler(
 pub async fn get_user_comments_handler(
     pool: web::Data<DbPool>,
     auth_user: AuthenticatedUser,
-    query: web::Query<CommentQuery>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    let page = query.page.unwrap_or(1);
-    let limit = query.limit.unwrap_or(20);
-    
-    match get_user_comments_service(&pool, auth_user.user_id, page, limit).await {
+    match get_user_comments_service(&pool, auth_user.user_id, &pagination).await {
         Ok(comments) => Ok(HttpResponse::Ok().json(comments)),
         Err(e) => Err(e),
     }
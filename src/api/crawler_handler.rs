@@ -0,0 +1,207 @@
+use actix_web::{get, post, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+
+use crate::db::connection::DbPool;
+use crate::db::crawl_queries::{get_recent_crawl_runs, get_crawl_source_stats_for_runs, get_zero_yield_sources};
+use crate::models::crawl::{CrawlRunDetail, CrawlRunsReport, CrawlerStatus, TriggerCrawlSchema, UpdateCrawlScheduleSchema};
+use crate::services::crawler_service::{CrawlerController, news_source_names};
+use crate::errors::ServiceError;
+use crate::errors::ErrorResponse;
+use crate::api::auth_handler::AdminUser;
+
+#[derive(Deserialize)]
+pub struct CrawlRunsQuery {
+    pub limit: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/crawler/runs",
+    params(
+        ("limit" = Option<i64>, Query, description = "Number of recent runs to return (default 10, max 100)")
+    ),
+    responses(
+        (status = 200, description = "Recent crawl runs with per-source statistics and zero-yield alerts", body = CrawlRunsReport),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/runs")]
+pub async fn get_crawl_runs_handler(
+    pool: web::Data<DbPool>,
+    query: web::Query<CrawlRunsQuery>,
+    _admin: AdminUser,
+) -> Result<impl Responder, ServiceError> {
+    let limit = query.limit.unwrap_or(10).clamp(1, 100);
+
+    let client = pool.get().await.map_err(|e| {
+        log::error!("Failed to get DB client from pool: {}", e);
+        ServiceError::InternalServerError("Database connection error".to_string())
+    })?;
+
+    let runs = get_recent_crawl_runs(&client, limit).await?;
+    let run_ids: Vec<_> = runs.iter().map(|run| run.id).collect();
+    let mut source_stats = get_crawl_source_stats_for_runs(&client, &run_ids).await?;
+    let zero_yield_sources = get_zero_yield_sources(&client).await?;
+
+    let runs = runs
+        .into_iter()
+        .map(|run| {
+            let (sources, rest) = source_stats.drain(..).partition(|s| s.run_id == run.id);
+            source_stats = rest;
+            CrawlRunDetail { run, sources }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(CrawlRunsReport { runs, zero_yield_sources }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/crawler/status",
+    responses(
+        (status = 200, description = "Current crawler state", body = CrawlerStatus),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/status")]
+pub async fn get_crawler_status_handler(
+    crawler: web::Data<CrawlerController>,
+    _admin: AdminUser,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(crawler.status().await))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/crawler/run",
+    request_body = TriggerCrawlSchema,
+    responses(
+        (status = 202, description = "Crawl started", body = CrawlerStatus),
+        (status = 400, description = "Unknown news source", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 409, description = "A crawl is already in progress", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/run")]
+pub async fn trigger_crawl_handler(
+    crawler: web::Data<CrawlerController>,
+    admin: AdminUser,
+    body: Option<web::Json<TriggerCrawlSchema>>,
+) -> Result<impl Responder, ServiceError> {
+    let source = body.and_then(|b| b.into_inner().source);
+    if let Some(name) = &source {
+        if !news_source_names().contains(name) {
+            return Err(ServiceError::BadRequest(format!("Unknown news source: {}", name)));
+        }
+    }
+
+    if !crawler.trigger_run(source.clone()) {
+        return Err(ServiceError::Conflict("A crawl is already in progress".to_string()));
+    }
+    log::info!("Manual crawl triggered by {} ({}), source: {}", admin.username, admin.user_id, source.as_deref().unwrap_or("all"));
+
+    Ok(HttpResponse::Accepted().json(crawler.status().await))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/crawler/pause",
+    responses(
+        (status = 200, description = "Schedule paused", body = CrawlerStatus),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/pause")]
+pub async fn pause_crawler_handler(
+    crawler: web::Data<CrawlerController>,
+    _admin: AdminUser,
+) -> Result<impl Responder, ServiceError> {
+    crawler.pause().await.map_err(|e| {
+        log::error!("Failed to pause crawler schedule: {}", e);
+        ServiceError::InternalServerError("Failed to pause crawler schedule".to_string())
+    })?;
+    Ok(HttpResponse::Ok().json(crawler.status().await))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/crawler/resume",
+    responses(
+        (status = 200, description = "Schedule resumed", body = CrawlerStatus),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/resume")]
+pub async fn resume_crawler_handler(
+    crawler: web::Data<CrawlerController>,
+    _admin: AdminUser,
+) -> Result<impl Responder, ServiceError> {
+    crawler.resume().await.map_err(|e| {
+        log::error!("Failed to resume crawler schedule: {}", e);
+        ServiceError::InternalServerError("Failed to resume crawler schedule".to_string())
+    })?;
+    Ok(HttpResponse::Ok().json(crawler.status().await))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/crawler/schedule",
+    request_body = UpdateCrawlScheduleSchema,
+    responses(
+        (status = 200, description = "Schedule updated", body = CrawlerStatus),
+        (status = 400, description = "Invalid cron expression", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[put("/schedule")]
+pub async fn update_crawl_schedule_handler(
+    crawler: web::Data<CrawlerController>,
+    _admin: AdminUser,
+    body: web::Json<UpdateCrawlScheduleSchema>,
+) -> Result<impl Responder, ServiceError> {
+    let cron_expression = body.cron_expression.trim();
+    crawler.reschedule(cron_expression).await.map_err(|e| {
+        log::warn!("Rejected crawler cron expression {:?}: {}", cron_expression, e);
+        ServiceError::BadRequest(format!("Invalid cron expression: {}", cron_expression))
+    })?;
+    Ok(HttpResponse::Ok().json(crawler.status().await))
+}
+
+pub fn init_crawler_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/api/v1/crawler")
+            .service(get_crawl_runs_handler)
+            .service(get_crawler_status_handler)
+            .service(trigger_crawl_handler)
+            .service(pause_crawler_handler)
+            .service(resume_crawler_handler)
+            .service(update_crawl_schedule_handler)
+    );
+}
@@ -14,28 +14,20 @@ use crate::models::article::{
     InteractionResponse, ArticleInteractionStats, UserInteraction,
     UserFavorite, ReadingHistory, UserFeedbackHistory
 };
+use crate::models::pagination::{Page, Pagination, PaginationQuery};
 use crate::errors::ServiceError;
 use crate::errors::ErrorResponse;
 use crate::api::auth_handler::AuthenticatedUser;
 
+// 分页参数由 Pagination 提取器读取
 #[derive(Deserialize)]
 pub struct InteractionQuery {
     pub interaction_type: Option<String>,
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
 }
 
 #[derive(Deserialize)]
 pub struct FavoriteQuery {
     pub folder_name: Option<String>,
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
-}
-
-#[derive(Deserialize)]
-pub struct FeedbackHistoryQuery {
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
 }
 
 #[utoipa::path(
@@ -96,11 +88,11 @@ pub async fn get_article_interactions_handler(
     path = "/api/v1/interactions/users/interactions",
     params(
         ("interaction_type" = Option<String>, Query, description = "Filter by interaction type"),
-        ("page" = Option<i64>, Query, description = "Page number"),
-        ("limit" = Option<i64>, Query, description = "Items per page")
+        PaginationQuery
     ),
     responses(
-        (status = 200, description = "User interactions", body = Vec<UserInteraction>),
+        (status = 200, description = "User interactions, most recently updated first", body = Page<UserInteraction>),
+        (status = 400, description = "Invalid pagination parameters or cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -113,11 +105,9 @@ pub async fn get_user_interactions_handler(
     pool: web::Data<DbPool>,
     auth_user: AuthenticatedUser,
     query: web::Query<InteractionQuery>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    let page = query.page.unwrap_or(1);
-    let limit = query.limit.unwrap_or(20);
-    
-    match get_user_interactions_service(&pool, auth_user.user_id, query.interaction_type.clone(), page, limit).await {
+    match get_user_interactions_service(&pool, auth_user.user_id, query.interaction_type.as_deref(), &pagination).await {
         Ok(interactions) => Ok(HttpResponse::Ok().json(interactions)),
         Err(e) => Err(e),
     }
@@ -184,11 +174,11 @@ pub async fn remove_from_favorites_handler(
     path = "/api/v1/interactions/favorites",
     params(
         ("folder_name" = Option<String>, Query, description = "Filter by folder name"),
-        ("page" = Option<i64>, Query, description = "Page number"),
-        ("limit" = Option<i64>, Query, description = "Items per page")
+        PaginationQuery
     ),
     responses(
-        (status = 200, description = "User favorites", body = Vec<UserFavorite>),
+        (status = 200, description = "User favorites, most recent first", body = Page<UserFavorite>),
+        (status = 400, description = "Invalid pagination parameters or cursor", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -201,11 +191,9 @@ pub async fn get_user_favorites_handler(
     pool: web::Data<DbPool>,
     auth_user: AuthenticatedUser,
     query: web::Query<FavoriteQuery>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    let page = query.page.unwrap_or(1);
-    let limit = query.limit.unwrap_or(20);
-    
-    match get_user_favorites_service(&pool, auth_user.user_id, query.folder_name.clone(), page, limit).await {
+    match get_user_favorites_service(&pool, auth_user.user_id, query.folder_name.as_deref(), &pagination).await {
         Ok(favorites) => Ok(HttpResponse::Ok().json(favorites)),
         Err(e) => Err(e),
     }
@@ -240,12 +228,10 @@ pub async fn record_reading_history_handler(
 #[utoipa::path(
     get,
     path = "/api/v1/interactions/users/feedback-history",
-    params(
-        ("page" = Option<i64>, Query, description = "Page number"),
-        ("limit" = Option<i64>, Query, description = "Items per page")
-    ),
+    params(PaginationQuery),
     responses(
-        (status = 200, description = "User feedback history", body = UserFeedbackHistory),
+        (status = 200, description = "User feedback history; page and limit apply to each of the feedback, comment and interaction lists", body = UserFeedbackHistory),
+        (status = 400, description = "Invalid pagination parameters", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
@@ -257,12 +243,12 @@ pub async fn record_reading_history_handler(
 pub async fn get_user_feedback_history_handler(
     pool: web::Data<DbPool>,
     auth_user: AuthenticatedUser,
-    query: web::Query<FeedbackHistoryQuery>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    let page = query.page.unwrap_or(1);
-    let limit = query.limit.unwrap_or(20);
-    
-    match get_user_feedback_history_service(&pool, auth_user.user_id, page, limit).await {
+    // 反馈历史由三个列表拼成，只支持按页码翻页
+    pagination.reject_cursor()?;
+
+    match get_user_feedback_history_service(&pool, auth_user.user_id, pagination.page(), pagination.limit()).await {
         Ok(history) => Ok(HttpResponse::Ok().json(history)),
         Err(e) => Err(e),
     }
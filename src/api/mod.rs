@@ -1,4 +1,5 @@
 pub mod auth_handler;
+pub mod pagination;
 pub mod user_handler;
 pub mod news_handler;
 pub mod recommendation_handler;
@@ -6,4 +7,6 @@ pub mod feedback_handler;
 pub mod comment_handler;
 pub mod interaction_handler;
 pub mod system_info_handler;
+pub mod crawler_handler;
+pub mod category_handler;
 pub mod viz;
\ No newline at end of file
@@ -1,46 +1,266 @@
-use actix_web::{web, get, HttpResponse, Responder};
+use actix_files::HttpRange;
+use actix_web::http::header;
+use actix_web::{web, delete, get, post, put, HttpRequest, HttpResponse, Responder};
+use chrono::{DateTime, NaiveDate, Utc};
 use uuid::Uuid;
 use crate::db::connection::DbPool;
 //use crate::services::recommendation_service; // Assuming articles might be fetched via recommendation or a dedicated article service
 use crate::errors::ServiceError;
 use crate::errors::ErrorResponse;
-use crate::models::article::Article;
+use crate::api::auth_handler::AdminUser;
+use crate::api::category_handler::resolve_article_categories;
+use crate::services::category_service::load_taxonomy;
+use crate::models::article::{Article, ArticleFields, ArticleImageResponse, ArticleListItem, ArticleRevision, ArticleSort, ArticleSummary, ManagedArticle, RecrawlResponse, SearchHit, SearchResponse, SearchSuggestion, NewsImage, ThumbnailUrl, UpdateArticleCategoriesSchema, UpdateArticleSchema};
+use crate::db::article_queries::{count_total_articles, find_managed_article, query_article_summaries, query_articles, ArticleCursor, ArticleFilter};
+use crate::db::search_queries::{count_search_results, log_search_query, search_articles};
+use crate::db::category_queries::{get_source_labeled_examples, set_manual_labels};
+use crate::services::classifier_service::{get_classifier, measure_agreement, Classifier, ClassifierAgreementReport};
+use crate::db::image_queries::{find_first_image_by_article_url, find_image_by_id, get_article_images};
+use crate::services::image_service::{self, FitMode, ImageVariant, VariantRequest, MAX_VARIANT_DIMENSION, THUMBNAIL_WIDTHS};
+use crate::services::storage::ImageStorage;
+use crate::services::summary_service::summarize;
+use crate::services::article_service::{
+    delete_article_service, edit_article_service, get_article_revisions_service, recrawl_article_service, set_article_hidden_service,
+};
+use crate::services::search_service::{highlight, normalize_query, query_terms, snippet};
+use crate::services::suggest_service::{get_suggestions, MAX_SUGGESTIONS};
+use crate::models::pagination::{Page, Pagination, PaginationQuery};
 
-use std::path::PathBuf;
+// 文章列表的筛选和排序参数；分页参数由 Pagination 提取器读取
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct ArticleListQuery {
+    #[param(inline)]
+    pub fields: Option<ArticleFields>, // 默认 summary，只返回精简字段
+    pub category: Option<String>, // 类别的 id、slug、名称或别名，包含其子类别
+    pub source_name: Option<String>,
+    pub published_from: Option<String>, // RFC 3339 时间或 YYYY-MM-DD，含当天
+    pub published_to: Option<String>, // RFC 3339 时间或 YYYY-MM-DD，含当天
+    pub has_image: Option<bool>,
+    #[param(inline)]
+    pub sort: Option<ArticleSort>, // 默认 newest
+}
+
+// 解析日期参数；只给出日期时，起始取当天零点，结束取当天最后一刻（UTC）
+fn parse_date_param(name: &str, value: &str, end_of_day: bool) -> Result<DateTime<Utc>, ServiceError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        ServiceError::BadRequest(format!("{} must be an RFC 3339 timestamp or a YYYY-MM-DD date", name))
+    })?;
+    let time = if end_of_day {
+        date.and_hms_micro_opt(23, 59, 59, 999_999)
+    } else {
+        date.and_hms_opt(0, 0, 0)
+    };
+    Ok(time.expect("valid time of day").and_utc())
+}
+
+impl ArticleListQuery {
+    async fn to_filter(&self, client: &tokio_postgres::Client) -> Result<ArticleFilter, ServiceError> {
+        let categories = match self.category.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            Some(name) => {
+                let taxonomy = load_taxonomy(client).await?;
+                let category = taxonomy
+                    .resolve(name)
+                    .ok_or_else(|| ServiceError::BadRequest(format!("Unknown category: {}", name)))?;
+                Some(taxonomy.descendant_names(category))
+            }
+            None => None,
+        };
+        let published_from = self
+            .published_from
+            .as_deref()
+            .map(|value| parse_date_param("published_from", value, false))
+            .transpose()?;
+        let published_to = self
+            .published_to
+            .as_deref()
+            .map(|value| parse_date_param("published_to", value, true))
+            .transpose()?;
+        if let (Some(from), Some(to)) = (published_from, published_to) {
+            if from > to {
+                return Err(ServiceError::BadRequest("published_from must not be after published_to".to_string()));
+            }
+        }
+
+        Ok(ArticleFilter {
+            categories,
+            source_name: self.source_name.clone().filter(|s| !s.trim().is_empty()),
+            published_from,
+            published_to,
+            has_image: self.has_image,
+            tag: None,
+            sort: self.sort.unwrap_or_default(),
+        })
+    }
+}
+
+// 查询一页文章，只有 fields=full 时才读取正文。游标记录了生成它时的排序方式，换了排序就不能再用
+async fn fetch_article_page(
+    client: &tokio_postgres::Client,
+    filter: &ArticleFilter,
+    fields: ArticleFields,
+    pagination: &Pagination,
+) -> Result<Page<ArticleListItem>, ServiceError> {
+    let after: Option<ArticleCursor> = pagination.cursor()?;
+    if after.as_ref().is_some_and(|cursor| cursor.sort != filter.sort) {
+        return Err(ServiceError::BadRequest("Cursor was issued for a different sort order".to_string()));
+    }
+    let total = count_total_articles(client, filter).await?;
+    let (offset, limit) = (pagination.offset(), pagination.fetch_limit());
+    let page = match fields {
+        ArticleFields::Summary => {
+            let articles = query_article_summaries(client, filter, after.as_ref(), offset, limit).await?;
+            pagination
+                .page_of(articles, total, |article| ArticleCursor::for_summary(article, filter.sort))
+                .map(ArticleListItem::Summary)
+        }
+        ArticleFields::Full => {
+            let articles = query_articles(client, filter, after.as_ref(), offset, limit).await?;
+            pagination
+                .page_of(articles, total, |article| ArticleCursor::for_article(article, filter.sort))
+                .map(|article| article_list_item(article, ArticleFields::Full))
+        }
+    };
+    Ok(page)
+}
+
+async fn list_articles(pool: &DbPool, query: &ArticleListQuery, pagination: &Pagination) -> Result<Page<ArticleListItem>, ServiceError> {
+    let client = pool.get().await?;
+    let filter = query.to_filter(&client).await?;
+    fetch_article_page(&client, &filter, query.fields.unwrap_or_default(), pagination).await
+}
 
 #[utoipa::path(
     get,
     path = "/api/v1/news/articles",
-    params(
-        ("page" = Option<i64>, Query, description = "Page number for pagination"),
-        ("limit" = Option<i64>, Query, description = "Number of items per page")
-    ),
+    params(ArticleListQuery, PaginationQuery),
     responses(
-        (status = 200, description = "List of articles retrieved successfully", body = Vec<Article>),
+        (status = 200, description = "Filtered page of articles; items are ArticleSummary unless fields=full", body = Page<ArticleListItem>),
+        (status = 400, description = "Unknown category, malformed date, invalid pagination parameters or cursor", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
 #[get("/articles")]
 pub async fn get_articles_handler(
     pool: web::Data<DbPool>,
-    query: web::Query<PaginationParams>,
+    query: web::Query<ArticleListQuery>,
+    pagination: Pagination,
 ) -> Result<impl Responder, ServiceError> {
-    let client = pool.get().await.map_err(|_| ServiceError::InternalServerError("DB Pool error".to_string()))?;
-    let raw_client: &tokio_postgres::Client = &*client; // ✅ 解引用转换为 tokio_postgres::Client
-
-    match crate::db::article_queries::get_recent_articles(
-        raw_client,
-        Some(query.limit.unwrap_or(10)),
-        Some(query.page.unwrap_or(1) - 1),
-    ).await {
-        Ok(articles) => Ok(HttpResponse::Ok().json(articles)),
-        Err(e) => {
-            log::error!("Failed to fetch articles: {}", e);
-            Err(ServiceError::InternalServerError("Could not fetch articles".to_string()))
+    Ok(HttpResponse::Ok().json(list_articles(&pool, &query, &pagination).await?))
+}
+
+// 全文检索参数；筛选条件与文章列表相同，只支持按页码翻页
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct SearchQuery {
+    pub q: String,
+    pub category: Option<String>, // 类别的 id、slug、名称或别名，包含其子类别
+    pub source_name: Option<String>,
+    pub published_from: Option<String>, // RFC 3339 时间或 YYYY-MM-DD，含当天
+    pub published_to: Option<String>, // RFC 3339 时间或 YYYY-MM-DD，含当天
+    pub has_image: Option<bool>,
+}
+
+impl SearchQuery {
+    fn list_query(&self) -> ArticleListQuery {
+        ArticleListQuery {
+            fields: None,
+            category: self.category.clone(),
+            source_name: self.source_name.clone(),
+            published_from: self.published_from.clone(),
+            published_to: self.published_to.clone(),
+            has_image: self.has_image,
+            sort: None,
         }
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/search",
+    params(SearchQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "Articles ranked by relevance (BM25), with highlighted titles and snippets; cursor is not supported", body = SearchResponse),
+        (status = 400, description = "Query has no searchable words, unknown category, malformed date or invalid pagination parameters", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("/search")]
+pub async fn search_articles_handler(
+    pool: web::Data<DbPool>,
+    query: web::Query<SearchQuery>,
+    pagination: Pagination,
+) -> Result<impl Responder, ServiceError> {
+    // 按相关度排序没有稳定的键集顺序，只支持按页码翻页
+    pagination.reject_cursor()?;
+    let terms = query_terms(&query.q);
+    if terms.is_empty() {
+        return Err(ServiceError::BadRequest("Search query has no searchable words".to_string()));
+    }
+
+    let client = pool.get().await?;
+    let filter = query.list_query().to_filter(&client).await?;
+    let hits = search_articles(&client, &terms, &filter, pagination.offset(), pagination.fetch_limit()).await?;
+    let total = count_search_results(&client, &terms, &filter).await?;
+    // 只记录第一页，翻页不算新的搜索
+    if pagination.page() == 1 {
+        let result_count = i32::try_from(total).unwrap_or(i32::MAX);
+        if let Err(e) = log_search_query(&client, &normalize_query(&query.q), result_count).await {
+            log::error!("Failed to log search query: {}", e);
+        }
+    }
+
+    let results = pagination.page_without_cursor(hits, total).map(|(article, score)| SearchHit {
+        title_highlight: highlight(&article.title, &terms),
+        snippet: snippet(&article.content, &terms),
+        article: ArticleSummary::from(with_summary(article)),
+        score,
+    });
+    Ok(HttpResponse::Ok().json(SearchResponse { query: query.q.clone(), terms, results }))
+}
+
+const DEFAULT_SUGGESTIONS: usize = 10;
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct SuggestQuery {
+    pub prefix: String,
+    pub limit: Option<usize>, // 默认 10，最多 20
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/search/suggest",
+    params(SuggestQuery),
+    responses(
+        (status = 200, description = "Completions for the prefix drawn from article titles, article tags and popular searches, best first", body = Vec<SearchSuggestion>)
+    )
+)]
+#[get("/search/suggest")]
+pub async fn search_suggest_handler(query: web::Query<SuggestQuery>) -> Result<impl Responder, ServiceError> {
+    let limit = query.limit.unwrap_or(DEFAULT_SUGGESTIONS).clamp(1, MAX_SUGGESTIONS);
+    Ok(HttpResponse::Ok().json(get_suggestions().complete(&query.prefix, limit)))
+}
+
+// 与 /api/v1/news/articles 相同，前端按类别筛选时使用
+#[utoipa::path(
+    get,
+    path = "/api/v1/articles",
+    params(ArticleListQuery, PaginationQuery),
+    responses(
+        (status = 200, description = "Same as /api/v1/news/articles", body = Page<ArticleListItem>),
+        (status = 400, description = "Unknown category, malformed date, invalid pagination parameters or cursor", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("/api/v1/articles")]
+pub async fn list_articles_handler(
+    pool: web::Data<DbPool>,
+    query: web::Query<ArticleListQuery>,
+    pagination: Pagination,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(list_articles(&pool, &query, &pagination).await?))
+}
 
 #[utoipa::path(
     get,
@@ -50,7 +270,7 @@ pub async fn get_articles_handler(
     ),
     responses(
         (status = 200, description = "Article retrieved successfully", body = Article),
-        (status = 404, description = "Article not found", body = ErrorResponse),
+        (status = 404, description = "Article not found, hidden or deleted", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     )
 )]
@@ -72,17 +292,376 @@ pub async fn get_article_by_id_handler(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/v1/news/articles/{article_id}/categories",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to label")
+    ),
+    request_body = UpdateArticleCategoriesSchema,
+    responses(
+        (status = 200, description = "Categories updated; they are used as training data for the classifier", body = Article),
+        (status = 400, description = "No categories given, or a category is not in the taxonomy", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[put("/articles/{article_id}/categories")]
+pub async fn update_article_categories_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+    body: web::Json<UpdateArticleCategoriesSchema>,
+) -> Result<impl Responder, ServiceError> {
+    let article_id = article_id.into_inner();
+    let client = pool.get().await.map_err(|_| ServiceError::InternalServerError("DB Pool error".to_string()))?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let categories = resolve_article_categories(&taxonomy, &body.categories)?;
+    if !set_manual_labels(&client, article_id, &categories).await? {
+        return Err(ServiceError::NotFound("Article not found".to_string()));
+    }
+    // 隐藏的文章也可以标注
+    match find_managed_article(&client, article_id).await? {
+        Some(managed) if !managed.is_deleted() => Ok(HttpResponse::Ok().json(managed.article)),
+        _ => Err(ServiceError::NotFound("Article not found".to_string())),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/v1/news/articles/{article_id}",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to edit")
+    ),
+    request_body = UpdateArticleSchema,
+    responses(
+        (status = 200, description = "Article updated; if the title or content changed, the previous version was added to the revision history", body = ManagedArticle),
+        (status = 400, description = "Nothing to update or empty title", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found or deleted", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[put("/articles/{article_id}")]
+pub async fn update_article_handler(
+    pool: web::Data<DbPool>,
+    admin: AdminUser,
+    article_id: web::Path<Uuid>,
+    body: web::Json<UpdateArticleSchema>,
+) -> Result<impl Responder, ServiceError> {
+    let article = edit_article_service(&pool, article_id.into_inner(), &body, admin.user_id).await?;
+    Ok(HttpResponse::Ok().json(article))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/news/articles/{article_id}",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to delete")
+    ),
+    responses(
+        (status = 204, description = "Article soft-deleted; it is kept in the database but no longer served"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found or already deleted", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/articles/{article_id}")]
+pub async fn delete_article_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    delete_article_service(&pool, article_id.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/news/articles/{article_id}/hide",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to hide")
+    ),
+    responses(
+        (status = 200, description = "Article hidden from lists, search, recommendations and the detail page", body = ManagedArticle),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found or deleted", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/articles/{article_id}/hide")]
+pub async fn hide_article_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(set_article_hidden_service(&pool, article_id.into_inner(), true).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/news/articles/{article_id}/unhide",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to show again")
+    ),
+    responses(
+        (status = 200, description = "Article visible again", body = ManagedArticle),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found or deleted", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/articles/{article_id}/unhide")]
+pub async fn unhide_article_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(set_article_hidden_service(&pool, article_id.into_inner(), false).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/articles/{article_id}/revisions",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article")
+    ),
+    responses(
+        (status = 200, description = "Previous versions of the article, most recently replaced first", body = Vec<ArticleRevision>),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/articles/{article_id}/revisions")]
+pub async fn get_article_revisions_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(get_article_revisions_service(&pool, article_id.into_inner()).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/news/articles/{article_id}/recrawl",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article to fetch again from its URL")
+    ),
+    responses(
+        (status = 200, description = "Article fetched again; changed is true if the title or content was updated", body = RecrawlResponse),
+        (status = 400, description = "No news source is configured for the article", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse),
+        (status = 404, description = "Article not found or deleted", body = ErrorResponse),
+        (status = 500, description = "The page could not be fetched or parsed", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[post("/articles/{article_id}/recrawl")]
+pub async fn recrawl_article_handler(
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn ImageStorage>,
+    _admin: AdminUser,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    Ok(HttpResponse::Ok().json(recrawl_article_service(&pool, storage.get_ref(), article_id.into_inner()).await?))
+}
+
+const DEFAULT_AGREEMENT_SAMPLE: i64 = 1000;
+const MAX_AGREEMENT_SAMPLE: i64 = 5000;
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct ClassifierAgreementQuery {
+    pub limit: Option<i64>, // 参与比较的最新文章数，默认 1000，最多 5000
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/classifier/agreement",
+    params(ClassifierAgreementQuery),
+    responses(
+        (status = 200, description = "How often the classifiers disagree with the category given by the source section", body = ClassifierAgreementReport),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Forbidden (not an administrator)", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[get("/classifier/agreement")]
+pub async fn get_classifier_agreement_handler(
+    pool: web::Data<DbPool>,
+    _admin: AdminUser,
+    query: web::Query<ClassifierAgreementQuery>,
+) -> Result<impl Responder, ServiceError> {
+    let limit = query.limit.unwrap_or(DEFAULT_AGREEMENT_SAMPLE).clamp(1, MAX_AGREEMENT_SAMPLE);
+    let examples = {
+        let client = pool.get().await.map_err(|_| ServiceError::InternalServerError("DB Pool error".to_string()))?;
+        get_source_labeled_examples(&client, Some(limit)).await?
+    };
+
+    // 分词和打分较耗 CPU，放到阻塞线程池
+    let report = web::block(move || {
+        let classifier = get_classifier();
+        let trained_model = matches!(*classifier, Classifier::Trained(_))
+            .then(|| measure_agreement(&classifier, &examples));
+        ClassifierAgreementReport {
+            keyword_rules: measure_agreement(&Classifier::Keywords, &examples),
+            trained_model,
+        }
+    })
+    .await
+    .map_err(|_| ServiceError::InternalServerError("Classifier evaluation failed".to_string()))?;
+    Ok(HttpResponse::Ok().json(report))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/tags/{tag}",
+    params(
+        ("tag" = String, Path, description = "Keyword or entity name (person, organization, place); English tags are matched case-insensitively"),
+        ArticleFieldsQuery,
+        PaginationQuery
+    ),
+    responses(
+        (status = 200, description = "Articles carrying the tag, newest first; items are ArticleSummary unless fields=full", body = Page<ArticleListItem>),
+        (status = 400, description = "Empty tag, invalid pagination parameters or cursor", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("/tags/{tag}")]
+pub async fn get_articles_by_tag_handler(
+    pool: web::Data<DbPool>,
+    path: web::Path<String>,
+    query: web::Query<ArticleFieldsQuery>,
+    pagination: Pagination,
+) -> Result<impl Responder, ServiceError> {
+    let tag = path.into_inner().trim().to_string();
+    if tag.is_empty() {
+        return Err(ServiceError::BadRequest("Tag must not be empty".to_string()));
+    }
+    let filter = ArticleFilter { tag: Some(tag), ..ArticleFilter::default() };
+
+    let client = pool.get().await?;
+    let page = fetch_article_page(&client, &filter, query.fields.unwrap_or_default(), &pagination).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
 // #[derive(serde::Deserialize, ToSchema)]
 #[derive(serde::Deserialize)]
 pub struct ImageQuery {
     url: String,
 }
 
+// If-None-Match 可能是逗号分隔的多个 ETag，也可能是 *
+fn etag_matches(header_value: &str, etag: &str) -> bool {
+    header_value
+        .split(',')
+        .map(|tag| tag.trim().trim_start_matches("W/"))
+        .any(|tag| tag == "*" || tag == etag)
+}
+
+fn image_file_response(req: &HttpRequest, variant: ImageVariant) -> HttpResponse {
+    let header_str = |name: header::HeaderName| req.headers().get(name).and_then(|v| v.to_str().ok());
+
+    let not_modified = header_str(header::IF_NONE_MATCH).is_some_and(|v| etag_matches(v, &variant.etag));
+    let mut response = if not_modified { HttpResponse::NotModified() } else { HttpResponse::Ok() };
+    response
+        .insert_header((header::ETAG, variant.etag.clone()))
+        .insert_header((header::CACHE_CONTROL, "public, max-age=86400")) // 缓存1天
+        .insert_header((header::VARY, "Accept")) // 返回格式取决于 Accept
+        .insert_header((header::ACCEPT_RANGES, "bytes"))
+        // 直接打开 SVG 时也不允许执行脚本
+        .insert_header((header::CONTENT_SECURITY_POLICY, "default-src 'none'; style-src 'unsafe-inline'; sandbox"))
+        .insert_header((header::X_CONTENT_TYPE_OPTIONS, "nosniff"));
+    if not_modified {
+        return response.finish();
+    }
+
+    let data = variant.bytes;
+    response.content_type(variant.content_type);
+
+    // If-Range 与当前 ETag 不一致时忽略 Range，返回完整内容
+    let range = header_str(header::RANGE)
+        .filter(|_| header_str(header::IF_RANGE).is_none_or(|v| v == variant.etag));
+    let Some(range) = range else {
+        return response.body(data);
+    };
+
+    let total = data.len() as u64;
+    match HttpRange::parse(range, total) {
+        // 只支持单个区间，多个区间时返回完整内容
+        Ok(ranges) if ranges.len() == 1 => {
+            let HttpRange { start, length } = ranges[0];
+            let end = start + length;
+            response
+                .status(actix_web::http::StatusCode::PARTIAL_CONTENT)
+                .insert_header((header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end - 1, total)))
+                .body(data[start as usize..end as usize].to_vec())
+        }
+        Ok(_) => response.body(data),
+        Err(_) => HttpResponse::RangeNotSatisfiable()
+            .insert_header((header::CONTENT_RANGE, format!("bytes */{}", total)))
+            .finish(),
+    }
+}
+
+async fn render_image(
+    req: &HttpRequest,
+    storage: &dyn ImageStorage,
+    image: &NewsImage,
+    request: &VariantRequest,
+) -> Result<HttpResponse, ServiceError> {
+    let accept = req.headers().get(header::ACCEPT).and_then(|v| v.to_str().ok());
+    let variant = image_service::render_variant(
+        storage,
+        &image.content_hash,
+        &image.storage_path,
+        &image.content_type,
+        request,
+        accept,
+    )
+    .await
+    .map_err(|e| {
+        log::error!("Failed to read image {} from {}: {}", image.id, storage.describe(), e);
+        ServiceError::InternalServerError("Could not read image".to_string())
+    })?;
+    match variant {
+        Some(variant) => Ok(image_file_response(req, variant)),
+        None => {
+            log::warn!("Image file for image {} is missing: {}", image.id, image.storage_path);
+            Err(ServiceError::NotFound("Image not found".to_string()))
+        }
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/api/v1/images",
     params(
-        ("url" = String, Query, description = "URL of the image to retrieve")
+        ("url" = String, Query, description = "URL of the article whose first image should be returned. Kept for old clients; use /api/v1/images/{image_id}")
     ),
     responses(
         (status = 200, description = "Image retrieved successfully", content_type = "image/*"),
@@ -92,45 +671,147 @@ pub struct ImageQuery {
 )]
 #[get("/images")]
 pub async fn get_image_handler(
+    req: HttpRequest,
     pool: web::Data<DbPool>,
+    storage: web::Data<dyn ImageStorage>,
     query: web::Query<ImageQuery>,
 ) -> Result<impl actix_web::Responder, ServiceError> {
-    let client = pool.get().await.map_err(|_| 
-        ServiceError::InternalServerError("DB Pool error".to_string())
-    )?;
-    //let raw_client: &tokio_postgres::Client = &*client;
-    // let raw_client = pool.get().await.map_err(|e| anyhow::anyhow!("DB Pool error: {}", e))?;
-    log::info!("Fetching image start: {}", query.url);
-    let raw_client = pool.get().await.map_err(|_| {
-    ServiceError::InternalServerError("DB Pool error".to_string())})?;
-
     log::info!("Fetching image for URL: {}", query.url);
-    match crate::db::article_queries::load_image_from_db(&raw_client, &query.url).await {
-        Ok(Some(image)) => {
-            // println!(
-            //         "Image loaded - Type: {}, Size: {} bytes", 
-            //         &image.content_type, 
-            // );
-            let filepath=PathBuf::from(&image.file_path);
-            match tokio::fs::read(&filepath).await {
-                Ok(data) => {
-                    Ok(HttpResponse::Ok()
-                        .content_type(image.clone().content_type)
-                        .insert_header(("Cache-Control", "public, max-age=86400")) // 缓存1天
-                        .body(data))},
-                Err(_) => Err(ServiceError::NotFound("Image not found".to_string())),
-            }  
+    let image = {
+        let client = pool.get().await.map_err(|_|
+            ServiceError::InternalServerError("DB Pool error".to_string())
+        )?;
+        find_first_image_by_article_url(&client, &query.url).await?
+    };
+    match image {
+        Some(image) => render_image(&req, storage.as_ref(), &image, &VariantRequest::default()).await,
+        None => Err(ServiceError::NotFound("Image not found".to_string())),
+    }
+}
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct ImageVariantQuery {
+    pub w: Option<u32>, // 目标宽度（像素）
+    pub h: Option<u32>, // 目标高度（像素）
+    pub fit: Option<FitMode>, // 同时给出 w 和 h 时的缩放方式，默认 contain
+}
+
+impl ImageVariantQuery {
+    fn to_request(&self) -> Result<VariantRequest, ServiceError> {
+        for (name, value) in [("w", self.w), ("h", self.h)] {
+            if value.is_some_and(|v| v == 0 || v > MAX_VARIANT_DIMENSION) {
+                return Err(ServiceError::BadRequest(format!(
+                    "{} must be between 1 and {}",
+                    name, MAX_VARIANT_DIMENSION
+                )));
+            }
         }
-        Ok(None) => Err(ServiceError::NotFound("Image not found".to_string())),
-        Err(e) => Err(ServiceError::DatabaseError(e)),
+        Ok(VariantRequest { width: self.w, height: self.h, fit: self.fit.unwrap_or_default() })
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/images/{image_id}",
+    params(
+        ("image_id" = i32, Path, description = "ID of the image"),
+        ImageVariantQuery
+    ),
+    responses(
+        (status = 200, description = "Image retrieved successfully; format follows the Accept header (AVIF, WebP, JPEG)", content_type = "image/*"),
+        (status = 206, description = "Requested byte range of the image", content_type = "image/*"),
+        (status = 304, description = "Image matches If-None-Match"),
+        (status = 400, description = "Invalid width or height", body = ErrorResponse),
+        (status = 404, description = "Image not found", body = ErrorResponse),
+        (status = 416, description = "Requested range is not satisfiable"),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("/images/{image_id}")]
+pub async fn get_image_by_id_handler(
+    req: HttpRequest,
+    pool: web::Data<DbPool>,
+    storage: web::Data<dyn ImageStorage>,
+    image_id: web::Path<i32>,
+    query: web::Query<ImageVariantQuery>,
+) -> Result<impl actix_web::Responder, ServiceError> {
+    let request = query.to_request()?;
+
+    // 查完即归还连接，缩放和编码可能较慢
+    let image = {
+        let client = pool.get().await.map_err(|_| ServiceError::InternalServerError("DB Pool error".to_string()))?;
+        find_image_by_id(&client, image_id.into_inner()).await?
+    };
+    match image {
+        Some(image) => render_image(&req, storage.as_ref(), &image, &request).await,
+        None => Err(ServiceError::NotFound("Image not found".to_string())),
     }
 }
 
-// Helper struct for pagination query parameters (used in get_articles_handler)
+fn image_response(image: NewsImage) -> ArticleImageResponse {
+    let url = format!("/api/v1/images/{}", image.id);
+    // 只列出实际生成过的缩略图（原图比缩略图宽时才会生成）
+    let thumbnails = THUMBNAIL_WIDTHS
+        .iter()
+        .filter(|&&w| image.width.is_some_and(|iw| (w as i32) < iw))
+        .map(|&width| ThumbnailUrl { width, url: format!("{}?w={}", url, width) })
+        .collect();
+    ArticleImageResponse {
+        id: image.id,
+        position: image.position,
+        caption: image.caption,
+        content_type: image.content_type,
+        width: image.width,
+        height: image.height,
+        url,
+        thumbnails,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/news/articles/{article_id}/images",
+    params(
+        ("article_id" = Uuid, Path, description = "ID of the article")
+    ),
+    responses(
+        (status = 200, description = "Images of the article in page order", body = Vec<ArticleImageResponse>),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    )
+)]
+#[get("/articles/{article_id}/images")]
+pub async fn get_article_images_handler(
+    pool: web::Data<DbPool>,
+    article_id: web::Path<Uuid>,
+) -> Result<impl Responder, ServiceError> {
+    let client = pool.get().await.map_err(|_| ServiceError::InternalServerError("DB Pool error".to_string()))?;
+    let images = get_article_images(&client, article_id.into_inner()).await?;
+    let images: Vec<ArticleImageResponse> = images.into_iter().map(image_response).collect();
+    Ok(HttpResponse::Ok().json(images))
+}
+
+// 列表接口返回的字段（标签文章列表、推荐列表）
 #[derive(serde::Deserialize, utoipa::IntoParams)]
-pub struct PaginationParams {
-    pub page: Option<i64>,
-    pub limit: Option<i64>,
+#[into_params(parameter_in = Query)]
+pub struct ArticleFieldsQuery {
+    #[param(inline)]
+    pub fields: Option<ArticleFields>, // 默认 summary，只返回精简字段；full 返回完整正文和 content_html
+}
+
+// 还没有摘要的旧文章当场生成
+fn with_summary(mut article: Article) -> Article {
+    if article.summary.is_none() {
+        article.summary = Some(summarize(&article.content));
+    }
+    article
+}
+
+// 把已经读出的完整文章转成列表项
+pub fn article_list_item(article: Article, fields: ArticleFields) -> ArticleListItem {
+    match fields {
+        ArticleFields::Summary => ArticleListItem::Summary(ArticleSummary::from(with_summary(article))),
+        ArticleFields::Full => ArticleListItem::Full(with_summary(article)),
+    }
 }
 
 //pub fn init_news_routes(cfg: &mut web::ServiceConfig) {
@@ -146,11 +827,24 @@ pub fn init_news_routes(cfg: &mut web::ServiceConfig) {
         web::scope("/api/v1/news")
             .service(get_articles_handler)
             .service(get_article_by_id_handler)
+            .service(get_article_images_handler)
+            .service(update_article_categories_handler)
+            .service(update_article_handler)
+            .service(delete_article_handler)
+            .service(hide_article_handler)
+            .service(unhide_article_handler)
+            .service(get_article_revisions_handler)
+            .service(recrawl_article_handler)
+            .service(get_classifier_agreement_handler)
+            .service(get_articles_by_tag_handler)
+            .service(search_articles_handler)
+            .service(search_suggest_handler)
     );
+    cfg.service(list_articles_handler);
 }
 
 pub fn init_news_img_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(get_image_handler)
-        ;
+        .service(get_image_by_id_handler);
 }
 
@@ -0,0 +1,17 @@
+use actix_web::{dev::Payload, web, FromRequest, HttpRequest};
+use futures_util::future::{ready, Ready};
+
+use crate::errors::ServiceError;
+use crate::models::pagination::{Pagination, PaginationQuery};
+
+// 从查询串中读取 page、limit、cursor 并校验，超出范围时返回 400
+impl FromRequest for Pagination {
+    type Error = ServiceError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let query = web::Query::<PaginationQuery>::from_query(req.query_string())
+            .map_err(|e| ServiceError::BadRequest(format!("Invalid pagination parameters: {}", e)));
+        ready(query.and_then(|query| query.into_inner().validate()))
+    }
+}
@@ -4,16 +4,19 @@ use actix_web::{get, web, HttpResponse, Responder};
 use rust_stemmers::{Algorithm, Stemmer};
 use std::collections::{HashMap, HashSet};
 
+use crate::db::article_queries::ArticleFilter;
 use crate::db::connection::DbPool;
-use crate::models::article::Article;
+use crate::models::article::{Article, ArticleListItem};
 use crate::api::auth_handler::AuthenticatedUser;
 use crate::errors::ServiceError;
+use crate::api::news_handler::{article_list_item, ArticleFieldsQuery};
 
 #[utoipa::path(
     get,
     path = "/api/v1/recommendations",
+    params(ArticleFieldsQuery),
     responses(
-        (status = 200, description = "Successfully retrieved recommendations", body = Vec<Article>),
+        (status = 200, description = "Successfully retrieved recommendations; items are ArticleSummary unless fields=full", body = Vec<ArticleListItem>),
         (status = 401, description = "Unauthorized", body = crate::errors::ErrorResponse),
         (status = 500, description = "Internal server error", body = crate::errors::ErrorResponse)
     ),
@@ -23,18 +26,19 @@ use crate::errors::ServiceError;
 pub async fn get_recommendations_handler(
     pool: web::Data<DbPool>,
     auth_user: AuthenticatedUser,
+    query: web::Query<ArticleFieldsQuery>,
 ) -> Result<impl Responder, ServiceError> {
     let limit = 10;
 
     let client = pool.get().await.map_err(ServiceError::from)?;
 
     // 获取最近的一些文章（模拟用户相关推荐）
-    let articles = crate::db::article_queries::get_articles(&client, 1, 20, true)
+    let articles = crate::db::article_queries::query_articles(&client, &ArticleFilter::default(), None, 0, 20)
         .await
         .map_err(ServiceError::from)?;
 
     if articles.is_empty() {
-        return Ok(HttpResponse::Ok().json(Vec::<Article>::new()));
+        return Ok(HttpResponse::Ok().json(Vec::<ArticleListItem>::new()));
     }
 
     // ------------------ TF-IDF 相似度推荐逻辑 ------------------
@@ -42,7 +46,7 @@ pub async fn get_recommendations_handler(
     let stemmer = Stemmer::create(Algorithm::English);
     let docs: Vec<Vec<String>> = articles
         .iter()
-        .map(|article| tokenize_and_stem(&article.content, &stemmer))
+        .map(|article| article_features(article, &stemmer))
         .collect();
 
     let tfidf_vecs = compute_tfidf(&docs);
@@ -57,10 +61,11 @@ pub async fn get_recommendations_handler(
 
     scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
 
-    let recommended_articles: Vec<Article> = scores
+    let fields = query.fields.unwrap_or_default();
+    let recommended_articles: Vec<ArticleListItem> = scores
         .into_iter()
         .take(limit as usize)
-        .map(|(i, _)| articles[i].clone())
+        .map(|(i, _)| article_list_item(articles[i].clone(), fields))
         .collect();
 
     Ok(HttpResponse::Ok().json(recommended_articles))
@@ -68,6 +73,19 @@ pub async fn get_recommendations_handler(
 
 // ------------------ TF-IDF 工具函数 ------------------
 
+// 标签作为额外特征重复加入，使共享关键词、人物、机构、地名的文章更相似
+const TAG_FEATURE_REPEAT: usize = 3;
+
+fn article_features(article: &Article, stemmer: &Stemmer) -> Vec<String> {
+    let mut features = tokenize_and_stem(&article.content, stemmer);
+    for tag in &article.tags {
+        // 加前缀，避免与正文分词结果混在一起
+        let feature = format!("#{}:{}", tag.kind.as_str(), tag.tag.to_lowercase());
+        features.extend(std::iter::repeat_n(feature, TAG_FEATURE_REPEAT));
+    }
+    features
+}
+
 fn tokenize_and_stem(text: &str, stemmer: &Stemmer) -> Vec<String> {
     text.split(|c: char| !c.is_alphanumeric())
         .filter(|token| !token.is_empty())
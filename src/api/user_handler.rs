@@ -1,11 +1,11 @@
-use actix_web::{web, get, put, HttpResponse, Responder};
+use actix_web::{web, get, put, delete, HttpResponse, Responder};
 // use uuid::Uuid; // Already in auth_handler, but good to have if this module is standalone
 
-use crate::services::user_service::{get_user_profile_service, update_user_profile_service};
+use crate::services::user_service::{get_user_profile_service, update_user_profile_service, delete_account_service};
 use crate::db::connection::DbPool;
 use crate::errors::ServiceError;
 use crate::errors::ErrorResponse;
-use crate::models::user::{UserProfileResponse, UpdateUserProfileSchema};
+use crate::models::user::{UserProfileResponse, UpdateUserProfileSchema, DeleteAccountSchema};
 use crate::api::auth_handler::AuthenticatedUser; // Re-use the AuthenticatedUser from auth_handler
 
 #[utoipa::path(
@@ -59,10 +59,35 @@ pub async fn update_my_profile_handler(
     }
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/v1/users/profile/me",
+    request_body = DeleteAccountSchema,
+    responses(
+        (status = 204, description = "Account and all of its interactions, favorites, comments and history deleted"),
+        (status = 401, description = "Unauthorized or incorrect password", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    )
+)]
+#[delete("/profile/me")]
+pub async fn delete_my_account_handler(
+    pool: web::Data<DbPool>,
+    auth_user: AuthenticatedUser,
+    body: web::Json<DeleteAccountSchema>,
+) -> Result<impl Responder, ServiceError> {
+    delete_account_service(&pool, auth_user.user_id, body.into_inner()).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
 pub fn init_user_profile_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api/v1/users") // Base path for user-related routes
             .service(get_my_profile_handler)
             .service(update_my_profile_handler)
+            .service(delete_my_account_handler)
     );
 }
@@ -1,9 +1,14 @@
-use actix_web::{get, HttpResponse, Responder};
+use actix_web::{get, web, HttpResponse, Responder};
 use serde::Serialize;
 use std::collections::HashMap;
-use chrono::{Local, Duration};
+use chrono::{Duration, Utc};
 use rand::Rng;
 
+use crate::db::category_queries::count_articles_by_category_per_day;
+use crate::db::connection::DbPool;
+use crate::errors::ServiceError;
+use crate::services::category_service::load_taxonomy;
+
 /// 保留指定小数位
 trait Round3 {
     fn round_to(self, digits: u32) -> f64;
@@ -21,28 +26,40 @@ struct CategoryPoint {
     counts: HashMap<String, u32>,
 }
 
-/// 各类别新闻热度（最近 7 天）
+/// 各类别新闻热度（最近 7 天）：按首选类别统计每天抓取的文章数，子类别计入顶层类别
 #[get("/viz/category_heat")] 
-pub async fn category_heat() -> impl Responder {
-    let categories = vec!["财经", "科技", "体育", "娱乐", "社会"];
-    let mut data = Vec::with_capacity(7);
-    let mut rng = rand::thread_rng();
-    
-    for i in 0..7 {
-        let date = Local::today()
-            .naive_local()
-            .checked_sub_signed(Duration::days((6 - i) as i64))
-            .unwrap()
-            .format("%m-%d")
-            .to_string();
-        let mut counts = HashMap::new();
-        for &cat in &categories {
-            counts.insert(cat.to_string(), rng.gen_range(20..200));
+pub async fn category_heat(pool: web::Data<DbPool>) -> Result<impl Responder, ServiceError> {
+    let client = pool.get().await?;
+    let taxonomy = load_taxonomy(&client).await?;
+    let today = Utc::now().date_naive();
+    let first_day = today - Duration::days(6);
+    let since = first_day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let rows = count_articles_by_category_per_day(&client, since).await?;
+
+    let top_level: Vec<&str> = taxonomy
+        .categories()
+        .iter()
+        .filter(|c| c.parent_id.is_none())
+        .map(|c| c.name_zh.as_str())
+        .collect();
+    let mut data: Vec<CategoryPoint> = (0..7)
+        .map(|i| CategoryPoint {
+            date: (first_day + Duration::days(i)).format("%m-%d").to_string(),
+            counts: top_level.iter().map(|name| (name.to_string(), 0)).collect(),
+        })
+        .collect();
+    for (day, category, count) in rows {
+        let index = (day - first_day).num_days();
+        let Some(point) = usize::try_from(index).ok().and_then(|i| data.get_mut(i)) else {
+            continue;
+        };
+        // 类别表中已没有的旧类别名不计入
+        if let Some(category) = taxonomy.resolve(&category) {
+            *point.counts.entry(taxonomy.root_of(category).name_zh.clone()).or_insert(0) += count as u32;
         }
-        data.push(CategoryPoint { date, counts });
     }
 
-    HttpResponse::Ok().json(data)
+    Ok(HttpResponse::Ok().json(data))
 }
 
 #[derive(Serialize)]
@@ -74,22 +91,32 @@ pub async fn model_performance() -> impl Responder {
 #[derive(Serialize)]
 struct UserInterest {
     user_segment: String,
+    categories: Vec<String>, // 顶层类别，与 heat 一一对应
     heat: Vec<f64>,
 }
 
 /// 用户兴趣热力数据
 #[get("/viz/user_interest")] 
-pub async fn user_interest() -> impl Responder {
+pub async fn user_interest(pool: web::Data<DbPool>) -> Result<impl Responder, ServiceError> {
+    let client = pool.get().await?;
+    let categories: Vec<String> = load_taxonomy(&client)
+        .await?
+        .categories()
+        .iter()
+        .filter(|c| c.parent_id.is_none())
+        .map(|c| c.name_zh.clone())
+        .collect();
     let segments = vec!["老用户", "新用户", "潜在用户"];
     let mut rng = rand::thread_rng();
 
     let data: Vec<UserInterest> = segments.into_iter().map(|seg| {
-        let heat = (0..5).map(|_| (rng.gen::<f64>() * 100.0).round_to(2)).collect();
+        let heat = categories.iter().map(|_| (rng.gen::<f64>() * 100.0).round_to(2)).collect();
         UserInterest {
             user_segment: seg.to_string(),
+            categories: categories.clone(),
             heat,
         }
     }).collect();
 
-    HttpResponse::Ok().json(data)
+    Ok(HttpResponse::Ok().json(data))
 }
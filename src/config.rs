@@ -11,9 +11,70 @@ pub mod app_config {
         pub jwt_secret: String,
         pub jwt_expiration_hours: i64,
         pub crawler_cron_expression: String,
+        pub admin_usernames: Vec<String>, // Users allowed to call admin endpoints
+        pub storage: StorageConfig, // Where article images are stored
+        pub auto_migrate: bool, // 启动时执行待执行的表结构迁移（AUTO_MIGRATE=false 关闭，改用 migrate 子命令）
         // pub crawler_sources_config_path: Option<String>, // If you load sources from a file
     }
 
+    // 图片存储后端，由 STORAGE_BACKEND 选择（local / s3）
+    #[derive(Debug, Deserialize, Clone)]
+    pub enum StorageConfig {
+        Local { root: String },
+        S3(S3Config),
+    }
+
+    // S3 兼容存储（AWS S3、MinIO 等）
+    #[derive(Debug, Deserialize, Clone)]
+    pub struct S3Config {
+        pub endpoint: String, // e.g. https://s3.us-east-1.amazonaws.com or http://127.0.0.1:9000
+        pub bucket: String,
+        pub region: String,
+        pub access_key_id: String,
+        pub secret_access_key: String,
+        pub path_style: bool, // MinIO 等通常只支持 path-style 访问
+        pub prefix: String,   // 对象键前缀，可为空
+    }
+
+    impl StorageConfig {
+        pub fn backend_name(&self) -> &'static str {
+            match self {
+                StorageConfig::Local { .. } => "local",
+                StorageConfig::S3(_) => "s3",
+            }
+        }
+
+        // 读取指定后端的配置；backend 为 None 时取 STORAGE_BACKEND（默认 local）
+        pub fn from_env(backend: Option<&str>) -> Result<Self> {
+            let backend = match backend {
+                Some(backend) => backend.to_string(),
+                None => env::var("STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string()),
+            };
+            match backend.trim().to_ascii_lowercase().as_str() {
+                "local" => Ok(StorageConfig::Local {
+                    root: env::var("IMAGE_STORAGE_DIR").unwrap_or_else(|_| "storage/img".to_string()),
+                }),
+                "s3" => {
+                    let required = |name: &str| {
+                        env::var(name).map_err(|e| anyhow::anyhow!("{} must be set for the s3 storage backend: {}", name, e))
+                    };
+                    Ok(StorageConfig::S3(S3Config {
+                        endpoint: required("S3_ENDPOINT")?,
+                        bucket: required("S3_BUCKET")?,
+                        region: env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+                        access_key_id: required("S3_ACCESS_KEY_ID")?,
+                        secret_access_key: required("S3_SECRET_ACCESS_KEY")?,
+                        path_style: env::var("S3_PATH_STYLE")
+                            .map(|v| v != "false" && v != "0")
+                            .unwrap_or(true),
+                        prefix: env::var("S3_PREFIX").unwrap_or_default(),
+                    }))
+                }
+                other => Err(anyhow::anyhow!("Unknown STORAGE_BACKEND {:?}, expected local or s3", other)),
+            }
+        }
+    }
+
     impl Config {
         pub fn from_env() -> Result<Self> {
             dotenv::dotenv().ok(); // Load .env file if present
@@ -39,6 +100,17 @@ pub mod app_config {
                 .unwrap_or_else(|_| "*/5 * * * * *".to_string());
             // let crawler_sources_config_path = env::var("CRAWLER_SOURCES_CONFIG_PATH").ok();
 
+            let admin_usernames = env::var("ADMIN_USERNAMES")
+                .unwrap_or_default()
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+
+            let auto_migrate = env::var("AUTO_MIGRATE")
+                .map(|v| v != "false" && v != "0")
+                .unwrap_or(true);
+
             Ok(Config {
                 server_address,
                 server_port,
@@ -46,6 +118,9 @@ pub mod app_config {
                 jwt_secret,
                 jwt_expiration_hours,
                 crawler_cron_expression,
+                admin_usernames,
+                storage: StorageConfig::from_env(None)?,
+                auto_migrate,
                 // crawler_sources_config_path,
             })
         }
@@ -1,234 +1,625 @@
-use tokio_postgres::{Client, Error as PgError};
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, Error as PgError, Row};
 use uuid::Uuid;
 
-use crate::models::article::Article; // Assuming Article struct is defined
-use crate::models::article::NewsImage;
+use crate::models::article::{
+    thumbnail_url, Article, ArticleRevision, ArticleSort, ArticleSummary, ArticleTag, ManagedArticle, RevisionSource, TagKind,
+};
 
-// Function to insert a new article into the database
-// It should handle potential conflicts if an article with the same URL already exists.
-pub async fn insert_article(client: &Client, article: &Article) -> Result<Article, PgError> {
-    // Using ON CONFLICT (url) DO NOTHING to avoid inserting duplicate articles based on URL
-    // Alternatively, ON CONFLICT (url) DO UPDATE SET ... if we want to update existing articles.
-    // For now, we'll just skip duplicates.
-    let row_option = client.query_opt(
-        "INSERT INTO articles (id, title, content, url, source_name, published_at, crawled_at, categories, feature_vector) \
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
-         ON CONFLICT (url) DO NOTHING \
-         RETURNING id, title, content, url, source_name, published_at, crawled_at, categories, feature_vector",
-        &[
-            &article.id,
+// 查询文章时统一使用的列，顺序与 article_from_row 对应
+// cover_image_id 是文章第一张图片的 id，供列表页按 /api/v1/images/{id} 加载封面；
+// tags 按权重排序，每项为 "种类:标签"
+pub(crate) const ARTICLE_COLUMNS: &str = "id, title, content, content_html, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count, source_category, \
+    (SELECT i.id FROM news_images i WHERE i.article_id = articles.id ORDER BY i.position LIMIT 1) AS cover_image_id, \
+    (SELECT array_agg(t.kind || ':' || t.tag ORDER BY t.weight DESC, t.tag) FROM article_tags t WHERE t.article_id = articles.id) AS tags, \
+    summary";
+
+// 对读者可见的文章：没有隐藏也没有删除。列表、检索、推荐和详情都只返回这些文章
+pub(crate) const VISIBLE_CONDITION: &str = "articles.hidden_at IS NULL AND articles.deleted_at IS NULL";
+
+// 列表只查精简字段，不读取正文和 HTML 正文；还没有摘要的旧文章截取正文开头代替
+pub(crate) const ARTICLE_SUMMARY_COLUMNS: &str = "id, title, COALESCE(summary, left(content, 200)) AS summary, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count, \
+    (SELECT i.id FROM news_images i WHERE i.article_id = articles.id ORDER BY i.position LIMIT 1) AS cover_image_id";
+
+fn tags_from_column(tags: Option<Vec<String>>) -> Vec<ArticleTag> {
+    tags.unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let (kind, tag) = entry.split_once(':')?;
+            Some(ArticleTag { tag: tag.to_string(), kind: TagKind::parse(kind)? })
+        })
+        .collect()
+}
+
+pub(crate) fn article_from_row(row: &Row) -> Article {
+    Article {
+        id: row.get(0),
+        title: row.get(1),
+        content: row.get(2),
+        content_html: row.get(3),
+        url: row.get(4),
+        source_name: row.get(5),
+        published_at: row.get(6),
+        crawled_at: row.get(7),
+        categories: row.get(8),
+        like_count: row.get(9),
+        comment_count: row.get(10),
+        favorite_count: row.get(11),
+        source_category: row.get(12),
+        cover_image_id: row.get(13),
+        tags: tags_from_column(row.get(14)),
+        summary: row.get(15),
+    }
+}
+
+pub(crate) fn article_summary_from_row(row: &Row) -> ArticleSummary {
+    ArticleSummary {
+        id: row.get(0),
+        title: row.get(1),
+        summary: row.get(2),
+        url: row.get(3),
+        source_name: row.get(4),
+        published_at: row.get(5),
+        crawled_at: row.get(6),
+        categories: row.get(7),
+        like_count: row.get(8),
+        comment_count: row.get(9),
+        favorite_count: row.get(10),
+        thumbnail_url: row.get::<_, Option<i32>>(11).map(thumbnail_url),
+    }
+}
+
+// 管理接口额外查询的状态列，接在 ARTICLE_COLUMNS 之后
+fn managed_article_columns() -> String {
+    format!("{}, updated_at, hidden_at, deleted_at", ARTICLE_COLUMNS)
+}
+
+fn managed_article_from_row(row: &Row) -> ManagedArticle {
+    ManagedArticle {
+        article: article_from_row(row),
+        updated_at: row.get(16),
+        hidden_at: row.get(17),
+        deleted_at: row.get(18),
+    }
+}
+
+// 写入一篇文章的结果，都带着库中最终的文章
+#[derive(Debug, Clone)]
+pub enum InsertOutcome {
+    Inserted(Article),
+    AlreadyExisted(Article), // URL 已存在且不需要更新（见 RecrawlMode），或文章已删除，库中文章保持不变
+    Updated(Article), // URL 已存在且内容有变化，已更新，旧版本存入修订历史
+}
+
+impl InsertOutcome {
+    pub fn article(&self) -> &Article {
+        match self {
+            InsertOutcome::Inserted(article) | InsertOutcome::AlreadyExisted(article) | InsertOutcome::Updated(article) => article,
+        }
+    }
+
+    pub fn into_article(self) -> Article {
+        match self {
+            InsertOutcome::Inserted(article) | InsertOutcome::AlreadyExisted(article) | InsertOutcome::Updated(article) => article,
+        }
+    }
+}
+
+// 再次抓取到已有文章时如何处理
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecrawlMode {
+    // 定时爬取：标题或正文与上次抓取的版本不同时才更新；最近一次修改是管理员编辑的文章保持不变
+    Scheduled,
+    // 管理员要求重新抓取：标题、正文、HTML 正文或摘要有任何不同都用抓取结果覆盖，包括管理员编辑过的文章
+    Requested,
+}
+
+impl RecrawlMode {
+    // stored 为库中的版本，last_revision 为它最近一次修改的来源（从未修改过为 None）。
+    // 最近一次修改不是管理员编辑时，库中的版本就是上次抓取的结果
+    pub fn should_update(self, stored: &ArticleContent, last_revision: Option<RevisionSource>, crawled: &ArticleContent) -> bool {
+        match self {
+            RecrawlMode::Scheduled => {
+                last_revision != Some(RevisionSource::Admin)
+                    && (stored.title != crawled.title || stored.content != crawled.content)
+            }
+            RecrawlMode::Requested => stored != crawled,
+        }
+    }
+}
+
+// 已有文章连同最近一次修改的来源，顺序与 existing_article_from_row 对应
+fn existing_article_columns() -> String {
+    format!(
+        "{}, (SELECT r.source FROM article_revisions r WHERE r.article_id = articles.id ORDER BY r.revised_at DESC, r.id DESC LIMIT 1)",
+        ARTICLE_COLUMNS
+    )
+}
+
+fn existing_article_from_row(row: &Row) -> (Article, Option<RevisionSource>) {
+    let last_revision = row.get::<_, Option<String>>(16).as_deref().and_then(RevisionSource::parse);
+    (article_from_row(row), last_revision)
+}
+
+// 每篇文章占用的参数个数，见 insert_articles
+const INSERT_PARAMS_PER_ARTICLE: usize = 11;
+
+// 用一条多行 INSERT 写入一批文章，按输入顺序返回每篇的结果。
+// URL 已存在的文章不会重复插入，按 mode 决定是否用抓取结果更新（见 RecrawlMode::should_update）
+pub async fn insert_articles(client: &Client, articles: &[Article], mode: RecrawlMode) -> Result<Vec<InsertOutcome>, PgError> {
+    if articles.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut values = Vec::with_capacity(articles.len());
+    let mut params: Vec<&(dyn ToSql + Sync)> = Vec::with_capacity(articles.len() * INSERT_PARAMS_PER_ARTICLE);
+    for article in articles {
+        let first = params.len() + 1;
+        let placeholders: Vec<String> = (first..first + INSERT_PARAMS_PER_ARTICLE).map(|n| format!("${}", n)).collect();
+        values.push(format!("({})", placeholders.join(", ")));
+        params.extend_from_slice(&[
+            &article.id as &(dyn ToSql + Sync),
             &article.title,
             &article.content,
+            &article.content_html,
             &article.url,
             &article.source_name,
             &article.published_at,
             &article.crawled_at,
             &article.categories,
-            &None::<Vec<u8>> // Placeholder for feature_vector
-        ]
-    ).await?;
-
-    if let Some(row) = row_option {
-        Ok(Article {
-            id: row.get(0),
-            title: row.get(1),
-            content: row.get(2),
-            url: row.get(3),
-            source_name: row.get(4),
-            published_at: row.get(5),
-            crawled_at: row.get(6),
-            categories: row.get(7),
-            like_count: Some(0),
-            comment_count: Some(0),
-            favorite_count: Some(0),
-            // feature_vector: row.get(8), // Assuming it's Option<Vec<u8>>
-        })
-    } else {
-        // Article with this URL already exists and was skipped
-        // We might want to fetch the existing one or return an indicator
-        // For now, returning the input article might be misleading. Let's return an error or a specific type.
-        // Or, fetch the existing article by URL.
-        // For simplicity, if insert was skipped, we can indicate it.
-        // Let's try to fetch the existing one if insert was skipped.
-        get_article_by_url(client, &article.url).await.map(|opt_art| opt_art.unwrap_or_else(|| article.clone()))
-        // This unwrap_or_else is a bit of a hack. A better way is to return an enum: Inserted(Article) or Existed(Article)
-    }
-}
-
-pub async fn save_image_to_db(
-    client: &deadpool_postgres::Client,
-    image: &NewsImage,
-) -> Result<(), anyhow::Error> {
-    //println!("c_url:{}",&image.url);
-    client.execute(
-        "INSERT INTO news_images (url, content_type, storage_path) 
-         VALUES ($1, $2, $3)",
-        &[&image.url, &image.content_type, &image.file_path]
+            &article.source_category,
+            &article.summary,
+        ]);
+    }
+    // 同一批里重复的 URL 也由 DO NOTHING 跳过，只插入第一篇
+    let rows = client.query(
+        &format!(
+            "INSERT INTO articles (id, title, content, content_html, url, source_name, published_at, crawled_at, categories, source_category, summary) \
+             VALUES {} \
+             ON CONFLICT (url) DO NOTHING \
+             RETURNING {}",
+            values.join(", "),
+            ARTICLE_COLUMNS
+        ),
+        &params
     ).await?;
-    
-    Ok(())
-}
+    let mut inserted: HashMap<Uuid, Article> = rows.iter().map(article_from_row).map(|a| (a.id, a)).collect();
 
-pub async fn load_image_from_db(
-    client: &deadpool_postgres::Client,
-    url: &str
-) -> Result<Option<NewsImage>, PgError> {
-    // println!("c_url:{}",&url);
-    // log::info!("load start:");
-
-    let row_option=
-    client.query_opt(
-        "SELECT id, url, content_type, storage_path
-        FROM news_images
-        WHERE url = $1",
-        &[&url]
-    ).await?;
+    // 没有插入的文章一次查出库中已有的版本
+    let skipped_urls: Vec<&str> = articles
+        .iter()
+        .filter(|article| !inserted.contains_key(&article.id))
+        .map(|article| article.url.as_str())
+        .collect();
+    let mut existing: HashMap<String, (Article, Option<RevisionSource>)> = HashMap::new();
+    if !skipped_urls.is_empty() {
+        let rows = client
+            .query(&format!("SELECT {} FROM articles WHERE url = ANY($1)", existing_article_columns()), &[&skipped_urls])
+            .await?;
+        existing = rows.iter().map(existing_article_from_row).map(|e| (e.0.url.clone(), e)).collect();
+    }
 
-    if let Some(row) = row_option{
-        // log::info!("Load Fin!");
-        Ok(Some(NewsImage{
-            id:row.get(0),
-            url:row.get(1),
-            content_type:row.get(2),
-            file_path:row.get(3),
-        }))
-    } else{
-        // log::info!("Load Failed!");
-        Ok(None)
+    let mut outcomes = Vec::with_capacity(articles.len());
+    for article in articles {
+        if let Some(stored) = inserted.remove(&article.id) {
+            outcomes.push(InsertOutcome::Inserted(stored));
+            continue;
+        }
+        let (stored, last_revision) = match existing.get(&article.url) {
+            Some(entry) => entry.clone(),
+            // 上面查询之后才插入的同 URL 文章
+            None => existing_article_from_row(
+                &client.query_one(&format!("SELECT {} FROM articles WHERE url = $1", existing_article_columns()), &[&article.url]).await?,
+            ),
+        };
+        let current = ArticleContent::of(&stored);
+        let crawled = ArticleContent::of(article);
+        let updated = if mode.should_update(&current, last_revision, &crawled) {
+            replace_article_content(client, stored.id, &crawled, RevisionSource::Crawl, None, Some(&current)).await?
+        } else {
+            None
+        };
+        let outcome = match updated {
+            // 同一批里后面同 URL 的文章与更新后的版本比较
+            Some(updated) => {
+                existing.insert(article.url.clone(), (updated.clone(), Some(RevisionSource::Crawl)));
+                InsertOutcome::Updated(updated)
+            }
+            None => {
+                existing.insert(article.url.clone(), (stored.clone(), last_revision));
+                InsertOutcome::AlreadyExisted(stored)
+            }
+        };
+        outcomes.push(outcome);
     }
+    Ok(outcomes)
+}
+
+// 写入单篇文章，见 insert_articles
+pub async fn insert_article(client: &Client, article: &Article, mode: RecrawlMode) -> Result<InsertOutcome, PgError> {
+    let mut outcomes = insert_articles(client, std::slice::from_ref(article), mode).await?;
+    Ok(outcomes.pop().expect("one outcome per article"))
 }
 
 // Function to get an article by its URL
 pub async fn get_article_by_url(client: &Client, url: &str) -> Result<Option<Article>, PgError> {
     let row_option = client.query_opt(
-        "SELECT id, title, content, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count FROM articles WHERE url = $1",
+        &format!("SELECT {} FROM articles WHERE url = $1", ARTICLE_COLUMNS),
         &[&url]
     ).await?;
 
     if let Some(row) = row_option {
-        Ok(Some(Article {
-            id: row.get(0),
-            title: row.get(1),
-            content: row.get(2),
-            url: row.get(3),
-            source_name: row.get(4),
-            published_at: row.get(5),
-            crawled_at: row.get(6),
-            categories: row.get(7),
-            like_count: row.get(8),
-            comment_count: row.get(9),
-            favorite_count: row.get(10),
-            // feature_vector: row.get(11), // If selected
-        }))
+        Ok(Some(article_from_row(&row)))
     } else {
         Ok(None)
     }
 }
 
-// Function to get articles (e.g., for display, with pagination)
-pub async fn get_articles(
+// 文章列表的筛选条件；字段为空表示不限
+#[derive(Debug, Clone, Default)]
+pub struct ArticleFilter {
+    pub categories: Option<Vec<String>>, // 命中其中任意一个类别即可（调用方已展开子类别）
+    pub source_name: Option<String>,
+    pub published_from: Option<DateTime<Utc>>, // 含
+    pub published_to: Option<DateTime<Utc>>, // 含
+    pub has_image: Option<bool>,
+    pub tag: Option<String>, // 任意种类的标签，不区分英文大小写
+    pub sort: ArticleSort,
+}
+
+// 没有发布时间的文章按入库时间排
+const PUBLISHED_KEY: &str = "COALESCE(published_at, crawled_at)";
+
+// 文章列表的翻页位置：上一页最后一篇文章的排序键
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArticleCursor {
+    pub sort: ArticleSort, // 游标只能用于生成它的排序方式
+    #[serde(default)]
+    pub count: i32, // 按点赞、评论、收藏数排序时的计数
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl ArticleCursor {
+    // counts 依次为点赞、评论、收藏数
+    fn new(sort: ArticleSort, counts: [Option<i32>; 3], published_at: Option<DateTime<Utc>>, crawled_at: DateTime<Utc>, id: Uuid) -> Self {
+        let count = match sort {
+            ArticleSort::Newest => None,
+            ArticleSort::MostLiked => counts[0],
+            ArticleSort::MostCommented => counts[1],
+            ArticleSort::MostFavorited => counts[2],
+        };
+        ArticleCursor {
+            sort,
+            count: count.unwrap_or(0),
+            at: published_at.unwrap_or(crawled_at),
+            id,
+        }
+    }
+
+    pub fn for_article(article: &Article, sort: ArticleSort) -> Self {
+        let counts = [article.like_count, article.comment_count, article.favorite_count];
+        Self::new(sort, counts, article.published_at, article.crawled_at, article.id)
+    }
+
+    pub fn for_summary(article: &ArticleSummary, sort: ArticleSort) -> Self {
+        let counts = [article.like_count, article.comment_count, article.favorite_count];
+        Self::new(sort, counts, article.published_at, article.crawled_at, article.id)
+    }
+}
+
+impl ArticleFilter {
+    // WHERE 子句和对应参数，参数编号从 $1 开始
+    pub fn where_clause(&self) -> (String, Vec<&(dyn ToSql + Sync)>) {
+        let mut conditions: Vec<String> = vec![VISIBLE_CONDITION.to_string()];
+        let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+
+        if let Some(categories) = &self.categories {
+            params.push(categories);
+            conditions.push(format!("categories && ${}", params.len()));
+        }
+        if let Some(source_name) = &self.source_name {
+            params.push(source_name);
+            conditions.push(format!("source_name = ${}", params.len()));
+        }
+        if let Some(from) = &self.published_from {
+            params.push(from);
+            conditions.push(format!("published_at >= ${}", params.len()));
+        }
+        if let Some(to) = &self.published_to {
+            params.push(to);
+            conditions.push(format!("published_at <= ${}", params.len()));
+        }
+        match self.has_image {
+            Some(true) => conditions.push("EXISTS (SELECT 1 FROM news_images i WHERE i.article_id = articles.id)".to_string()),
+            Some(false) => conditions.push("NOT EXISTS (SELECT 1 FROM news_images i WHERE i.article_id = articles.id)".to_string()),
+            None => {}
+        }
+        if let Some(tag) = &self.tag {
+            params.push(tag);
+            conditions.push(format!(
+                "EXISTS (SELECT 1 FROM article_tags t WHERE t.article_id = articles.id AND lower(t.tag) = lower(${}))",
+                params.len()
+            ));
+        }
+
+        (format!("WHERE {}", conditions.join(" AND ")), params)
+    }
+
+    // 排序用的计数列，按最新排序时没有
+    fn count_key(&self) -> Option<&'static str> {
+        match self.sort {
+            ArticleSort::Newest => None,
+            ArticleSort::MostLiked => Some("COALESCE(like_count, 0)"),
+            ArticleSort::MostCommented => Some("COALESCE(comment_count, 0)"),
+            ArticleSort::MostFavorited => Some("COALESCE(favorite_count, 0)"),
+        }
+    }
+
+    // 排序键依次为计数、发布时间和 id，全部倒序；以 id 收尾保证顺序唯一，键集翻页依赖这一点
+    fn sort_keys(&self) -> Vec<&'static str> {
+        self.count_key().into_iter().chain([PUBLISHED_KEY, "id"]).collect()
+    }
+
+    pub fn order_by(&self) -> String {
+        let keys: Vec<String> = self.sort_keys().iter().map(|key| format!("{} DESC", key)).collect();
+        format!("ORDER BY {}", keys.join(", "))
+    }
+
+    // 排在游标之后的条件；排序键全部倒序，可以直接用行比较
+    fn after_condition<'a>(&self, cursor: &'a ArticleCursor, params: &mut Vec<&'a (dyn ToSql + Sync)>) -> String {
+        let mut placeholders = Vec::new();
+        if self.count_key().is_some() {
+            params.push(&cursor.count);
+            placeholders.push(format!("${}", params.len()));
+        }
+        params.push(&cursor.at);
+        placeholders.push(format!("${}", params.len()));
+        params.push(&cursor.id);
+        placeholders.push(format!("${}", params.len()));
+        format!("({}) < ({})", self.sort_keys().join(", "), placeholders.join(", "))
+    }
+}
+
+// 按筛选条件查询一页文章的指定列；给出 after 时只取排在游标之后的文章（键集翻页），offset 此时通常为 0
+async fn select_article_rows(
     client: &Client,
-    page: i64, // 1-based page number
+    columns: &str,
+    filter: &ArticleFilter,
+    after: Option<&ArticleCursor>,
+    offset: i64,
     limit: i64,
-    sort_by_published_at_desc: bool,
-) -> Result<Vec<Article>, PgError> {
-    let offset = (page - 1) * limit;
-    let order_by_clause = if sort_by_published_at_desc {
-        "ORDER BY published_at DESC NULLS LAST, crawled_at DESC"
-    } else {
-        "ORDER BY crawled_at DESC"
-    };
-
+) -> Result<Vec<Row>, PgError> {
+    let (mut where_clause, mut params) = filter.where_clause();
+    if let Some(cursor) = after {
+        let condition = filter.after_condition(cursor, &mut params);
+        where_clause = format!("{} AND {}", where_clause, condition);
+    }
     let query_string = format!(
-        "SELECT id, title, content, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count \
-         FROM articles \
-         {} \
-         LIMIT $1 OFFSET $2",
-        order_by_clause
+        "SELECT {} FROM articles {} {} LIMIT ${} OFFSET ${}",
+        columns,
+        where_clause,
+        filter.order_by(),
+        params.len() + 1,
+        params.len() + 2
     );
+    params.push(&limit);
+    params.push(&offset);
 
-    let rows = client.query(&query_string, &[&limit, &offset]).await?;
+    client.query(&query_string, &params).await
+}
 
-    Ok(rows.into_iter().map(|row| Article {
-        id: row.get(0),
-        title: row.get(1),
-        content: row.get(2),
-        url: row.get(3),
-        source_name: row.get(4),
-        published_at: row.get(5),
-        crawled_at: row.get(6),
-        categories: row.get(7),
-        like_count: row.get(8),
-        comment_count: row.get(9),
-        favorite_count: row.get(10),
-        // feature_vector: None, // Not fetching for general list view
-    }).collect())
+pub async fn query_articles(
+    client: &Client,
+    filter: &ArticleFilter,
+    after: Option<&ArticleCursor>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<Article>, PgError> {
+    let rows = select_article_rows(client, ARTICLE_COLUMNS, filter, after, offset, limit).await?;
+    Ok(rows.iter().map(article_from_row).collect())
 }
 
-// Function to count total articles (for pagination metadata)
-pub async fn count_total_articles(client: &Client) -> Result<i64, PgError> {
-    let row = client.query_one("SELECT COUNT(*) FROM articles", &[]).await?;
+// 与 query_articles 相同，但只返回列表用的精简字段
+pub async fn query_article_summaries(
+    client: &Client,
+    filter: &ArticleFilter,
+    after: Option<&ArticleCursor>,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<ArticleSummary>, PgError> {
+    let rows = select_article_rows(client, ARTICLE_SUMMARY_COLUMNS, filter, after, offset, limit).await?;
+    Ok(rows.iter().map(article_summary_from_row).collect())
+}
+
+// 符合筛选条件的文章总数（用于分页信息）
+pub async fn count_total_articles(client: &Client, filter: &ArticleFilter) -> Result<i64, PgError> {
+    let (where_clause, params) = filter.where_clause();
+    let row = client
+        .query_one(&format!("SELECT COUNT(*) FROM articles {}", where_clause), &params)
+        .await?;
     Ok(row.get(0))
 }
 
+// 读者看到的文章详情；隐藏和删除的文章视为不存在
 pub async fn find_article_by_id(client: &Client, article_id: Uuid) -> Result<Option<Article>, PgError> {
     let row_option = client
         .query_opt(
-            "SELECT id, title, content, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count FROM articles WHERE id = $1",
+            &format!("SELECT {} FROM articles WHERE id = $1 AND {}", ARTICLE_COLUMNS, VISIBLE_CONDITION),
             &[&article_id],
         )
         .await?;
 
     if let Some(row) = row_option {
-        Ok(Some(Article {
-            id: row.get(0),
-            title: row.get(1),
-            content: row.get(2),
-            url: row.get(3),
-            source_name: row.get(4),
-            published_at: row.get(5),
-            crawled_at: row.get(6),
-            categories: row.get(7),
-            like_count: row.get(8),
-            comment_count: row.get(9),
-            favorite_count: row.get(10),
-        }))
+        Ok(Some(article_from_row(&row)))
     } else {
         Ok(None)
     }
 }
 
-pub async fn get_recent_articles(client: &Client, limit: Option<i64>, page: Option<i64>) -> Result<Vec<Article>, PgError> {
-    let limit = limit.unwrap_or(10);
-    let page = page.unwrap_or(0); // 默认从第 0 页开始（偏移 0）
+// 锁住文章行直到事务结束，返回文章是否存在（任何状态）。
+// 点赞、收藏、评论的计数由触发器更新，触发器会检查同一用户在另一处的收藏；
+// 先拿到这把锁，之后语句中的检查就能看到并发事务已提交的改动，不会重复计数
+pub async fn lock_article(client: &Client, article_id: Uuid) -> Result<bool, PgError> {
+    let row = client.query_opt("SELECT 1 FROM articles WHERE id = $1 FOR UPDATE", &[&article_id]).await?;
+    Ok(row.is_some())
+}
 
-    let offset = page * limit;
+// 同 lock_article，但只锁对读者可见的文章；隐藏和已删除的文章返回 false，不再接受新的点赞、收藏和评论
+pub async fn lock_visible_article(client: &Client, article_id: Uuid) -> Result<bool, PgError> {
+    let row = client
+        .query_opt(&format!("SELECT 1 FROM articles WHERE id = $1 AND {} FOR UPDATE", VISIBLE_CONDITION), &[&article_id])
+        .await?;
+    Ok(row.is_some())
+}
 
-    let rows = client
-        .query(
-            "SELECT id, title, content, url, source_name, published_at, crawled_at, categories, like_count, comment_count, favorite_count \
-             FROM articles \
-             ORDER BY crawled_at DESC \
-             LIMIT $1 OFFSET $2",
-            &[&limit, &offset],
-        )
+// 管理接口读取文章，包括隐藏和已删除的文章
+pub async fn find_managed_article(client: &Client, article_id: Uuid) -> Result<Option<ManagedArticle>, PgError> {
+    let row = client
+        .query_opt(&format!("SELECT {} FROM articles WHERE id = $1", managed_article_columns()), &[&article_id])
         .await?;
+    Ok(row.as_ref().map(managed_article_from_row))
+}
+
+// 文章中带版本的部分：修改其中任何一项都会保存一个修订
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArticleContent {
+    pub title: String,
+    pub content: String,
+    pub content_html: Option<String>,
+    pub summary: Option<String>,
+}
+
+impl ArticleContent {
+    pub fn of(article: &Article) -> Self {
+        ArticleContent {
+            title: article.title.clone(),
+            content: article.content.clone(),
+            content_html: article.content_html.clone(),
+            summary: article.summary.clone(),
+        }
+    }
+}
+
+// 用新版本替换文章的标题、正文、HTML 正文和摘要，并把旧版本存入修订历史。
+// 内容没有变化、文章不存在或已删除时什么也不做，返回 None
+pub async fn revise_article(
+    client: &Client,
+    article_id: Uuid,
+    revision: &ArticleContent,
+    source: RevisionSource,
+    edited_by: Option<Uuid>,
+) -> Result<Option<Article>, PgError> {
+    replace_article_content(client, article_id, revision, source, edited_by, None).await
+}
 
+// 同 revise_article；expected 不为 None 时只在库中版本仍与它相同时替换，读取之后被改过的文章保持不变
+async fn replace_article_content(
+    client: &Client,
+    article_id: Uuid,
+    revision: &ArticleContent,
+    source: RevisionSource,
+    edited_by: Option<Uuid>,
+    expected: Option<&ArticleContent>,
+) -> Result<Option<Article>, PgError> {
+    let expected_title = expected.map(|e| e.title.as_str());
+    let expected_content = expected.map(|e| e.content.as_str());
+    let expected_content_html = expected.and_then(|e| e.content_html.as_deref());
+    let expected_summary = expected.and_then(|e| e.summary.as_deref());
+    // 锁定旧行、写修订和更新在同一条语句中完成，并发的重新抓取不会漏记或重复记录旧版本
+    let row = client.query_opt(
+        &format!(
+            "WITH old AS ( \
+                 SELECT id AS article_id, title AS old_title, content AS old_content, content_html AS old_content_html, summary AS old_summary \
+                 FROM articles WHERE id = $1 AND deleted_at IS NULL FOR UPDATE \
+             ), changed AS ( \
+                 SELECT * FROM old \
+                 WHERE (old_title, old_content, old_content_html, old_summary) IS DISTINCT FROM ($2::text, $3::text, $4::text, $5::text) \
+                   AND ($8::text IS NULL OR (old_title, old_content, old_content_html, old_summary) IS NOT DISTINCT FROM ($8::text, $9::text, $10::text, $11::text)) \
+             ), revision AS ( \
+                 INSERT INTO article_revisions (article_id, title, content, content_html, summary, source, edited_by) \
+                 SELECT article_id, old_title, old_content, old_content_html, old_summary, $6, $7 FROM changed \
+             ) \
+             UPDATE articles SET title = $2, content = $3, content_html = $4, summary = $5, updated_at = CURRENT_TIMESTAMP \
+             FROM changed WHERE articles.id = changed.article_id \
+             RETURNING {}",
+            ARTICLE_COLUMNS
+        ),
+        &[
+            &article_id,
+            &revision.title,
+            &revision.content,
+            &revision.content_html,
+            &revision.summary,
+            &source.as_str(),
+            &edited_by,
+            &expected_title,
+            &expected_content,
+            &expected_content_html,
+            &expected_summary,
+        ]
+    ).await?;
+    Ok(row.as_ref().map(article_from_row))
+}
+
+// 文章的修订历史，最近的在前
+pub async fn get_article_revisions(client: &Client, article_id: Uuid) -> Result<Vec<ArticleRevision>, PgError> {
+    let rows = client.query(
+        "SELECT id, article_id, title, content, content_html, summary, source, edited_by, revised_at \
+         FROM article_revisions WHERE article_id = $1 ORDER BY revised_at DESC, id DESC",
+        &[&article_id]
+    ).await?;
     Ok(rows
-        .into_iter()
-        .map(|row| Article {
+        .iter()
+        .map(|row| ArticleRevision {
             id: row.get(0),
-            title: row.get(1),
-            content: row.get(2),
-            url: row.get(3),
-            source_name: row.get(4),
-            published_at: row.get(5),
-            crawled_at: row.get(6),
-            categories: row.get(7),
-            like_count: row.get(8),
-            comment_count: row.get(9),
-            favorite_count: row.get(10),
+            article_id: row.get(1),
+            title: row.get(2),
+            content: row.get(3),
+            content_html: row.get(4),
+            summary: row.get(5),
+            source: RevisionSource::parse(row.get(6)).unwrap_or(RevisionSource::Admin),
+            edited_by: row.get(7),
+            revised_at: row.get(8),
         })
         .collect())
 }
 
+// 隐藏或重新显示文章；已删除的文章不能再改，返回是否找到了文章
+pub async fn set_article_hidden(client: &Client, article_id: Uuid, hidden: bool) -> Result<bool, PgError> {
+    let updated = client.execute(
+        "UPDATE articles SET hidden_at = CASE WHEN $2 THEN COALESCE(hidden_at, CURRENT_TIMESTAMP) END \
+         WHERE id = $1 AND deleted_at IS NULL",
+        &[&article_id, &hidden]
+    ).await?;
+    Ok(updated > 0)
+}
+
+// 软删除文章：保留数据，但此后所有读者接口都看不到它。返回是否删除了文章
+pub async fn soft_delete_article(client: &Client, article_id: Uuid) -> Result<bool, PgError> {
+    let updated = client.execute(
+        "UPDATE articles SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1 AND deleted_at IS NULL",
+        &[&article_id]
+    ).await?;
+    Ok(updated > 0)
+}
+
+// 还没有摘要的文章 (id, 正文)，按 id 分批读取，after 为上一批最后一篇的 id
+pub async fn get_unsummarized_articles(client: &Client, after: Option<Uuid>, limit: i64) -> Result<Vec<(Uuid, String)>, PgError> {
+    let rows = client.query(
+        "SELECT id, COALESCE(content, '') FROM articles \
+         WHERE summary IS NULL AND ($1::uuid IS NULL OR id > $1) \
+         ORDER BY id LIMIT $2",
+        &[&after, &limit]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+pub async fn set_article_summary(client: &Client, article_id: Uuid, summary: &str) -> Result<(), PgError> {
+    client.execute("UPDATE articles SET summary = $2 WHERE id = $1", &[&article_id, &summary]).await?;
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio_postgres::{Client, Error as PgError, Row, Transaction};
+use uuid::Uuid;
+
+use crate::models::category::Category;
+use crate::services::classifier_service::CategoryScore;
+
+// 一篇带类别的训练样本
+#[derive(Debug, Clone)]
+pub struct LabeledExample {
+    pub article_id: Uuid,
+    pub title: String,
+    pub content: String,
+    pub categories: Vec<String>,
+}
+
+// 保存分类器给出的类别和置信度，覆盖该文章此前的分类结果
+pub async fn replace_classifier_labels(client: &Client, article_id: Uuid, scores: &[CategoryScore]) -> Result<(), PgError> {
+    client.execute(
+        "DELETE FROM article_category_labels WHERE article_id = $1 AND source = 'classifier'",
+        &[&article_id]
+    ).await?;
+    for score in scores {
+        client.execute(
+            "INSERT INTO article_category_labels (article_id, category, source, confidence) VALUES ($1, $2, 'classifier', $3)",
+            &[&article_id, &score.category, &score.confidence]
+        ).await?;
+    }
+    Ok(())
+}
+
+// 人工标注文章类别，同时更新 articles.categories；文章不存在时返回 false
+pub async fn set_manual_labels(client: &Client, article_id: Uuid, categories: &[String]) -> Result<bool, PgError> {
+    let updated = client.execute(
+        "UPDATE articles SET categories = $2 WHERE id = $1",
+        &[&article_id, &categories]
+    ).await?;
+    if updated == 0 {
+        return Ok(false);
+    }
+    client.execute(
+        "DELETE FROM article_category_labels WHERE article_id = $1 AND source = 'manual'",
+        &[&article_id]
+    ).await?;
+    for category in categories {
+        client.execute(
+            "INSERT INTO article_category_labels (article_id, category, source) VALUES ($1, $2, 'manual')",
+            &[&article_id, category]
+        ).await?;
+    }
+    Ok(true)
+}
+
+// 有人工标注的文章
+pub async fn get_labeled_examples(client: &Client) -> Result<Vec<LabeledExample>, PgError> {
+    let rows = client.query(
+        "SELECT a.id, a.title, COALESCE(a.content, ''), array_agg(l.category ORDER BY l.category) \
+         FROM articles a JOIN article_category_labels l ON l.article_id = a.id \
+         WHERE l.source = 'manual' \
+         GROUP BY a.id, a.title, a.content \
+         ORDER BY a.id",
+        &[]
+    ).await?;
+    Ok(rows.iter().map(|row| LabeledExample {
+        article_id: row.get(0),
+        title: row.get(1),
+        content: row.get(2),
+        categories: row.get(3),
+    }).collect())
+}
+
+// 以 articles.categories 作为样本（冷启动用），跳过只有“未分类”类别的文章
+pub async fn get_seed_examples(client: &Client, uncategorized: &str) -> Result<Vec<LabeledExample>, PgError> {
+    let rows = client.query(
+        "SELECT id, title, COALESCE(content, ''), array_remove(categories, $1) FROM articles \
+         WHERE cardinality(array_remove(categories, $1)) > 0 \
+         ORDER BY id",
+        &[&uncategorized]
+    ).await?;
+    Ok(rows.iter().map(|row| LabeledExample {
+        article_id: row.get(0),
+        title: row.get(1),
+        content: row.get(2),
+        categories: row.get(3),
+    }).collect())
+}
+
+// 带栏目类别的文章，按抓取时间从新到旧；limit 为空时返回全部
+pub async fn get_source_labeled_examples(client: &Client, limit: Option<i64>) -> Result<Vec<LabeledExample>, PgError> {
+    let rows = client.query(
+        "SELECT id, title, COALESCE(content, ''), source_category FROM articles \
+         WHERE source_category IS NOT NULL \
+         ORDER BY crawled_at DESC, id \
+         LIMIT $1",
+        &[&limit]
+    ).await?;
+    Ok(rows.iter().map(|row| LabeledExample {
+        article_id: row.get(0),
+        title: row.get(1),
+        content: row.get(2),
+        categories: vec![row.get(3)],
+    }).collect())
+}
+
+const CATEGORY_COLUMNS: &str = "c.id, c.slug, c.name_zh, c.name_en, c.parent_id, c.position, c.icon, \
+    COALESCE((SELECT array_agg(a.alias ORDER BY a.alias) FROM category_aliases a WHERE a.category_id = c.id), '{}')";
+
+fn category_from_row(row: &Row) -> Category {
+    Category {
+        id: row.get(0),
+        slug: row.get(1),
+        name_zh: row.get(2),
+        name_en: row.get(3),
+        parent_id: row.get(4),
+        position: row.get(5),
+        icon: row.get(6),
+        aliases: row.get(7),
+    }
+}
+
+pub async fn list_categories(client: &Client) -> Result<Vec<Category>, PgError> {
+    let rows = client.query(
+        &format!("SELECT {} FROM categories c ORDER BY c.position, c.id", CATEGORY_COLUMNS),
+        &[]
+    ).await?;
+    Ok(rows.iter().map(category_from_row).collect())
+}
+
+pub async fn find_category_by_id(client: &Client, category_id: i32) -> Result<Option<Category>, PgError> {
+    let row = client.query_opt(
+        &format!("SELECT {} FROM categories c WHERE c.id = $1", CATEGORY_COLUMNS),
+        &[&category_id]
+    ).await?;
+    Ok(row.as_ref().map(category_from_row))
+}
+
+pub async fn insert_category(
+    client: &Client,
+    slug: &str,
+    name_zh: &str,
+    name_en: &str,
+    parent_id: Option<i32>,
+    position: i32,
+    icon: Option<&str>,
+) -> Result<i32, PgError> {
+    let row = client.query_one(
+        "INSERT INTO categories (slug, name_zh, name_en, parent_id, position, icon) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+        &[&slug, &name_zh, &name_en, &parent_id, &position, &icon]
+    ).await?;
+    Ok(row.get(0))
+}
+
+// 重命名时文章上保存的中文名一并更新
+pub async fn update_category(client: &mut Client, category: &Category, previous_name_zh: &str) -> Result<(), PgError> {
+    let tx = client.transaction().await?;
+    tx.execute(
+        "UPDATE categories SET name_zh = $2, name_en = $3, parent_id = $4, position = $5, icon = $6 WHERE id = $1",
+        &[&category.id, &category.name_zh, &category.name_en, &category.parent_id, &category.position, &category.icon]
+    ).await?;
+    if category.name_zh != previous_name_zh {
+        rename_category_references(&tx, previous_name_zh, &category.name_zh).await?;
+    }
+    tx.commit().await
+}
+
+// 整体替换类别的别名
+pub async fn replace_category_aliases(client: &mut Client, category_id: i32, aliases: &[String]) -> Result<(), PgError> {
+    let tx = client.transaction().await?;
+    tx.execute("DELETE FROM category_aliases WHERE category_id = $1", &[&category_id]).await?;
+    for alias in aliases {
+        tx.execute(
+            "INSERT INTO category_aliases (alias, category_id) VALUES ($1, $2)",
+            &[alias, &category_id]
+        ).await?;
+    }
+    tx.commit().await
+}
+
+// 把文章上的类别名 from 改为 to；文章已有 to 时去重并保留原来的顺序
+async fn rename_category_references(tx: &Transaction<'_>, from: &str, to: &str) -> Result<(u64, u64), PgError> {
+    let articles_updated = tx.execute(
+        "UPDATE articles SET categories = ( \
+             SELECT array_agg(t.c ORDER BY t.first) FROM ( \
+                 SELECT c, MIN(o) AS first FROM unnest(array_replace(categories, $1, $2)) WITH ORDINALITY AS u(c, o) GROUP BY c \
+             ) t \
+         ) \
+         WHERE $1 = ANY(categories)",
+        &[&from, &to]
+    ).await?;
+    tx.execute("UPDATE articles SET source_category = $2 WHERE source_category = $1", &[&from, &to]).await?;
+
+    let labels_moved = tx.execute(
+        "INSERT INTO article_category_labels (article_id, category, source, confidence, created_at) \
+         SELECT article_id, $2, source, confidence, created_at FROM article_category_labels WHERE category = $1 \
+         ON CONFLICT (article_id, category, source) DO NOTHING",
+        &[&from, &to]
+    ).await?;
+    tx.execute("DELETE FROM article_category_labels WHERE category = $1", &[&from]).await?;
+    Ok((articles_updated, labels_moved))
+}
+
+// 合并类别：文章、标注、子类别和别名都转到 into，from 的名称成为 into 的别名，然后删除 from。
+// 返回 (更新的文章数, 转移的标注数, 转移的子类别数)
+pub async fn merge_categories(client: &mut Client, from: &Category, into: &Category) -> Result<(u64, u64, u64), PgError> {
+    let tx = client.transaction().await?;
+    let (articles_updated, labels_moved) = rename_category_references(&tx, &from.name_zh, &into.name_zh).await?;
+    let children_moved = tx.execute(
+        "UPDATE categories SET parent_id = $2 WHERE parent_id = $1",
+        &[&from.id, &into.id]
+    ).await?;
+    tx.execute("UPDATE category_aliases SET category_id = $2 WHERE category_id = $1", &[&from.id, &into.id]).await?;
+    tx.execute("DELETE FROM categories WHERE id = $1", &[&from.id]).await?;
+    tx.execute(
+        "INSERT INTO category_aliases (alias, category_id) VALUES ($1, $2) ON CONFLICT (alias) DO UPDATE SET category_id = EXCLUDED.category_id",
+        &[&from.name_zh, &into.id]
+    ).await?;
+    tx.commit().await?;
+    Ok((articles_updated, labels_moved, children_moved))
+}
+
+// 按抓取日期统计各类别的文章数（只看文章的首选类别）
+pub async fn count_articles_by_category_per_day(
+    client: &Client,
+    since: DateTime<Utc>,
+) -> Result<Vec<(NaiveDate, String, i64)>, PgError> {
+    let rows = client.query(
+        "SELECT (crawled_at AT TIME ZONE 'UTC')::date AS day, categories[1], COUNT(*) FROM articles \
+         WHERE crawled_at >= $1 AND categories[1] IS NOT NULL \
+         GROUP BY day, categories[1]",
+        &[&since]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect())
+}
@@ -3,6 +3,7 @@ use uuid::Uuid;
 use chrono::Utc;
 
 use crate::models::article::{Comment, CreateCommentSchema};
+use crate::utils::cursor::TimeCursor;
 
 // Function to create a new comment
 pub async fn create_comment(
@@ -43,21 +44,23 @@ pub async fn create_comment(
     })
 }
 
-// Function to get comments for an article (supports basic pagination)
+// 文章的评论，按发表时间倒序；after 为上一页最后一条评论的位置，给出时 offset 为 0
 pub async fn get_comments_by_article_id(
     client: &Client,
     article_id: Uuid,
-    page: i64, // 1-based page number
+    after: Option<&TimeCursor>,
+    offset: i64,
     limit: i64,
 ) -> Result<Vec<Comment>, PgError> {
-    let offset = (page - 1) * limit;
+    let after_at = after.map(|cursor| cursor.at);
+    let after_id = after.map(|cursor| cursor.id);
     let rows = client.query(
         "SELECT id, user_id, article_id, username, content, parent_comment_id, created_at, updated_at \
          FROM comments \
-         WHERE article_id = $1 \
-         ORDER BY created_at DESC \
-         LIMIT $2 OFFSET $3",
-        &[&article_id, &limit, &offset]
+         WHERE article_id = $1 AND ($2::timestamptz IS NULL OR (created_at, id) < ($2, $3)) \
+         ORDER BY created_at DESC, id DESC \
+         LIMIT $4 OFFSET $5",
+        &[&article_id, &after_at, &after_id, &limit, &offset]
     ).await?;
 
     Ok(rows.into_iter().map(|row| Comment {
@@ -114,21 +117,42 @@ pub async fn get_comment_by_id(client: &Client, comment_id: Uuid) -> Result<Opti
     }
 }
 
-// Function to get comments by user ID (supports pagination)
+// 读取评论并锁住这一行直到事务结束，用于删除前的权限检查
+pub async fn lock_comment_by_id(client: &Client, comment_id: Uuid) -> Result<Option<Comment>, PgError> {
+    let row_option = client.query_opt(
+        "SELECT id, user_id, article_id, username, content, parent_comment_id, created_at, updated_at FROM comments WHERE id = $1 FOR UPDATE",
+        &[&comment_id]
+    ).await?;
+
+    Ok(row_option.map(|row| Comment {
+        id: row.get(0),
+        user_id: row.get(1),
+        article_id: row.get(2),
+        username: row.get(3),
+        content: row.get(4),
+        parent_comment_id: row.get(5),
+        created_at: row.get(6),
+        updated_at: row.get(7),
+    }))
+}
+
+// 用户发表的评论，按发表时间倒序；after 为上一页最后一条评论的位置，给出时 offset 为 0
 pub async fn get_comments_by_user_id(
     client: &Client,
     user_id: Uuid,
-    page: i64, // 1-based page number
+    after: Option<&TimeCursor>,
+    offset: i64,
     limit: i64,
 ) -> Result<Vec<Comment>, PgError> {
-    let offset = (page - 1) * limit;
+    let after_at = after.map(|cursor| cursor.at);
+    let after_id = after.map(|cursor| cursor.id);
     let rows = client.query(
         "SELECT c.id, c.user_id, c.article_id, c.username, c.content, c.parent_comment_id, c.created_at, c.updated_at \
          FROM comments c \
-         WHERE c.user_id = $1 \
-         ORDER BY c.created_at DESC \
-         LIMIT $2 OFFSET $3",
-        &[&user_id, &limit, &offset]
+         WHERE c.user_id = $1 AND ($2::timestamptz IS NULL OR (c.created_at, c.id) < ($2, $3)) \
+         ORDER BY c.created_at DESC, c.id DESC \
+         LIMIT $4 OFFSET $5",
+        &[&user_id, &after_at, &after_id, &limit, &offset]
     ).await?;
 
     Ok(rows.into_iter().map(|row| Comment {
@@ -0,0 +1,180 @@
+use tokio_postgres::{Client, Error as PgError, Row};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+use crate::models::crawl::{CrawlRun, CrawlSourceStats, SourceYieldAlert};
+
+// 记录一次爬虫运行的开始
+pub async fn start_crawl_run(client: &Client, triggered_by: &str, started_at: DateTime<Utc>) -> Result<Uuid, PgError> {
+    let run_id = Uuid::new_v4();
+    client.execute(
+        "INSERT INTO crawl_runs (id, triggered_by, status, started_at) VALUES ($1, $2, 'running', $3)",
+        &[&run_id, &triggered_by, &started_at]
+    ).await?;
+    Ok(run_id)
+}
+
+// 写入某个新闻源在本次运行中的统计，并累加到运行汇总上；两条语句应在同一事务中执行
+pub async fn insert_crawl_source_stats(client: &Client, stats: &CrawlSourceStats) -> Result<(), PgError> {
+    client.execute(
+        "INSERT INTO crawl_source_stats (id, run_id, source_name, listing_url, link_selector, started_at, finished_at, \
+                                         urls_found, articles_inserted, articles_updated, duplicates, parse_failures, http_errors, last_error) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)",
+        &[
+            &stats.id,
+            &stats.run_id,
+            &stats.source_name,
+            &stats.listing_url,
+            &stats.link_selector,
+            &stats.started_at,
+            &stats.finished_at,
+            &stats.urls_found,
+            &stats.articles_inserted,
+            &stats.articles_updated,
+            &stats.duplicates,
+            &stats.parse_failures,
+            &stats.http_errors,
+            &stats.last_error,
+        ]
+    ).await?;
+
+    client.execute(
+        "UPDATE crawl_runs SET urls_found = urls_found + $2, articles_inserted = articles_inserted + $3, \
+                articles_updated = articles_updated + $4, duplicates = duplicates + $5, \
+                parse_failures = parse_failures + $6, http_errors = http_errors + $7 \
+         WHERE id = $1",
+        &[
+            &stats.run_id,
+            &stats.urls_found,
+            &stats.articles_inserted,
+            &stats.articles_updated,
+            &stats.duplicates,
+            &stats.parse_failures,
+            &stats.http_errors,
+        ]
+    ).await?;
+
+    Ok(())
+}
+
+// 标记一次运行结束
+pub async fn finish_crawl_run(
+    client: &Client,
+    run_id: Uuid,
+    status: &str,
+    finished_at: DateTime<Utc>,
+) -> Result<(), PgError> {
+    client.execute(
+        "UPDATE crawl_runs SET status = $2, finished_at = $3 WHERE id = $1",
+        &[&run_id, &status, &finished_at]
+    ).await?;
+    Ok(())
+}
+
+// 把仍为 running 的运行标记为失败并返回条数；启动时调用，这些运行所在的进程已经退出，不会再结束
+pub async fn fail_unfinished_crawl_runs(client: &Client) -> Result<u64, PgError> {
+    client.execute(
+        "UPDATE crawl_runs SET status = 'failed', finished_at = CURRENT_TIMESTAMP WHERE status = 'running'",
+        &[]
+    ).await
+}
+
+fn crawl_run_from_row(row: &Row) -> CrawlRun {
+    CrawlRun {
+        id: row.get(0),
+        triggered_by: row.get(1),
+        status: row.get(2),
+        started_at: row.get(3),
+        finished_at: row.get(4),
+        urls_found: row.get(5),
+        articles_inserted: row.get(6),
+        articles_updated: row.get(7),
+        duplicates: row.get(8),
+        parse_failures: row.get(9),
+        http_errors: row.get(10),
+    }
+}
+
+fn crawl_source_stats_from_row(row: &Row) -> CrawlSourceStats {
+    CrawlSourceStats {
+        id: row.get(0),
+        run_id: row.get(1),
+        source_name: row.get(2),
+        listing_url: row.get(3),
+        link_selector: row.get(4),
+        started_at: row.get(5),
+        finished_at: row.get(6),
+        urls_found: row.get(7),
+        articles_inserted: row.get(8),
+        articles_updated: row.get(9),
+        duplicates: row.get(10),
+        parse_failures: row.get(11),
+        http_errors: row.get(12),
+        last_error: row.get(13),
+    }
+}
+
+// 获取最近的运行记录
+pub async fn get_recent_crawl_runs(client: &Client, limit: i64) -> Result<Vec<CrawlRun>, PgError> {
+    let rows = client.query(
+        "SELECT id, triggered_by, status, started_at, finished_at, urls_found, articles_inserted, articles_updated, duplicates, parse_failures, http_errors \
+         FROM crawl_runs \
+         ORDER BY started_at DESC \
+         LIMIT $1",
+        &[&limit]
+    ).await?;
+
+    Ok(rows.iter().map(crawl_run_from_row).collect())
+}
+
+// 获取若干次运行的分源统计
+pub async fn get_crawl_source_stats_for_runs(client: &Client, run_ids: &[Uuid]) -> Result<Vec<CrawlSourceStats>, PgError> {
+    let rows = client.query(
+        "SELECT id, run_id, source_name, listing_url, link_selector, started_at, finished_at, \
+                urls_found, articles_inserted, articles_updated, duplicates, parse_failures, http_errors, last_error \
+         FROM crawl_source_stats \
+         WHERE run_id = ANY($1) \
+         ORDER BY started_at ASC",
+        &[&run_ids]
+    ).await?;
+
+    Ok(rows.iter().map(crawl_source_stats_from_row).collect())
+}
+
+// 找出最近一次运行产出为零（没有新增、更新也没有重复文章）、但历史上有过产出的新闻源
+pub async fn get_zero_yield_sources(client: &Client) -> Result<Vec<SourceYieldAlert>, PgError> {
+    let rows = client.query(
+        "WITH ranked AS ( \
+             SELECT s.*, ROW_NUMBER() OVER (PARTITION BY source_name, listing_url, link_selector ORDER BY started_at DESC) AS rn \
+             FROM crawl_source_stats s \
+         ), latest AS ( \
+             SELECT r.source_name, r.listing_url, r.link_selector, r.run_id, r.started_at, r.urls_found, \
+                    r.parse_failures, r.http_errors, r.last_error, \
+                    (SELECT MAX(p.started_at) FROM crawl_source_stats p \
+                     WHERE p.source_name = r.source_name AND p.listing_url = r.listing_url \
+                       AND p.link_selector = r.link_selector \
+                       AND p.articles_inserted + p.articles_updated + p.duplicates > 0) AS last_productive_at \
+             FROM ranked r \
+             WHERE r.rn = 1 AND r.articles_inserted + r.articles_updated + r.duplicates = 0 \
+         ) \
+         SELECT source_name, listing_url, link_selector, run_id, started_at, urls_found, \
+                parse_failures, http_errors, last_error, last_productive_at \
+         FROM latest \
+         WHERE last_productive_at IS NOT NULL \
+         ORDER BY source_name, link_selector",
+        &[]
+    ).await?;
+
+    Ok(rows.into_iter().map(|row| SourceYieldAlert {
+        source_name: row.get(0),
+        listing_url: row.get(1),
+        link_selector: row.get(2),
+        last_run_id: row.get(3),
+        last_run_at: row.get(4),
+        last_urls_found: row.get(5),
+        last_parse_failures: row.get(6),
+        last_http_errors: row.get(7),
+        last_error: row.get(8),
+        last_productive_at: row.get(9),
+    }).collect())
+}
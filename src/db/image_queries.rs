@@ -0,0 +1,126 @@
+use tokio_postgres::{Client, Error as PgError, Row};
+use uuid::Uuid;
+
+use crate::db::article_queries::VISIBLE_CONDITION;
+use crate::models::article::NewsImage;
+
+const IMAGE_COLUMNS: &str = "id, article_id, position, source_url, caption, content_hash, content_type, width, height, storage_path, created_at";
+// 与文章表连接查询时使用；对外提供的图片都只取可见文章的
+const JOINED_IMAGE_COLUMNS: &str = "i.id, i.article_id, i.position, i.source_url, i.caption, i.content_hash, i.content_type, \
+                                    i.width, i.height, i.storage_path, i.created_at";
+
+fn image_from_row(row: &Row) -> NewsImage {
+    NewsImage {
+        id: row.get(0),
+        article_id: row.get(1),
+        position: row.get(2),
+        source_url: row.get(3),
+        caption: row.get(4),
+        content_hash: row.get(5),
+        content_type: row.get(6),
+        width: row.get(7),
+        height: row.get(8),
+        storage_path: row.get(9),
+        created_at: row.get(10),
+    }
+}
+
+// 保存文章的一张图片；同一位置已有图片时不覆盖
+pub async fn insert_article_image(client: &Client, image: &NewsImage) -> Result<(), PgError> {
+    client.execute(
+        "INSERT INTO news_images (article_id, position, source_url, caption, content_hash, content_type, width, height, storage_path) \
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+         ON CONFLICT (article_id, position) DO NOTHING",
+        &[
+            &image.article_id,
+            &image.position,
+            &image.source_url,
+            &image.caption,
+            &image.content_hash,
+            &image.content_type,
+            &image.width,
+            &image.height,
+            &image.storage_path,
+        ]
+    ).await?;
+    Ok(())
+}
+
+pub async fn count_article_images(client: &Client, article_id: Uuid) -> Result<i64, PgError> {
+    let row = client.query_one("SELECT COUNT(*) FROM news_images WHERE article_id = $1", &[&article_id]).await?;
+    Ok(row.get(0))
+}
+
+// 按顺序获取文章的全部图片
+pub async fn get_article_images(client: &Client, article_id: Uuid) -> Result<Vec<NewsImage>, PgError> {
+    let rows = client.query(
+        &format!(
+            "SELECT {} FROM news_images i JOIN articles ON articles.id = i.article_id \
+             WHERE i.article_id = $1 AND {} ORDER BY i.position",
+            JOINED_IMAGE_COLUMNS, VISIBLE_CONDITION
+        ),
+        &[&article_id]
+    ).await?;
+    Ok(rows.iter().map(image_from_row).collect())
+}
+
+pub async fn find_image_by_id(client: &Client, image_id: i32) -> Result<Option<NewsImage>, PgError> {
+    let row = client.query_opt(
+        &format!(
+            "SELECT {} FROM news_images i JOIN articles ON articles.id = i.article_id WHERE i.id = $1 AND {}",
+            JOINED_IMAGE_COLUMNS, VISIBLE_CONDITION
+        ),
+        &[&image_id]
+    ).await?;
+    Ok(row.as_ref().map(image_from_row))
+}
+
+// 旧接口 /images?url= 以文章 URL 查询，返回该文章的第一张图片
+pub async fn find_first_image_by_article_url(client: &Client, article_url: &str) -> Result<Option<NewsImage>, PgError> {
+    let row = client.query_opt(
+        &format!(
+            "SELECT {} FROM news_images i JOIN articles ON articles.id = i.article_id \
+             WHERE articles.url = $1 AND {} \
+             ORDER BY i.position \
+             LIMIT 1",
+            JOINED_IMAGE_COLUMNS, VISIBLE_CONDITION
+        ),
+        &[&article_url]
+    ).await?;
+    Ok(row.as_ref().map(image_from_row))
+}
+
+// 记录一张未通过校验的图片
+pub async fn insert_rejected_image(
+    client: &Client,
+    article_id: Option<Uuid>,
+    source_url: &str,
+    reason: &str,
+    detail: &str,
+    declared_content_type: Option<&str>,
+    byte_size: Option<i64>,
+) -> Result<(), PgError> {
+    client.execute(
+        "INSERT INTO rejected_images (article_id, source_url, reason, detail, declared_content_type, byte_size) \
+         VALUES ($1, $2, $3, $4, $5, $6)",
+        &[&article_id, &source_url, &reason, &detail, &declared_content_type, &byte_size]
+    ).await?;
+    Ok(())
+}
+
+// 全部图片记录，用于存储迁移
+pub async fn list_all_images(client: &Client) -> Result<Vec<NewsImage>, PgError> {
+    let rows = client.query(
+        &format!("SELECT {} FROM news_images ORDER BY id", IMAGE_COLUMNS),
+        &[]
+    ).await?;
+    Ok(rows.iter().map(image_from_row).collect())
+}
+
+pub async fn update_image_storage_path(client: &Client, image_id: i32, storage_path: &str) -> Result<(), PgError> {
+    client.execute(
+        "UPDATE news_images SET storage_path = $2 WHERE id = $1",
+        &[&image_id, &storage_path]
+    ).await?;
+    Ok(())
+}
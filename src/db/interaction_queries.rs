@@ -9,6 +9,8 @@ use crate::models::article::{
     UserFeedbackWithArticle, CommentWithArticle, UserInteractionWithArticle,
     UserFeedback, Comment
 };
+use crate::db::article_queries::VISIBLE_CONDITION;
+use crate::utils::cursor::TimeCursor;
 
 // 创建或更新用户互动
 pub async fn upsert_user_interaction(
@@ -87,6 +89,14 @@ pub async fn get_article_interaction_stats(
             }
         }
 
+        // 收藏夹中的文章也算已收藏，与 favorite_count 的口径一致
+        if !favorited {
+            favorited = client.query_opt(
+                "SELECT 1 FROM user_favorites WHERE user_id = $1 AND article_id = $2",
+                &[&uid, &article_id]
+            ).await?.is_some();
+        }
+
         // 检查是否有评论
         let comment_count_row = client.query_one(
             "SELECT COUNT(*) FROM comments WHERE user_id = $1 AND article_id = $2",
@@ -112,37 +122,62 @@ pub async fn get_article_interaction_stats(
     })
 }
 
-// 获取用户的互动记录
+// 按明细表重算一批文章（按 id 顺序，after 之后的 limit 篇）的点赞、收藏、评论计数，只改写有偏差的行。
+// 收藏数为通过收藏夹或 favorite 互动收藏了文章的用户数，与触发器 update_article_stats 的口径一致。
+// 返回这一批最后一篇文章的 id（没有更多文章时为 None）和修正的文章数
+pub async fn recount_article_counters(
+    client: &Client,
+    after: Option<Uuid>,
+    limit: i64,
+) -> Result<(Option<Uuid>, i64), PgError> {
+    let row = client.query_one(
+        "WITH batch AS ( \
+             SELECT id FROM articles WHERE ($1::uuid IS NULL OR id > $1) ORDER BY id LIMIT $2 \
+         ), counted AS ( \
+             SELECT b.id, \
+                 (SELECT COUNT(*) FROM user_interactions i \
+                  WHERE i.article_id = b.id AND i.interaction_type = 'like' AND i.is_active = TRUE)::int AS likes, \
+                 (SELECT COUNT(*) FROM ( \
+                     SELECT f.user_id FROM user_favorites f WHERE f.article_id = b.id \
+                     UNION \
+                     SELECT i.user_id FROM user_interactions i \
+                     WHERE i.article_id = b.id AND i.interaction_type = 'favorite' AND i.is_active = TRUE \
+                  ) AS favorited)::int AS favorites, \
+                 (SELECT COUNT(*) FROM comments c WHERE c.article_id = b.id)::int AS comments \
+             FROM batch b \
+         ), fixed AS ( \
+             UPDATE articles SET like_count = c.likes, favorite_count = c.favorites, comment_count = c.comments \
+             FROM counted c \
+             WHERE articles.id = c.id \
+               AND (articles.like_count <> c.likes OR articles.favorite_count <> c.favorites OR articles.comment_count <> c.comments) \
+             RETURNING articles.id \
+         ) \
+         SELECT (SELECT id FROM batch ORDER BY id DESC LIMIT 1), (SELECT COUNT(*) FROM fixed)",
+        &[&after, &limit]
+    ).await?;
+    Ok((row.get(0), row.get(1)))
+}
+
+// 获取用户的互动记录，按更新时间倒序；after 为上一页最后一条的位置，给出时 offset 为 0
 pub async fn get_user_interactions(
     client: &Client,
     user_id: Uuid,
-    interaction_type: Option<String>,
-    page: i64,
+    interaction_type: Option<&str>,
+    after: Option<&TimeCursor>,
+    offset: i64,
     limit: i64,
 ) -> Result<Vec<UserInteraction>, PgError> {
-    let offset = (page - 1) * limit;
-    
-    let (query, params): (String, Vec<&(dyn tokio_postgres::types::ToSql + Sync)>) = if let Some(itype) = &interaction_type {
-        (
-            "SELECT id, user_id, article_id, interaction_type, is_active, created_at, updated_at \
-             FROM user_interactions \
-             WHERE user_id = $1 AND interaction_type = $2 AND is_active = TRUE \
-             ORDER BY updated_at DESC \
-             LIMIT $3 OFFSET $4".to_string(),
-            vec![&user_id, itype, &limit, &offset]
-        )
-    } else {
-        (
-            "SELECT id, user_id, article_id, interaction_type, is_active, created_at, updated_at \
-             FROM user_interactions \
-             WHERE user_id = $1 AND is_active = TRUE \
-             ORDER BY updated_at DESC \
-             LIMIT $2 OFFSET $3".to_string(),
-            vec![&user_id, &limit, &offset]
-        )
-    };
-
-    let rows = client.query(&query, &params).await?;
+    let after_at = after.map(|cursor| cursor.at);
+    let after_id = after.map(|cursor| cursor.id);
+    let rows = client.query(
+        "SELECT id, user_id, article_id, interaction_type, is_active, created_at, updated_at \
+         FROM user_interactions \
+         WHERE user_id = $1 AND ($2::varchar IS NULL OR interaction_type = $2) AND is_active = TRUE \
+         AND ($3::timestamptz IS NULL OR (updated_at, id) < ($3, $4)) \
+         ORDER BY updated_at DESC, id DESC \
+         LIMIT $5 OFFSET $6",
+        &[&user_id, &interaction_type, &after_at, &after_id, &limit, &offset]
+    ).await?;
 
     Ok(rows.into_iter().map(|row| UserInteraction {
         id: row.get(0),
@@ -155,6 +190,16 @@ pub async fn get_user_interactions(
     }).collect())
 }
 
+// 用户有效互动记录的条数（用于分页信息）
+pub async fn count_user_interactions(client: &Client, user_id: Uuid, interaction_type: Option<&str>) -> Result<i64, PgError> {
+    let row = client.query_one(
+        "SELECT COUNT(*) FROM user_interactions \
+         WHERE user_id = $1 AND ($2::varchar IS NULL OR interaction_type = $2) AND is_active = TRUE",
+        &[&user_id, &interaction_type]
+    ).await?;
+    Ok(row.get(0))
+}
+
 // 添加到收藏夹
 pub async fn add_to_favorites(
     client: &Client,
@@ -195,37 +240,31 @@ pub async fn remove_from_favorites(
     ).await
 }
 
-// 获取用户收藏夹
+// 清空用户的收藏夹，返回删除的条数
+pub async fn remove_all_favorites(client: &Client, user_id: Uuid) -> Result<u64, PgError> {
+    client.execute("DELETE FROM user_favorites WHERE user_id = $1", &[&user_id]).await
+}
+
+// 获取用户收藏夹，按收藏时间倒序；after 为上一页最后一条的位置，给出时 offset 为 0
 pub async fn get_user_favorites(
     client: &Client,
     user_id: Uuid,
-    folder_name: Option<String>,
-    page: i64,
+    folder_name: Option<&str>,
+    after: Option<&TimeCursor>,
+    offset: i64,
     limit: i64,
 ) -> Result<Vec<UserFavorite>, PgError> {
-    let offset = (page - 1) * limit;
-    
-    let (query, params): (String, Vec<&(dyn tokio_postgres::types::ToSql + Sync)>) = if let Some(folder) = &folder_name {
-        (
-            "SELECT id, user_id, article_id, folder_name, created_at \
-             FROM user_favorites \
-             WHERE user_id = $1 AND folder_name = $2 \
-             ORDER BY created_at DESC \
-             LIMIT $3 OFFSET $4".to_string(),
-            vec![&user_id, folder, &limit, &offset]
-        )
-    } else {
-        (
-            "SELECT id, user_id, article_id, folder_name, created_at \
-             FROM user_favorites \
-             WHERE user_id = $1 \
-             ORDER BY created_at DESC \
-             LIMIT $2 OFFSET $3".to_string(),
-            vec![&user_id, &limit, &offset]
-        )
-    };
-
-    let rows = client.query(&query, &params).await?;
+    let after_at = after.map(|cursor| cursor.at);
+    let after_id = after.map(|cursor| cursor.id);
+    let rows = client.query(
+        "SELECT id, user_id, article_id, folder_name, created_at \
+         FROM user_favorites \
+         WHERE user_id = $1 AND ($2::varchar IS NULL OR folder_name = $2) \
+         AND ($3::timestamptz IS NULL OR (created_at, id) < ($3, $4)) \
+         ORDER BY created_at DESC, id DESC \
+         LIMIT $5 OFFSET $6",
+        &[&user_id, &folder_name, &after_at, &after_id, &limit, &offset]
+    ).await?;
 
     Ok(rows.into_iter().map(|row| UserFavorite {
         id: row.get(0),
@@ -236,6 +275,15 @@ pub async fn get_user_favorites(
     }).collect())
 }
 
+// 用户收藏的条数（用于分页信息）
+pub async fn count_user_favorites(client: &Client, user_id: Uuid, folder_name: Option<&str>) -> Result<i64, PgError> {
+    let row = client.query_one(
+        "SELECT COUNT(*) FROM user_favorites WHERE user_id = $1 AND ($2::varchar IS NULL OR folder_name = $2)",
+        &[&user_id, &folder_name]
+    ).await?;
+    Ok(row.get(0))
+}
+
 // 记录阅读历史
 pub async fn upsert_reading_history(
     client: &Client,
@@ -286,12 +334,15 @@ pub async fn get_user_feedback_history(
 
     // 获取反馈历史
     let feedback_rows = client.query(
-        "SELECT f.id, f.user_id, f.article_id, f.feedback_type, f.created_at, a.title, a.url \
-         FROM feedback f \
-         JOIN articles a ON f.article_id = a.id \
-         WHERE f.user_id = $1 \
-         ORDER BY f.created_at DESC \
-         LIMIT $2 OFFSET $3",
+        &format!(
+            "SELECT f.id, f.user_id, f.article_id, f.feedback_type, f.created_at, articles.title, articles.url \
+             FROM feedback f \
+             JOIN articles ON f.article_id = articles.id \
+             WHERE f.user_id = $1 AND {} \
+             ORDER BY f.created_at DESC \
+             LIMIT $2 OFFSET $3",
+            VISIBLE_CONDITION
+        ),
         &[&user_id, &limit, &offset]
     ).await?;
 
@@ -310,13 +361,16 @@ pub async fn get_user_feedback_history(
 
     // 获取评论历史
     let comment_rows = client.query(
-        "SELECT c.id, c.user_id, c.article_id, c.username, c.content, c.parent_comment_id, \
-                c.created_at, c.updated_at, a.title, a.url \
-         FROM comments c \
-         JOIN articles a ON c.article_id = a.id \
-         WHERE c.user_id = $1 \
-         ORDER BY c.created_at DESC \
-         LIMIT $2 OFFSET $3",
+        &format!(
+            "SELECT c.id, c.user_id, c.article_id, c.username, c.content, c.parent_comment_id, \
+                    c.created_at, c.updated_at, articles.title, articles.url \
+             FROM comments c \
+             JOIN articles ON c.article_id = articles.id \
+             WHERE c.user_id = $1 AND {} \
+             ORDER BY c.created_at DESC \
+             LIMIT $2 OFFSET $3",
+            VISIBLE_CONDITION
+        ),
         &[&user_id, &limit, &offset]
     ).await?;
 
@@ -339,13 +393,16 @@ pub async fn get_user_feedback_history(
 
     // 获取互动历史
     let interaction_rows = client.query(
-        "SELECT i.id, i.user_id, i.article_id, i.interaction_type, i.is_active, \
-                i.created_at, i.updated_at, a.title, a.url \
-         FROM user_interactions i \
-         JOIN articles a ON i.article_id = a.id \
-         WHERE i.user_id = $1 AND i.is_active = TRUE \
-         ORDER BY i.updated_at DESC \
-         LIMIT $2 OFFSET $3",
+        &format!(
+            "SELECT i.id, i.user_id, i.article_id, i.interaction_type, i.is_active, \
+                    i.created_at, i.updated_at, articles.title, articles.url \
+             FROM user_interactions i \
+             JOIN articles ON i.article_id = articles.id \
+             WHERE i.user_id = $1 AND i.is_active = TRUE AND {} \
+             ORDER BY i.updated_at DESC \
+             LIMIT $2 OFFSET $3",
+            VISIBLE_CONDITION
+        ),
         &[&user_id, &limit, &offset]
     ).await?;
 
@@ -365,11 +422,15 @@ pub async fn get_user_feedback_history(
         }
     }).collect();
 
-    // 计算总数
+    // 计算总数（与上面一样不计隐藏和已删除的文章）
     let total_count_row = client.query_one(
-        "SELECT (SELECT COUNT(*) FROM feedback WHERE user_id = $1) + \
-                (SELECT COUNT(*) FROM comments WHERE user_id = $1) + \
-                (SELECT COUNT(*) FROM user_interactions WHERE user_id = $1 AND is_active = TRUE)",
+        &format!(
+            "SELECT (SELECT COUNT(*) FROM feedback f JOIN articles ON f.article_id = articles.id WHERE f.user_id = $1 AND {0}) + \
+                    (SELECT COUNT(*) FROM comments c JOIN articles ON c.article_id = articles.id WHERE c.user_id = $1 AND {0}) + \
+                    (SELECT COUNT(*) FROM user_interactions i JOIN articles ON i.article_id = articles.id \
+                     WHERE i.user_id = $1 AND i.is_active = TRUE AND {0})",
+            VISIBLE_CONDITION
+        ),
         &[&user_id]
     ).await?;
     let total_count: i64 = total_count_row.get(0);
@@ -0,0 +1,132 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tokio_postgres::{Client, Error as PgError};
+
+// 编译进程序的表结构迁移，按版本号顺序执行，已执行的版本记录在 schema_migrations 中。
+// 迁移文件在 migrations/ 下，文件名为 <四位版本号>_<名称>.sql（name 为去掉 .sql 的文件名）；
+// 已发布的迁移不要再修改，改表结构时加新文件。
+// 迁移 SQL 需要同时兼容 PostgreSQL 和 openGauss：不用 ADD COLUMN IF NOT EXISTS 和 EXECUTE FUNCTION
+
+#[derive(Debug)]
+pub struct Migration {
+    pub version: i32,
+    pub name: &'static str,
+    pub sql: &'static str,
+}
+
+macro_rules! migration {
+    ($version:literal, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            sql: include_str!(concat!("../../migrations/", $name, ".sql")),
+        }
+    };
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_initial_schema"),
+    migration!(2, "0002_legacy_columns"),
+    migration!(3, "0003_article_counters"),
+    migration!(4, "0004_legacy_images_and_constraints"),
+    migration!(5, "0005_favorite_count_on_delete"),
+];
+
+impl Migration {
+    pub fn file_name(&self) -> String {
+        format!("{}.sql", self.name)
+    }
+
+    // 按 LF 换行计算，Windows 检出的 CRLF 文件不会被当成改动过
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.sql.replace("\r\n", "\n").as_bytes()))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppliedMigration {
+    pub version: i32,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+const CREATE_VERSION_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS schema_migrations (
+        version INTEGER PRIMARY KEY,
+        name VARCHAR(100) NOT NULL,
+        checksum CHAR(64) NOT NULL,
+        applied_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT CURRENT_TIMESTAMP
+    )";
+
+pub async fn get_applied_migrations(client: &Client) -> Result<Vec<AppliedMigration>, PgError> {
+    client.batch_execute(CREATE_VERSION_TABLE).await?;
+    let rows = client
+        .query("SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version", &[])
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| AppliedMigration {
+            version: row.get(0),
+            name: row.get(1),
+            checksum: row.get::<_, String>(2).trim().to_string(),
+            applied_at: row.get(3),
+        })
+        .collect())
+}
+
+// 对照已执行的版本找出待执行的迁移。数据库比程序新（有未知版本），
+// 或已执行的迁移文件被改过时报错，不自动处理
+pub fn pending_migrations(applied: &[AppliedMigration]) -> anyhow::Result<Vec<&'static Migration>> {
+    for record in applied {
+        let Some(migration) = MIGRATIONS.iter().find(|m| m.version == record.version) else {
+            anyhow::bail!(
+                "database has migration {} ({}) which this build does not know; upgrade the program",
+                record.version,
+                record.name
+            );
+        };
+        if migration.checksum() != record.checksum {
+            anyhow::bail!(
+                "migration {} was changed after it was applied; add a new migration instead of editing it",
+                migration.file_name()
+            );
+        }
+    }
+    Ok(MIGRATIONS
+        .iter()
+        .filter(|m| applied.iter().all(|record| record.version != m.version))
+        .collect())
+}
+
+// 依次执行待执行的迁移，每个迁移和它的版本记录在同一个事务中提交。
+// 事务内锁住 schema_migrations，多个实例同时启动时只有一个会执行，其余的等它提交后跳过
+pub async fn run_migrations(client: &mut Client) -> anyhow::Result<Vec<&'static Migration>> {
+    let pending = pending_migrations(&get_applied_migrations(client).await?)?;
+    let mut executed = Vec::new();
+    for migration in pending {
+        let transaction = client.transaction().await?;
+        transaction.batch_execute("LOCK TABLE schema_migrations IN EXCLUSIVE MODE").await?;
+        let already_applied = transaction
+            .query_opt("SELECT 1 FROM schema_migrations WHERE version = $1", &[&migration.version])
+            .await?
+            .is_some();
+        if already_applied {
+            continue;
+        }
+        log::info!("Applying migration {}", migration.file_name());
+        transaction
+            .batch_execute(migration.sql)
+            .await
+            .map_err(|e| anyhow::anyhow!("migration {} failed: {}", migration.file_name(), e))?;
+        transaction
+            .execute(
+                "INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)",
+                &[&migration.version, &migration.name, &migration.checksum()],
+            )
+            .await?;
+        transaction.commit().await?;
+        executed.push(migration);
+    }
+    Ok(executed)
+}
@@ -1,6 +1,13 @@
 pub mod article_queries;
+pub mod category_queries;
 pub mod comment_queries;
 pub mod connection;
+pub mod crawl_queries;
 pub mod feedback_queries;
+pub mod image_queries;
 pub mod interaction_queries;
+pub mod migrations;
+pub mod search_queries;
+pub mod tag_queries;
+pub mod unit_of_work;
 pub mod user_queries;
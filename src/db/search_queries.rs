@@ -0,0 +1,130 @@
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, Error as PgError};
+use uuid::Uuid;
+
+use crate::db::article_queries::{article_from_row, ArticleFilter, ARTICLE_COLUMNS, VISIBLE_CONDITION};
+use crate::models::article::Article;
+
+// BM25 参数
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+// 保存一篇文章的索引词频和文档长度，覆盖此前的索引
+pub async fn replace_search_terms(client: &Client, article_id: Uuid, terms: &[(String, i32)], length: i32) -> Result<(), PgError> {
+    client.execute("DELETE FROM article_search_terms WHERE article_id = $1", &[&article_id]).await?;
+    let (words, counts): (Vec<&str>, Vec<i32>) = terms.iter().map(|(term, tf)| (term.as_str(), *tf)).unzip();
+    client.execute(
+        "INSERT INTO article_search_terms (term, article_id, tf) SELECT t.term, $1, t.tf FROM unnest($2::text[], $3::int[]) AS t(term, tf)",
+        &[&article_id, &words, &counts]
+    ).await?;
+    client.execute(
+        "INSERT INTO article_search_docs (article_id, length) VALUES ($1, $2) \
+         ON CONFLICT (article_id) DO UPDATE SET length = EXCLUDED.length",
+        &[&article_id, &length]
+    ).await?;
+    Ok(())
+}
+
+// 检索词命中的文章及 BM25 得分的公用 CTE；terms 参数编号为 $terms_param
+fn scores_cte(terms_param: usize) -> String {
+    format!(
+        "WITH query_terms AS (SELECT DISTINCT unnest(${p}::text[]) AS term), \
+         corpus AS (SELECT COUNT(*)::float8 AS n, COALESCE(AVG(length), 1)::float8 AS avg_length FROM article_search_docs), \
+         term_df AS (SELECT s.term, COUNT(*)::float8 AS df FROM article_search_terms s JOIN query_terms q ON q.term = s.term GROUP BY s.term), \
+         scores AS ( \
+             SELECT s.article_id, COUNT(*) AS matched_terms, \
+                    SUM(ln(1 + (c.n - d.df + 0.5) / (d.df + 0.5)) * s.tf * ({k1} + 1) \
+                        / (s.tf + {k1} * (1 - {b} + {b} * doc.length / c.avg_length)))::float8 AS score \
+             FROM article_search_terms s \
+             JOIN term_df d ON d.term = s.term \
+             JOIN article_search_docs doc ON doc.article_id = s.article_id \
+             CROSS JOIN corpus c \
+             GROUP BY s.article_id \
+         ) ",
+        p = terms_param,
+        k1 = BM25_K1,
+        b = BM25_B
+    )
+}
+
+// 按相关度检索文章：命中的检索词越多越靠前，其次按 BM25 得分；返回文章和得分
+pub async fn search_articles(
+    client: &Client,
+    terms: &[String],
+    filter: &ArticleFilter,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<(Article, f64)>, PgError> {
+    let (where_clause, mut params) = filter.where_clause();
+    let first = params.len() + 1;
+    let query_string = format!(
+        "{} SELECT {}, scores.score FROM articles JOIN scores ON scores.article_id = articles.id {} \
+         ORDER BY scores.matched_terms DESC, scores.score DESC, published_at DESC NULLS LAST, id \
+         LIMIT ${} OFFSET ${}",
+        scores_cte(first),
+        ARTICLE_COLUMNS,
+        where_clause,
+        first + 1,
+        first + 2
+    );
+    params.push(&terms as &(dyn ToSql + Sync));
+    params.push(&limit);
+    params.push(&offset);
+
+    let rows = client.query(&query_string, &params).await?;
+    Ok(rows.iter().map(|row| (article_from_row(row), row.get("score"))).collect())
+}
+
+// 命中检索词且符合筛选条件的文章总数
+pub async fn count_search_results(client: &Client, terms: &[String], filter: &ArticleFilter) -> Result<i64, PgError> {
+    let (where_clause, mut params) = filter.where_clause();
+    let query_string = format!(
+        "{} SELECT COUNT(*) FROM articles JOIN scores ON scores.article_id = articles.id {}",
+        scores_cte(params.len() + 1),
+        where_clause
+    );
+    params.push(&terms as &(dyn ToSql + Sync));
+    let row = client.query_one(&query_string, &params).await?;
+    Ok(row.get(0))
+}
+
+// 还没有建立索引的文章 (id, 标题, 正文)，按 id 分批读取，after 为上一批最后一篇的 id
+pub async fn get_unindexed_articles(client: &Client, after: Option<Uuid>, limit: i64) -> Result<Vec<(Uuid, String, String)>, PgError> {
+    let rows = client.query(
+        "SELECT a.id, a.title, COALESCE(a.content, '') FROM articles a \
+         WHERE NOT EXISTS (SELECT 1 FROM article_search_docs d WHERE d.article_id = a.id) \
+           AND ($1::uuid IS NULL OR a.id > $1) \
+         ORDER BY a.id LIMIT $2",
+        &[&after, &limit]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect())
+}
+
+// 记录一次搜索（只记录查询串和结果数）
+pub async fn log_search_query(client: &Client, query: &str, result_count: i32) -> Result<(), PgError> {
+    client.execute(
+        "INSERT INTO search_query_log (query, result_count) VALUES ($1, $2)",
+        &[&query, &result_count]
+    ).await?;
+    Ok(())
+}
+
+// 最近 days 天内有结果的搜索词及搜索次数，按次数从多到少
+pub async fn get_popular_queries(client: &Client, days: i32, limit: i64) -> Result<Vec<(String, i64)>, PgError> {
+    let rows = client.query(
+        "SELECT query, COUNT(*) AS searches FROM search_query_log \
+         WHERE result_count > 0 AND created_at >= NOW() - make_interval(days => $1) \
+         GROUP BY query ORDER BY searches DESC, query LIMIT $2",
+        &[&days, &limit]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
+
+// 最新的可见文章的标题
+pub async fn get_recent_titles(client: &Client, limit: i64) -> Result<Vec<String>, PgError> {
+    let rows = client.query(
+        &format!("SELECT title FROM articles WHERE {} ORDER BY crawled_at DESC LIMIT $1", VISIBLE_CONDITION),
+        &[&limit]
+    ).await?;
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
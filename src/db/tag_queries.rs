@@ -0,0 +1,44 @@
+use tokio_postgres::{Client, Error as PgError};
+use uuid::Uuid;
+
+use crate::db::article_queries::VISIBLE_CONDITION;
+use crate::services::tagging_service::ExtractedTag;
+
+// 保存文章标签，覆盖该文章此前的标签
+pub async fn replace_article_tags(client: &Client, article_id: Uuid, tags: &[ExtractedTag]) -> Result<(), PgError> {
+    client.execute("DELETE FROM article_tags WHERE article_id = $1", &[&article_id]).await?;
+    for tag in tags {
+        client.execute(
+            "INSERT INTO article_tags (article_id, tag, kind, weight) VALUES ($1, $2, $3, $4) \
+             ON CONFLICT (article_id, tag, kind) DO NOTHING",
+            &[&article_id, &tag.tag, &tag.kind.as_str(), &tag.weight]
+        ).await?;
+    }
+    Ok(())
+}
+
+// 还没有标签的文章 (id, 标题, 正文)，按 id 分批读取，after 为上一批最后一篇的 id
+pub async fn get_untagged_articles(client: &Client, after: Option<Uuid>, limit: i64) -> Result<Vec<(Uuid, String, String)>, PgError> {
+    let rows = client.query(
+        "SELECT a.id, a.title, COALESCE(a.content, '') FROM articles a \
+         WHERE NOT EXISTS (SELECT 1 FROM article_tags t WHERE t.article_id = a.id) \
+           AND ($1::uuid IS NULL OR a.id > $1) \
+         ORDER BY a.id LIMIT $2",
+        &[&after, &limit]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1), row.get(2))).collect())
+}
+
+// 各标签（不分种类）标注的可见文章数，按文章数从多到少
+pub async fn get_tag_counts(client: &Client, limit: i64) -> Result<Vec<(String, i64)>, PgError> {
+    let rows = client.query(
+        &format!(
+            "SELECT t.tag, COUNT(DISTINCT t.article_id) AS articles FROM article_tags t \
+             JOIN articles ON articles.id = t.article_id WHERE {} \
+             GROUP BY t.tag ORDER BY articles DESC, t.tag LIMIT $1",
+            VISIBLE_CONDITION
+        ),
+        &[&limit]
+    ).await?;
+    Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+}
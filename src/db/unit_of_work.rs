@@ -0,0 +1,91 @@
+use std::ops::Deref;
+
+use deadpool_postgres::Object;
+use tokio_postgres::Client;
+
+use crate::db::connection::DbPool;
+use crate::errors::ServiceError;
+
+// 一次由多条语句组成的操作：从连接池取一个连接，在它上面开一个事务，commit 之后才生效。
+// commit 之前出错返回（包括 ? 提前返回）时整体回滚。
+// 事务期间通过 Deref 得到 &Client，db 模块里接受 &Client 的查询函数可以直接传入 &uow 使用
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    // 每条语句看到执行时已提交的数据；配合 FOR UPDATE 行锁做“先检查再修改”，拿到锁之后的语句能看到并发事务的结果
+    ReadCommitted,
+    // 整个事务读同一个快照；用于一次读多张表、要求结果彼此一致的只读操作
+    RepeatableRead,
+}
+
+impl Isolation {
+    // START TRANSACTION 的写法 PostgreSQL 和 openGauss 都支持
+    pub fn start_statement(self, read_only: bool) -> String {
+        let level = match self {
+            Isolation::ReadCommitted => "READ COMMITTED",
+            Isolation::RepeatableRead => "REPEATABLE READ",
+        };
+        let mode = if read_only { "READ ONLY" } else { "READ WRITE" };
+        format!("START TRANSACTION ISOLATION LEVEL {}, {}", level, mode)
+    }
+}
+
+pub struct UnitOfWork {
+    client: Option<Object>, // commit 后为 None
+}
+
+impl UnitOfWork {
+    pub async fn begin(pool: &DbPool, isolation: Isolation) -> Result<Self, ServiceError> {
+        Self::start(pool, isolation.start_statement(false)).await
+    }
+
+    pub async fn read_only(pool: &DbPool, isolation: Isolation) -> Result<Self, ServiceError> {
+        Self::start(pool, isolation.start_statement(true)).await
+    }
+
+    async fn start(pool: &DbPool, statement: String) -> Result<Self, ServiceError> {
+        let client = pool.get().await.map_err(|e| {
+            log::error!("Failed to get DB client from pool: {}", e);
+            ServiceError::InternalServerError("Database connection error".to_string())
+        })?;
+        client.batch_execute(&statement).await?;
+        Ok(UnitOfWork { client: Some(client) })
+    }
+
+    pub async fn commit(mut self) -> Result<(), ServiceError> {
+        // COMMIT 失败时 self 被丢弃，走 Drop 中的回滚
+        self.batch_execute("COMMIT").await?;
+        // 事务已结束，连接直接还给连接池
+        drop(self.client.take());
+        Ok(())
+    }
+}
+
+impl Deref for UnitOfWork {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client.as_ref().expect("unit of work used after commit")
+    }
+}
+
+impl Drop for UnitOfWork {
+    // Drop 中不能等待：在后台执行 ROLLBACK，成功后连接才回到连接池；
+    // 回滚失败或不在 tokio 运行时中时丢弃这个连接，不把未结束的事务留给下一个使用者
+    fn drop(&mut self) {
+        let Some(client) = self.client.take() else {
+            return;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = client.batch_execute("ROLLBACK").await {
+                        log::error!("Failed to roll back transaction: {}", e);
+                        drop(Object::take(client));
+                    }
+                });
+            }
+            Err(_) => drop(Object::take(client)),
+        }
+    }
+}
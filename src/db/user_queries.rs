@@ -101,6 +101,28 @@ pub async fn find_user_by_id(client: &Client, user_id: Uuid) -> Result<Option<Us
     }
 }
 
+// 读取用户并锁住这一行直到事务结束（注销账号时使用）
+pub async fn lock_user_by_id(client: &Client, user_id: Uuid) -> Result<Option<User>, ServiceError> {
+    let row_option = client.query_opt(
+        "SELECT id, username, email, password_hash, created_at, updated_at FROM users WHERE id = $1 FOR UPDATE",
+        &[&user_id]
+    ).await?;
+
+    Ok(row_option.map(|row| User {
+        id: row.get(0),
+        username: row.get(1),
+        email: row.get(2),
+        password_hash: row.get(3),
+        created_at: row.get(4),
+        updated_at: row.get(5),
+    }))
+}
+
+// 删除用户；互动、收藏、评论、阅读历史和反馈随外键级联删除
+pub async fn delete_user(client: &Client, user_id: Uuid) -> Result<u64, ServiceError> {
+    Ok(client.execute("DELETE FROM users WHERE id = $1", &[&user_id]).await?)
+}
+
 // Function to update a user's profile
 // Only email and password can be updated for now. Username is fixed.
 pub async fn update_user_profile(
@@ -0,0 +1,9 @@
+// 项目模块；main.rs 与 tests/ 下的集成测试共用
+pub mod api;
+pub mod config;
+pub mod db;
+pub mod errors;
+pub mod models;
+pub mod services;
+pub mod utils;
+pub mod visualization;
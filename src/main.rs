@@ -1,30 +1,34 @@
 use actix_files::{Files, NamedFile};
-use actix_web::{web, App, HttpRequest, HttpServer, middleware::Logger};
+use actix_web::{web, App, HttpRequest, HttpServer, middleware::{Compress, Logger}};
 use dotenv::dotenv;
+use std::sync::Arc;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 // 导入项目模块
-mod api;
-mod config;
-mod db;
-mod errors;
-mod models;
-mod services;
-mod utils;
-mod visualization;
-
-use crate::config::app_config::Config;
-use crate::db::connection::create_pool;
-use crate::api::auth_handler;
-use crate::api::user_handler;
-use crate::api::news_handler;
-use crate::api::recommendation_handler;
-use crate::api::feedback_handler;
-use crate::api::comment_handler;
-use crate::api::interaction_handler;
-use crate::api::system_info_handler;
-use crate::services::crawler_service::init_crawler_scheduler;
+use news_recommendation_system::{api, errors, models, services};
+use news_recommendation_system::config::app_config::{Config, StorageConfig};
+use news_recommendation_system::db::connection::{create_pool, DbPool};
+use news_recommendation_system::db::migrations::{get_applied_migrations, pending_migrations, run_migrations};
+use news_recommendation_system::api::auth_handler;
+use news_recommendation_system::api::user_handler;
+use news_recommendation_system::api::news_handler;
+use news_recommendation_system::api::recommendation_handler;
+use news_recommendation_system::api::feedback_handler;
+use news_recommendation_system::api::comment_handler;
+use news_recommendation_system::api::interaction_handler;
+use news_recommendation_system::api::system_info_handler;
+use news_recommendation_system::api::crawler_handler;
+use news_recommendation_system::api::category_handler;
+use news_recommendation_system::services::crawler_service::init_crawler_scheduler;
+use news_recommendation_system::services::image_service::migrate_storage;
+use news_recommendation_system::services::classifier_service::train_classifier;
+use news_recommendation_system::services::tagging_service::tag_untagged_articles;
+use news_recommendation_system::services::summary_service::summarize_missing_articles;
+use news_recommendation_system::services::search_service::index_missing_articles;
+use news_recommendation_system::services::interaction_service::repair_article_counters;
+use news_recommendation_system::services::suggest_service::rebuild_suggestions;
+use news_recommendation_system::services::storage::{build_storage, ImageStorage};
 use api::viz::{category_heat, model_performance, user_interest};
 
 // --- OpenAPI Documentation Setup ---
@@ -35,8 +39,24 @@ use api::viz::{category_heat, model_performance, user_interest};
         api::auth_handler::login_user_handler,
         api::user_handler::get_my_profile_handler,
         api::user_handler::update_my_profile_handler,
+        api::user_handler::delete_my_account_handler,
         api::news_handler::get_articles_handler,
+        api::news_handler::list_articles_handler,
         api::news_handler::get_article_by_id_handler,
+        api::news_handler::get_article_images_handler,
+        api::news_handler::update_article_categories_handler,
+        api::news_handler::update_article_handler,
+        api::news_handler::delete_article_handler,
+        api::news_handler::hide_article_handler,
+        api::news_handler::unhide_article_handler,
+        api::news_handler::get_article_revisions_handler,
+        api::news_handler::recrawl_article_handler,
+        api::news_handler::get_classifier_agreement_handler,
+        api::news_handler::get_articles_by_tag_handler,
+        api::news_handler::search_articles_handler,
+        api::news_handler::search_suggest_handler,
+        api::news_handler::get_image_handler,
+        api::news_handler::get_image_by_id_handler,
         api::recommendation_handler::get_recommendations_handler,
         api::feedback_handler::submit_feedback_handler,
         api::feedback_handler::get_feedback_for_article_handler,
@@ -52,6 +72,17 @@ use api::viz::{category_heat, model_performance, user_interest};
         api::interaction_handler::record_reading_history_handler,
         api::interaction_handler::get_user_feedback_history_handler,
         api::system_info_handler::get_system_status_handler,
+        api::crawler_handler::get_crawl_runs_handler,
+        api::crawler_handler::get_crawler_status_handler,
+        api::crawler_handler::trigger_crawl_handler,
+        api::crawler_handler::pause_crawler_handler,
+        api::crawler_handler::resume_crawler_handler,
+        api::crawler_handler::update_crawl_schedule_handler,
+        api::category_handler::get_categories_handler,
+        api::category_handler::create_category_handler,
+        api::category_handler::update_category_handler,
+        api::category_handler::reclassify_articles_handler,
+        api::category_handler::merge_category_handler,
     ),
     components(
         schemas(
@@ -61,7 +92,32 @@ use api::viz::{category_heat, model_performance, user_interest};
             models::user::User,
             models::user::UserProfileResponse,
             models::user::UpdateUserProfileSchema,
+            models::user::DeleteAccountSchema,
             models::article::Article,
+            models::article::ArticleSort,
+            models::article::ArticleSummary,
+            models::article::ArticleFields,
+            models::article::ArticleListItem,
+            models::article::ArticleImageResponse,
+            models::article::ThumbnailUrl,
+            models::article::UpdateArticleCategoriesSchema,
+            models::article::UpdateArticleSchema,
+            models::article::ManagedArticle,
+            models::article::ArticleRevision,
+            models::article::RevisionSource,
+            models::article::RecrawlResponse,
+            models::article::ArticleTag,
+            models::article::TagKind,
+            models::article::SearchHit,
+            models::article::SearchResponse,
+            models::article::SearchSuggestion,
+            models::article::SuggestionKind,
+            services::classifier_service::CategoryScore,
+            services::classifier_service::CategoryCount,
+            services::classifier_service::SourceCategoryAgreement,
+            services::classifier_service::ClassifierAgreement,
+            services::classifier_service::ClassifierAgreementReport,
+            services::image_service::FitMode,
             models::article::FeedbackData,
             models::article::Comment,
             models::article::CreateCommentSchema,
@@ -75,6 +131,23 @@ use api::viz::{category_heat, model_performance, user_interest};
             models::article::ReadingHistory,
             models::article::CreateReadingHistorySchema,
             models::article::UserFeedbackHistory,
+            models::crawl::CrawlRun,
+            models::crawl::CrawlSourceStats,
+            models::crawl::CrawlRunDetail,
+            models::crawl::SourceYieldAlert,
+            models::crawl::CrawlRunsReport,
+            models::crawl::CurrentCrawlRun,
+            models::crawl::CrawlerStatus,
+            models::crawl::TriggerCrawlSchema,
+            models::crawl::UpdateCrawlScheduleSchema,
+            models::category::Category,
+            models::category::CategoryNode,
+            models::category::CreateCategorySchema,
+            models::category::UpdateCategorySchema,
+            models::category::ReclassifyArticlesSchema,
+            models::category::ReclassifyArticlesResponse,
+            models::category::MergeCategorySchema,
+            models::category::MergeCategoryReport,
             errors::ErrorResponse
         ),
     ),
@@ -87,7 +160,9 @@ use api::viz::{category_heat, model_performance, user_interest};
         (name = "Comments", description = "User comments on articles"),
         (name = "Interactions", description = "User interactions with articles"),
         (name = "Favorites", description = "User favorites management"),
-        (name = "System", description = "System status and information")
+        (name = "System", description = "System status and information"),
+        (name = "Crawler", description = "Crawler run history, source health and admin controls"),
+        (name = "Categories", description = "Editorial category taxonomy and reclassification")
     ),
     security(
         ("bearer_auth" = [])
@@ -100,18 +175,213 @@ async fn viz_page(_req: HttpRequest) -> actix_web::Result<NamedFile> {
     Ok(NamedFile::open("./static/viz.html")?)
 }
 
+// 子命令使用的连接池
+async fn open_pool(app_config: &Config) -> anyhow::Result<DbPool> {
+    create_pool(&app_config.database_url)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to create database pool: {}", e))
+}
+
+// 执行编译进程序的表结构迁移
+async fn apply_migrations(db_pool: &DbPool) -> anyhow::Result<()> {
+    let mut client = db_pool.get().await?;
+    let executed = run_migrations(&mut client).await?;
+    if executed.is_empty() {
+        log::info!("Database schema is up to date");
+    } else {
+        log::info!("Applied {} migrations", executed.len());
+    }
+    Ok(())
+}
+
+// `migrate [status]`：执行待执行的表结构迁移；加 status 时只列出各迁移的执行情况
+async fn run_migrate(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    let status_only = match args {
+        [] => false,
+        [flag] if flag == "status" => true,
+        _ => anyhow::bail!("usage: migrate [status]"),
+    };
+
+    let db_pool = open_pool(app_config).await?;
+    if !status_only {
+        return apply_migrations(&db_pool).await;
+    }
+
+    let client = db_pool.get().await?;
+    let applied = get_applied_migrations(&client).await?;
+    for record in &applied {
+        log::info!("  {}: applied at {}", record.name, record.applied_at);
+    }
+    let pending = pending_migrations(&applied)?;
+    for migration in &pending {
+        log::info!("  {}: pending", migration.file_name());
+    }
+    log::info!("{} applied, {} pending", applied.len(), pending.len());
+    Ok(())
+}
+
+// `migrate-storage <from> <to>`：把图片文件从一个存储后端复制到另一个（local / s3），
+// 两端的连接参数都从环境变量读取
+async fn run_migrate_storage(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    let [from, to] = args else {
+        anyhow::bail!("usage: migrate-storage <local|s3> <local|s3>");
+    };
+    let from = build_storage(&StorageConfig::from_env(Some(from))?)?;
+    let to = build_storage(&StorageConfig::from_env(Some(to))?)?;
+    if from.describe() == to.describe() {
+        anyhow::bail!("source and target storage are the same ({})", from.describe());
+    }
+
+    let db_pool = open_pool(app_config).await?;
+    log::info!("Migrating images from {} to {}", from.describe(), to.describe());
+    let report = migrate_storage(&db_pool, from.as_ref(), to.as_ref()).await?;
+    log::info!(
+        "Storage migration finished: {} images, {} objects copied, {} already present, {} missing, {} paths rewritten",
+        report.images, report.copied, report.already_present, report.missing, report.paths_rewritten
+    );
+    Ok(())
+}
+
+// `train-classifier [--seed-from-existing]`：用人工标注的文章训练类别分类器并保存模型，
+// 加 --seed-from-existing 时把 articles.categories 中已有的类别也作为训练样本
+async fn run_train_classifier(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    let seed_from_existing = match args {
+        [] => false,
+        [flag] if flag == "--seed-from-existing" => true,
+        _ => anyhow::bail!("usage: train-classifier [--seed-from-existing]"),
+    };
+
+    let db_pool = open_pool(app_config).await?;
+    let report = train_classifier(&db_pool, seed_from_existing).await?;
+    for (category, count) in &report.categories {
+        log::info!("  {}: {} examples", category, count);
+    }
+    match report.holdout_accuracy {
+        Some(accuracy) => log::info!(
+            "Holdout top-1 accuracy: {:.1}% on {} articles",
+            accuracy * 100.0,
+            report.holdout_examples
+        ),
+        None => log::info!("Too few articles for a holdout evaluation"),
+    }
+    log::info!(
+        "Trained on {} articles ({} labeled manually, {} by source section, {} seeded), model saved to {}",
+        report.examples,
+        report.manual_examples,
+        report.source_examples,
+        report.seed_examples,
+        report.model_path.display()
+    );
+    Ok(())
+}
+
+// 补标签、补摘要、补检索索引和重算计数时每批读取的文章数
+const BACKFILL_BATCH: i64 = 200;
+
+// `tag-articles`：为升级前入库、还没有标签的文章抽取关键词和实体
+async fn run_tag_articles(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: tag-articles");
+    }
+    let db_pool = open_pool(app_config).await?;
+    let tagged = tag_untagged_articles(&db_pool, BACKFILL_BATCH).await?;
+    log::info!("Tagged {} articles", tagged);
+    Ok(())
+}
+
+// `summarize-articles`：为升级前入库、还没有摘要的文章生成摘要
+async fn run_summarize_articles(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: summarize-articles");
+    }
+    let db_pool = open_pool(app_config).await?;
+    let summarized = summarize_missing_articles(&db_pool, BACKFILL_BATCH).await?;
+    log::info!("Summarized {} articles", summarized);
+    Ok(())
+}
+
+// `index-articles`：为升级前入库、还没有检索索引的文章建立索引
+async fn run_index_articles(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: index-articles");
+    }
+    let db_pool = open_pool(app_config).await?;
+    let indexed = index_missing_articles(&db_pool, BACKFILL_BATCH).await?;
+    log::info!("Indexed {} articles", indexed);
+    Ok(())
+}
+
+// `recount-articles`：按互动、收藏和评论明细重算文章的计数，修正偏差
+async fn run_recount_articles(app_config: &Config, args: &[String]) -> anyhow::Result<()> {
+    if !args.is_empty() {
+        anyhow::bail!("usage: recount-articles");
+    }
+    let db_pool = open_pool(app_config).await?;
+    let repaired = repair_article_counters(&db_pool, BACKFILL_BATCH).await?;
+    log::info!("Corrected counters on {} articles", repaired);
+    Ok(())
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok();
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
     let app_config = Config::from_env().expect("Failed to load configuration");
+
+    // 带子命令时执行一次维护任务后退出，不启动服务
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some((command, rest)) = args.split_first() {
+        let result = match command.as_str() {
+            "migrate" => run_migrate(&app_config, rest).await,
+            "migrate-storage" => run_migrate_storage(&app_config, rest).await,
+            "tag-articles" => run_tag_articles(&app_config, rest).await,
+            "summarize-articles" => run_summarize_articles(&app_config, rest).await,
+            "index-articles" => run_index_articles(&app_config, rest).await,
+            "recount-articles" => run_recount_articles(&app_config, rest).await,
+            "train-classifier" => run_train_classifier(&app_config, rest).await,
+            _ => Err(anyhow::anyhow!(
+                "unknown command; expected one of migrate, migrate-storage, tag-articles, summarize-articles, \
+                 index-articles, recount-articles, train-classifier"
+            )),
+        };
+        if let Err(e) = result {
+            log::error!("{} failed: {:#}", command, e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let db_pool = create_pool(&app_config.database_url)
         .await
         .expect("Failed to create database pool");
 
-    init_crawler_scheduler(
+    if app_config.auto_migrate {
+        if let Err(e) = apply_migrations(&db_pool).await {
+            log::error!("Database migration failed: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+
+    let storage: Arc<dyn ImageStorage> = build_storage(&app_config.storage).expect("Failed to open image storage");
+    log::info!("Serving images from {}", storage.describe());
+
+    // 搜索自动补全的前缀树在后台构建，不阻塞启动
+    let suggestion_pool = db_pool.clone();
+    actix_web::rt::spawn(async move {
+        match suggestion_pool.get().await {
+            Ok(client) => {
+                if let Err(e) = rebuild_suggestions(&client).await {
+                    log::error!("Failed to build search suggestions: {}", e);
+                }
+            }
+            Err(e) => log::error!("DB Pool error while building search suggestions: {}", e),
+        }
+    });
+
+    let crawler = init_crawler_scheduler(
         db_pool.clone(),
+        app_config.storage.clone(),
         &app_config.crawler_cron_expression,
     )
     .await
@@ -123,7 +393,12 @@ async fn main() -> std::io::Result<()> {
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(db_pool.clone()))
+            .app_data(web::Data::new(app_config.clone()))
+            .app_data(web::Data::new(crawler.clone()))
+            .app_data(web::Data::from(storage.clone()))
             .wrap(Logger::default())
+            // 按 Accept-Encoding 用 gzip 或 brotli 压缩响应；图片已经是压缩格式，中间件会跳过
+            .wrap(Compress::default())
             // 注册 API 路由
             .configure(auth_handler::init_auth_routes)
             .configure(user_handler::init_user_profile_routes)
@@ -138,6 +413,8 @@ async fn main() -> std::io::Result<()> {
             .configure(comment_handler::init_comment_routes)
             .configure(interaction_handler::init_interaction_routes)
             .configure(system_info_handler::init_system_info_routes)
+            .configure(crawler_handler::init_crawler_routes)
+            .configure(category_handler::init_category_routes)
             // 注册 viz 数据接口
             .service(category_heat)
             .service(model_performance)
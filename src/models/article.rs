@@ -3,6 +3,8 @@ use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::pagination::Page;
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
 pub enum FeedbackType {
     Interested,
@@ -101,25 +103,268 @@ pub struct Article {
     pub id: Uuid,
     pub title: String,
     pub content: String,
+    pub content_html: Option<String>, // 清洗后的 HTML 正文，保留标题、列表、链接和图片
+    pub summary: Option<String>, // 抽取式摘要，供列表卡片显示
     pub url: String,
     pub source_name: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub crawled_at: DateTime<Utc>,
     pub categories: Option<Vec<String>>,
+    pub source_category: Option<String>, // 新闻源栏目给出的类别，用作分类器的参考标注
     pub like_count: Option<i32>,
     pub comment_count: Option<i32>,
     pub favorite_count: Option<i32>,
+    pub cover_image_id: Option<i32>, // 第一张图片，没有图片时为空
+    pub tags: Vec<ArticleTag>, // 关键词和人名、机构、地名，按权重从高到低
     // pub feature_vector: Option<Vec<f32>>, // Or bytea depending on DB storage
 }
 
+// 管理接口返回的文章，附带修改、隐藏和删除时间
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ManagedArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    pub updated_at: Option<DateTime<Utc>>, // 最近一次修改标题或正文的时间
+    pub hidden_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl ManagedArticle {
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
+
+// 修订来源
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum RevisionSource {
+    Crawl, // 重新抓取到的新版本
+    Admin, // 管理员编辑
+}
+
+impl RevisionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionSource::Crawl => "crawl",
+            RevisionSource::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "crawl" => Some(RevisionSource::Crawl),
+            "admin" => Some(RevisionSource::Admin),
+            _ => None,
+        }
+    }
+}
+
+// 文章被替换掉的一个旧版本
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct ArticleRevision {
+    pub id: i32,
+    pub article_id: Uuid,
+    pub title: String,
+    pub content: Option<String>,
+    pub content_html: Option<String>,
+    pub summary: Option<String>,
+    pub source: RevisionSource, // 替换这个版本的修改来源
+    pub edited_by: Option<Uuid>,
+    pub revised_at: DateTime<Utc>, // 被替换的时间
+}
+
+// 管理员编辑文章；没有给出的字段保持不变。只改正文不给 HTML 正文时清空旧的 HTML 正文，
+// 只改正文不给摘要时重新生成摘要
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct UpdateArticleSchema {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    pub content_html: Option<String>,
+    pub summary: Option<String>,
+}
+
+// 重新抓取的结果
+#[derive(Serialize, Debug, ToSchema)]
+pub struct RecrawlResponse {
+    pub changed: bool, // 抓取到的内容是否与库中不同；不同时已覆盖，旧版本存入修订历史
+    pub article: ManagedArticle,
+}
+
+// 标签种类：关键词或命名实体
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TagKind {
+    Keyword,
+    Person,
+    Organization,
+    Place,
+}
+
+impl TagKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TagKind::Keyword => "keyword",
+            TagKind::Person => "person",
+            TagKind::Organization => "organization",
+            TagKind::Place => "place",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "keyword" => Some(TagKind::Keyword),
+            "person" => Some(TagKind::Person),
+            "organization" => Some(TagKind::Organization),
+            "place" => Some(TagKind::Place),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct ArticleTag {
+    pub tag: String,
+    pub kind: TagKind,
+}
+
+// 文章列表的排序方式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ArticleSort {
+    #[default]
+    Newest,
+    MostLiked,
+    MostCommented,
+    MostFavorited,
+}
+
+// 列表卡片使用的封面缩略图宽度，须是预生成的缩略图宽度之一
+pub const LIST_THUMBNAIL_WIDTH: u32 = 640;
+
+pub fn thumbnail_url(image_id: i32) -> String {
+    format!("/api/v1/images/{}?w={}", image_id, LIST_THUMBNAIL_WIDTH)
+}
+
+// 列表接口返回的精简文章，不带正文、HTML 正文和标签
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct ArticleSummary {
+    pub id: Uuid,
+    pub title: String,
+    pub summary: Option<String>,
+    pub url: String,
+    pub source_name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub crawled_at: DateTime<Utc>,
+    pub categories: Option<Vec<String>>,
+    pub like_count: Option<i32>,
+    pub comment_count: Option<i32>,
+    pub favorite_count: Option<i32>,
+    pub thumbnail_url: Option<String>, // 第一张图片的缩略图地址，没有图片时为空
+}
+
+impl From<Article> for ArticleSummary {
+    fn from(article: Article) -> Self {
+        ArticleSummary {
+            id: article.id,
+            title: article.title,
+            summary: article.summary,
+            url: article.url,
+            source_name: article.source_name,
+            published_at: article.published_at,
+            crawled_at: article.crawled_at,
+            categories: article.categories,
+            like_count: article.like_count,
+            comment_count: article.comment_count,
+            favorite_count: article.favorite_count,
+            thumbnail_url: article.cover_image_id.map(thumbnail_url),
+        }
+    }
+}
+
+// 列表接口的 fields 参数：summary 只返回精简字段（默认），full 返回与详情接口相同的完整文章
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleFields {
+    #[default]
+    Summary,
+    Full,
+}
+
+// 列表中的一项，形状由 fields 参数决定
+#[derive(Serialize, Debug, Clone, ToSchema)]
+#[serde(untagged)]
+pub enum ArticleListItem {
+    Summary(ArticleSummary),
+    Full(Article),
+}
+
+// 一条检索结果；标题和摘录已转义 HTML，命中的检索词用 <em> 标出
+#[derive(Serialize, Debug, ToSchema)]
+pub struct SearchHit {
+    pub article: ArticleSummary, // 与列表接口默认的精简字段相同
+    pub score: f64, // BM25 相关度
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct SearchResponse {
+    pub query: String,
+    pub terms: Vec<String>, // 查询串分词后实际使用的检索词
+    #[serde(flatten)]
+    pub results: Page<SearchHit>, // 与其他列表相同的分页字段，检索结果在 items 中
+}
+
+// 自动补全候选的来源
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SuggestionKind {
+    Title, // 文章标题
+    Tag, // 文章的关键词或人名、机构、地名
+    Query, // 热门搜索词
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct SearchSuggestion {
+    pub text: String,
+    pub kind: SuggestionKind,
+    pub score: f64, // 越大越靠前
+}
+
+// 文章中的一张图片；文件按内容哈希存储，见 services::image_service
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NewsImage {
     pub id: i32,
+    pub article_id: Uuid,
+    pub position: i32, // 在文章中的顺序，从 0 开始
+    pub source_url: String, // 原始图片地址
+    pub caption: Option<String>,
+    pub content_hash: String,
+    pub content_type: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub storage_path: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ThumbnailUrl {
+    pub width: u32,
     pub url: String,
-    //pub image_data: Vec<u8>,
+}
+
+// 返回给前端的图片信息
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct ArticleImageResponse {
+    pub id: i32,
+    pub position: i32,
+    pub caption: Option<String>,
     pub content_type: String,
-    pub file_path: String,
-    // article_id: Uuid,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub url: String,
+    pub thumbnails: Vec<ThumbnailUrl>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -254,3 +499,9 @@ pub struct UserInteractionWithArticle {
     pub article_title: String,
     pub article_url: String,
 }
+
+// 人工标注文章类别（管理员），用作分类器的训练数据
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct UpdateArticleCategoriesSchema {
+    pub categories: Vec<String>,
+}
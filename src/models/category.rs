@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// 类别表中的一个类别。文章上保存的是 name_zh
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct Category {
+    pub id: i32,
+    pub slug: String,
+    pub name_zh: String,
+    pub name_en: String,
+    pub parent_id: Option<i32>,
+    pub position: i32,
+    pub icon: Option<String>,
+    pub aliases: Vec<String>,
+}
+
+// 类别树中的一个节点，子类别按 position 排序
+#[derive(Serialize, Debug, Clone, ToSchema)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    #[schema(no_recursion)]
+    pub children: Vec<CategoryNode>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct CreateCategorySchema {
+    pub slug: String,
+    pub name_zh: String,
+    pub name_en: String,
+    pub parent: Option<String>, // 父类别的 id、slug、名称或别名
+    pub position: Option<i32>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+// 未给出的字段保持不变；aliases 给出时整体替换
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct UpdateCategorySchema {
+    pub name_zh: Option<String>,
+    pub name_en: Option<String>,
+    pub parent: Option<String>, // 空字符串表示移到顶层
+    pub position: Option<i32>,
+    pub icon: Option<String>,
+    pub aliases: Option<Vec<String>>,
+}
+
+// 批量重新分类：把这些文章的类别设为 categories，并记为人工标注
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct ReclassifyArticlesSchema {
+    pub article_ids: Vec<Uuid>,
+    pub categories: Vec<String>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ReclassifyArticlesResponse {
+    pub updated: usize,
+    pub not_found: Vec<Uuid>,
+    pub categories: Vec<String>, // 解析后的类别名称
+}
+
+// 把一个类别合并到另一个类别
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct MergeCategorySchema {
+    pub into: String, // 目标类别的 id、slug、名称或别名
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct MergeCategoryReport {
+    pub from: String,
+    pub into: String,
+    pub articles_updated: u64,
+    pub labels_moved: u64,
+    pub children_moved: u64,
+}
@@ -0,0 +1,93 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+// 一次爬虫运行的汇总记录
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CrawlRun {
+    pub id: Uuid,
+    pub triggered_by: String, // 'schedule', 'manual'
+    pub status: String, // 'running', 'completed', 'failed'
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub urls_found: i32,
+    pub articles_inserted: i32,
+    pub articles_updated: i32, // 已有文章内容有变化并已更新
+    pub duplicates: i32, // 已有且内容没有变化的文章
+    pub parse_failures: i32,
+    pub http_errors: i32,
+}
+
+// 单次运行中某个新闻源（列表页 + 链接选择器）的统计
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CrawlSourceStats {
+    pub id: Uuid,
+    pub run_id: Uuid,
+    pub source_name: String,
+    pub listing_url: String,
+    pub link_selector: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub urls_found: i32,
+    pub articles_inserted: i32,
+    pub articles_updated: i32, // 已有文章内容有变化并已更新
+    pub duplicates: i32, // 已有且内容没有变化的文章
+    pub parse_failures: i32,
+    pub http_errors: i32,
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CrawlRunDetail {
+    pub run: CrawlRun,
+    pub sources: Vec<CrawlSourceStats>,
+}
+
+// 最近一次运行产出为零、但此前有过产出的新闻源
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct SourceYieldAlert {
+    pub source_name: String,
+    pub listing_url: String,
+    pub link_selector: String,
+    pub last_run_id: Uuid,
+    pub last_run_at: DateTime<Utc>,
+    pub last_urls_found: i32,
+    pub last_parse_failures: i32,
+    pub last_http_errors: i32,
+    pub last_error: Option<String>,
+    pub last_productive_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CrawlRunsReport {
+    pub runs: Vec<CrawlRunDetail>,
+    pub zero_yield_sources: Vec<SourceYieldAlert>,
+}
+
+// 正在进行的爬取
+#[derive(Serialize, Deserialize, Debug, Clone, ToSchema)]
+pub struct CurrentCrawlRun {
+    pub trigger: String,
+    pub source: Option<String>, // None 表示全部新闻源
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, ToSchema)]
+pub struct CrawlerStatus {
+    pub running: bool,
+    pub current_run: Option<CurrentCrawlRun>,
+    pub paused: bool,
+    pub cron_expression: String,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct TriggerCrawlSchema {
+    pub source: Option<String>, // 新闻源名称，例如 "people"；省略则爬取全部
+}
+
+#[derive(Deserialize, Debug, ToSchema)]
+pub struct UpdateCrawlScheduleSchema {
+    pub cron_expression: String,
+}
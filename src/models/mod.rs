@@ -1,2 +1,5 @@
 pub mod user;
 pub mod article;
+pub mod crawl;
+pub mod category;
+pub mod pagination;
@@ -0,0 +1,137 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::errors::ServiceError;
+use crate::utils::cursor::{parse_cursor, split_page};
+
+// 列表接口统一的分页参数和返回格式。页码从 1 开始；
+// 支持游标的列表也可以传上一页返回的 next_cursor 翻页（不能与 page 同时使用）。
+// 处理函数用 Pagination 提取器取得校验过的参数，见 api::pagination。
+
+pub const DEFAULT_PAGE_SIZE: i64 = 20;
+pub const MAX_PAGE_SIZE: i64 = 100;
+// 按页码翻页的最大页数，更深的翻页用游标
+pub const MAX_PAGE: i64 = 1000;
+
+// 分页查询参数原文，校验后得到 Pagination
+#[derive(Deserialize, Debug, Default, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PaginationQuery {
+    pub page: Option<i64>, // 从 1 开始，默认 1，最大 1000
+    pub limit: Option<i64>, // 每页条数，默认 20，最大 100
+    pub cursor: Option<String>, // 上一页返回的 next_cursor
+}
+
+impl PaginationQuery {
+    pub fn validate(self) -> Result<Pagination, ServiceError> {
+        let page = self.page.unwrap_or(1);
+        if !(1..=MAX_PAGE).contains(&page) {
+            return Err(ServiceError::BadRequest(format!("page must be between 1 and {}", MAX_PAGE)));
+        }
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(ServiceError::BadRequest(format!("limit must be between 1 and {}", MAX_PAGE_SIZE)));
+        }
+        let cursor = self.cursor.filter(|c| !c.trim().is_empty());
+        if cursor.is_some() && self.page.is_some() {
+            return Err(ServiceError::BadRequest("page and cursor cannot be used together".to_string()));
+        }
+        Ok(Pagination { page, limit, cursor })
+    }
+}
+
+// 校验过的分页参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pagination {
+    page: i64,
+    limit: i64,
+    cursor: Option<String>,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination { page: 1, limit: DEFAULT_PAGE_SIZE, cursor: None }
+    }
+}
+
+impl Pagination {
+    pub fn page(&self) -> i64 {
+        self.page
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.limit
+    }
+
+    // 解析游标，游标里的排序键由各个列表决定
+    pub fn cursor<T: DeserializeOwned>(&self) -> Result<Option<T>, ServiceError> {
+        parse_cursor(self.cursor.as_deref())
+    }
+
+    // 不支持游标的列表（如按相关度排序的检索结果）调用
+    pub fn reject_cursor(&self) -> Result<(), ServiceError> {
+        match self.cursor {
+            Some(_) => Err(ServiceError::BadRequest("This list does not support cursor pagination".to_string())),
+            None => Ok(()),
+        }
+    }
+
+    // 给出游标时从游标之后开始，不再按页码偏移
+    pub fn offset(&self) -> i64 {
+        if self.cursor.is_some() {
+            0
+        } else {
+            (self.page - 1) * self.limit
+        }
+    }
+
+    // 查询时多取一行，用来判断是否还有下一页
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+
+    // 由按 fetch_limit 取到的行生成一页，key 给出下一页游标的排序键
+    pub fn page_of<T, K: Serialize>(&self, rows: Vec<T>, total: i64, key: impl Fn(&T) -> K) -> Page<T> {
+        let (items, next_cursor) = split_page(rows, self.limit, key);
+        Page {
+            items,
+            page: self.page,
+            limit: self.limit,
+            total,
+            has_more: next_cursor.is_some(),
+            next_cursor,
+        }
+    }
+
+    // 同上，用于不支持游标的列表
+    pub fn page_without_cursor<T>(&self, mut rows: Vec<T>, total: i64) -> Page<T> {
+        let has_more = rows.len() as i64 > self.limit;
+        rows.truncate(self.limit as usize);
+        Page { items: rows, page: self.page, limit: self.limit, total, has_more, next_cursor: None }
+    }
+}
+
+// 列表接口统一的返回格式
+#[derive(Serialize, Debug, ToSchema)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub page: i64,
+    pub limit: i64,
+    pub total: i64, // 符合条件的总条数
+    pub has_more: bool,
+    pub next_cursor: Option<String>, // 传给 cursor 参数取下一页；没有下一页或列表不支持游标时为空
+}
+
+impl<T> Page<T> {
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            page: self.page,
+            limit: self.limit,
+            total: self.total,
+            has_more: self.has_more,
+            next_cursor: self.next_cursor,
+        }
+    }
+}
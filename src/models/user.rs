@@ -97,3 +97,10 @@ pub struct UpdateUserProfileSchema {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub password: Option<String>, // For changing password
 }
+
+// 注销账号时需要再次输入密码
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({ "password": "password123" }))]
+pub struct DeleteAccountSchema {
+    pub password: String,
+}
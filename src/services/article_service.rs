@@ -0,0 +1,140 @@
+use tokio_postgres::Client;
+use uuid::Uuid;
+
+use crate::db::article_queries::{
+    find_managed_article, get_article_revisions, revise_article, set_article_hidden, soft_delete_article, ArticleContent,
+};
+use crate::db::connection::DbPool;
+use crate::db::search_queries::replace_search_terms;
+use crate::db::tag_queries::replace_article_tags;
+use crate::errors::ServiceError;
+use crate::models::article::{Article, ArticleRevision, ManagedArticle, RecrawlResponse, RevisionSource, UpdateArticleSchema};
+use crate::services::crawler_service;
+use crate::services::search_service::index_terms;
+use crate::services::storage::ImageStorage;
+use crate::services::suggest_service::rebuild_suggestions;
+use crate::services::summary_service::summarize;
+use crate::services::tagging_service::extract_tags;
+use crate::utils::html_sanitizer;
+
+// 把管理员的修改合并到当前版本上，得到新版本
+pub fn apply_edit(current: &ArticleContent, edit: &UpdateArticleSchema, article_url: &str) -> Result<ArticleContent, ServiceError> {
+    if edit.title.is_none() && edit.content.is_none() && edit.content_html.is_none() && edit.summary.is_none() {
+        return Err(ServiceError::BadRequest("Nothing to update".to_string()));
+    }
+    let title = match edit.title.as_deref().map(str::trim) {
+        Some("") => return Err(ServiceError::BadRequest("Title must not be empty".to_string())),
+        Some(title) => title.to_string(),
+        None => current.title.clone(),
+    };
+    let content_changed = edit.content.as_ref().is_some_and(|content| *content != current.content);
+    let content = edit.content.clone().unwrap_or_else(|| current.content.clone());
+    // HTML 正文和正文不一致时阅读页会显示旧内容，所以只改正文时清空 HTML 正文
+    let content_html = match &edit.content_html {
+        Some(html) => Some(html_sanitizer::sanitize_article_html(html, article_url)).filter(|h| !h.is_empty()),
+        None if content_changed => None,
+        None => current.content_html.clone(),
+    };
+    let summary = match &edit.summary {
+        Some(summary) => Some(summary.trim().to_string()).filter(|s| !s.is_empty()),
+        None if content_changed => Some(summarize(&content)).filter(|s| !s.is_empty()),
+        None => current.summary.clone(),
+    };
+    Ok(ArticleContent { title, content, content_html, summary })
+}
+
+// 标题或正文变化后重建标签和检索索引
+async fn reindex_article(client: &Client, article: &Article) {
+    let tags = extract_tags(&article.title, &article.content);
+    if let Err(e) = replace_article_tags(client, article.id, &tags).await {
+        log::error!("Failed to store tags for {}: {}", article.id, e);
+    }
+    let (terms, length) = index_terms(&article.title, &article.content);
+    if let Err(e) = replace_search_terms(client, article.id, &terms, length).await {
+        log::error!("Failed to index {} for search: {}", article.id, e);
+    }
+}
+
+// 标题或可见性变化后更新自动补全
+async fn refresh_suggestions(client: &Client) {
+    if let Err(e) = rebuild_suggestions(client).await {
+        log::error!("Failed to rebuild search suggestions: {}", e);
+    }
+}
+
+// 管理接口要操作的文章；已删除的文章视为不存在
+async fn find_live_article(client: &Client, article_id: Uuid) -> Result<ManagedArticle, ServiceError> {
+    find_managed_article(client, article_id)
+        .await?
+        .filter(|article| !article.is_deleted())
+        .ok_or_else(|| ServiceError::NotFound("Article not found".to_string()))
+}
+
+pub async fn edit_article_service(
+    pool: &DbPool,
+    article_id: Uuid,
+    edit: &UpdateArticleSchema,
+    editor_id: Uuid,
+) -> Result<ManagedArticle, ServiceError> {
+    let client = pool.get().await?;
+    let current = find_live_article(&client, article_id).await?;
+    let revision = apply_edit(&ArticleContent::of(&current.article), edit, &current.article.url)?;
+    if let Some(article) = revise_article(&client, article_id, &revision, RevisionSource::Admin, Some(editor_id)).await? {
+        reindex_article(&client, &article).await;
+        if article.title != current.article.title {
+            refresh_suggestions(&client).await;
+        }
+    }
+    find_live_article(&client, article_id).await
+}
+
+pub async fn set_article_hidden_service(pool: &DbPool, article_id: Uuid, hidden: bool) -> Result<ManagedArticle, ServiceError> {
+    let client = pool.get().await?;
+    if !set_article_hidden(&client, article_id, hidden).await? {
+        return Err(ServiceError::NotFound("Article not found".to_string()));
+    }
+    refresh_suggestions(&client).await;
+    find_live_article(&client, article_id).await
+}
+
+pub async fn delete_article_service(pool: &DbPool, article_id: Uuid) -> Result<(), ServiceError> {
+    let client = pool.get().await?;
+    if !soft_delete_article(&client, article_id).await? {
+        return Err(ServiceError::NotFound("Article not found".to_string()));
+    }
+    refresh_suggestions(&client).await;
+    Ok(())
+}
+
+// 修订历史在文章删除后仍可查看
+pub async fn get_article_revisions_service(pool: &DbPool, article_id: Uuid) -> Result<Vec<ArticleRevision>, ServiceError> {
+    let client = pool.get().await?;
+    if find_managed_article(&client, article_id).await?.is_none() {
+        return Err(ServiceError::NotFound("Article not found".to_string()));
+    }
+    Ok(get_article_revisions(&client, article_id).await?)
+}
+
+pub async fn recrawl_article_service(
+    pool: &DbPool,
+    storage: &dyn ImageStorage,
+    article_id: Uuid,
+) -> Result<RecrawlResponse, ServiceError> {
+    let article = {
+        let client = pool.get().await?;
+        find_live_article(&client, article_id).await?.article
+    };
+    let source = crawler_service::find_news_source(&article.url, article.source_name.as_deref())
+        .ok_or_else(|| ServiceError::BadRequest("No news source is configured for this article".to_string()))?;
+    let changed = crawler_service::recrawl_article(pool, storage, &article.url, &source)
+        .await
+        .map_err(|e| {
+            log::error!("Failed to re-crawl {}: {}", article.url, e);
+            ServiceError::InternalServerError(format!("Re-crawl failed: {}", e))
+        })?;
+    let client = pool.get().await?;
+    if changed {
+        refresh_suggestions(&client).await;
+    }
+    Ok(RecrawlResponse { changed, article: find_live_article(&client, article_id).await? })
+}
@@ -0,0 +1,129 @@
+use tokio_postgres::{Client, Error as PgError};
+
+use std::collections::HashMap;
+
+use crate::db::category_queries::list_categories;
+use crate::models::category::{Category, CategoryNode};
+use crate::services::classifier_service::UNCATEGORIZED;
+
+// 类别表的内存视图。分类器输出、栏目类别、人工标注和前端传来的类别名都经它解析为 name_zh
+#[derive(Debug, Clone, Default)]
+pub struct CategoryTaxonomy {
+    categories: Vec<Category>,
+    lookup: HashMap<String, usize>, // 归一化后的 id / slug / 名称 / 别名 -> 下标
+}
+
+// 名称匹配不区分大小写和首尾空白
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+impl CategoryTaxonomy {
+    pub fn new(categories: Vec<Category>) -> Self {
+        let mut lookup = HashMap::new();
+        // 别名优先级最低：先登记别名，再用 id、slug 和名称覆盖
+        for (index, category) in categories.iter().enumerate() {
+            for alias in &category.aliases {
+                lookup.insert(normalize(alias), index);
+            }
+        }
+        for (index, category) in categories.iter().enumerate() {
+            for key in [category.id.to_string(), category.slug.clone(), category.name_en.clone(), category.name_zh.clone()] {
+                lookup.insert(normalize(&key), index);
+            }
+        }
+        CategoryTaxonomy { categories, lookup }
+    }
+
+    pub fn categories(&self) -> &[Category] {
+        &self.categories
+    }
+
+    // 按 id、slug、中英文名称或别名查找
+    pub fn resolve(&self, name: &str) -> Option<&Category> {
+        self.lookup.get(&normalize(name)).map(|&index| &self.categories[index])
+    }
+
+    pub fn get(&self, id: i32) -> Option<&Category> {
+        self.categories.iter().find(|c| c.id == id)
+    }
+
+    // 解析一组类别名，去重并保持顺序；无法解析的名称放进第二个返回值
+    pub fn canonicalize<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> (Vec<String>, Vec<String>) {
+        let mut resolved: Vec<String> = Vec::new();
+        let mut unknown = Vec::new();
+        for name in names {
+            match self.resolve(name) {
+                Some(category) if !resolved.contains(&category.name_zh) => resolved.push(category.name_zh.clone()),
+                Some(_) => {}
+                None => unknown.push(name.to_string()),
+            }
+        }
+        (resolved, unknown)
+    }
+
+    // 最顶层的祖先（自身为顶层时返回自身）
+    pub fn root_of<'a>(&'a self, category: &'a Category) -> &'a Category {
+        let mut current = category;
+        // 限制层数，防止父子关系出现环时死循环
+        for _ in 0..self.categories.len() {
+            match current.parent_id.and_then(|id| self.get(id)) {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current
+    }
+
+    // 自身及全部子孙类别的中文名，用于按类别筛选文章
+    pub fn descendant_names(&self, category: &Category) -> Vec<String> {
+        let mut names = vec![category.name_zh.clone()];
+        let mut frontier = vec![category.id];
+        while let Some(id) = frontier.pop() {
+            for child in self.categories.iter().filter(|c| c.parent_id == Some(id)) {
+                if !names.contains(&child.name_zh) {
+                    names.push(child.name_zh.clone());
+                    frontier.push(child.id);
+                }
+            }
+        }
+        names
+    }
+
+    // candidate 是 ancestor 自身或其子孙时返回 true，用于拒绝会形成环的父类别
+    pub fn is_descendant(&self, candidate: &Category, ancestor: &Category) -> bool {
+        self.descendant_names(ancestor).contains(&candidate.name_zh)
+    }
+
+    pub fn tree(&self) -> Vec<CategoryNode> {
+        fn children_of(taxonomy: &CategoryTaxonomy, parent_id: Option<i32>, depth: usize) -> Vec<CategoryNode> {
+            if depth > taxonomy.categories.len() {
+                return Vec::new();
+            }
+            taxonomy
+                .categories
+                .iter()
+                .filter(|c| c.parent_id == parent_id)
+                .map(|c| CategoryNode { category: c.clone(), children: children_of(taxonomy, Some(c.id), depth + 1) })
+                .collect()
+        }
+        children_of(self, None, 0)
+    }
+
+    // 把分类器或新闻源给出的类别名解析为类别表中的名称；都无法解析时归为未分类
+    pub fn resolve_labels<'a>(&self, names: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let (resolved, unknown) = self.canonicalize(names);
+        if !unknown.is_empty() {
+            log::warn!("Categories not in the taxonomy were dropped: {}", unknown.join(", "));
+        }
+        if resolved.is_empty() {
+            let fallback = self.resolve(UNCATEGORIZED).map(|c| c.name_zh.clone()).unwrap_or_else(|| UNCATEGORIZED.to_string());
+            return vec![fallback];
+        }
+        resolved
+    }
+}
+
+pub async fn load_taxonomy(client: &Client) -> Result<CategoryTaxonomy, PgError> {
+    Ok(CategoryTaxonomy::new(list_categories(client).await?))
+}
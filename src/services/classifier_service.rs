@@ -0,0 +1,476 @@
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use crate::db::category_queries::{get_labeled_examples, get_seed_examples, get_source_labeled_examples, LabeledExample};
+use crate::db::connection::DbPool;
+use crate::utils::tokenizer::tokenize;
+
+// 文章分类：训练好的模型是按类别一对多的逻辑回归（特征为对数词频 TF-IDF，L2 归一化），
+// 每个类别给出独立的概率，因此一篇文章可以属于多个类别。
+// 还没有训练模型时退回关键词匹配。
+
+pub const UNCATEGORIZED: &str = "其他";
+
+// 一篇文章最多输出的类别数
+const MAX_LABELS: usize = 3;
+// 除概率最高的类别外，其余类别需达到的概率
+const SECONDARY_LABEL_MIN_CONFIDENCE: f64 = 0.5;
+
+const DEFAULT_MODEL_PATH: &str = "storage/models/category_classifier.json";
+
+// 训练参数
+const EPOCHS: usize = 30;
+const LEARNING_RATE: f64 = 0.5;
+const L2_PENALTY: f64 = 1e-4;
+const MAX_VOCABULARY: usize = 30_000;
+// 类别样本极少时正样本权重的上限
+const MAX_POSITIVE_WEIGHT: f64 = 10.0;
+
+// 一个类别及其置信度（0-1）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, ToSchema)]
+pub struct CategoryScore {
+    pub category: String,
+    pub confidence: f64,
+}
+
+// 关键词表：一个词可以同时计入多个类别（例如“外交”既算政治也算国际）
+const CATEGORY_KEYWORDS: &[(&str, &[&str])] = &[
+    ("政治", &["政治", "政府", "选举", "政策", "外交"]),
+    ("经济", &["经济", "金融", "股市", "贸易", "GDP"]),
+    ("科技", &["科技", "人工智能", "互联网", "5G", "量子"]),
+    ("体育", &["体育", "足球", "篮球", "奥运会", "冠军"]),
+    ("娱乐", &["娱乐", "电影", "明星", "音乐", "综艺"]),
+    ("健康", &["健康", "医疗", "疫苗", "养生", "疾病"]),
+    ("教育", &["教育", "学校", "学生", "高考", "教师"]),
+    ("环境", &["环境", "气候", "污染", "环保", "碳中和"]),
+    ("国际", &["国际", "联合国", "外交", "全球", "世界"]),
+    ("社会", &["社会", "民生", "社区", "公共", "公民"]),
+];
+
+// 关键词匹配：命中关键词最多的类别胜出，置信度为该类别命中数占全部命中的比例
+fn classify_by_keywords(text: &str) -> Vec<CategoryScore> {
+    let hits: Vec<(&str, usize)> = CATEGORY_KEYWORDS
+        .iter()
+        .map(|(category, keywords)| (*category, keywords.iter().filter(|k| text.contains(*k)).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let total: usize = hits.iter().map(|(_, count)| count).sum();
+
+    // 命中数相同时按关键词表的顺序，结果稳定
+    let Some(&(category, count)) = hits.iter().rev().max_by_key(|(_, count)| *count) else {
+        return vec![CategoryScore { category: UNCATEGORIZED.to_string(), confidence: 0.0 }];
+    };
+    vec![CategoryScore { category: category.to_string(), confidence: count as f64 / total as f64 }]
+}
+
+// 一个类别的二分类器
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct CategoryWeights {
+    category: String,
+    bias: f64,
+    weights: HashMap<String, f64>,
+}
+
+// 持久化的模型
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClassifierModel {
+    pub trained_at: DateTime<Utc>,
+    pub training_examples: usize,
+    idf: HashMap<String, f64>,
+    categories: Vec<CategoryWeights>,
+}
+
+fn sigmoid(x: f64) -> f64 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+// 对数词频 × IDF，再做 L2 归一化；不在词表中的词忽略
+fn features(tokens: &[String], idf: &HashMap<String, f64>) -> Vec<(String, f64)> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for token in tokens {
+        *counts.entry(token.as_str()).or_insert(0) += 1;
+    }
+    let mut vector: Vec<(String, f64)> = counts
+        .into_iter()
+        .filter_map(|(term, tf)| idf.get(term).map(|idf| (term.to_string(), (1.0 + (tf as f64).ln()) * idf)))
+        .collect();
+    // 固定顺序，保证同一输入的浮点求和结果完全一致
+    vector.sort_by(|a, b| a.0.cmp(&b.0));
+    let norm = vector.iter().map(|(_, w)| w * w).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for (_, w) in &mut vector {
+            *w /= norm;
+        }
+    }
+    vector
+}
+
+fn document_text(title: &str, content: &str) -> String {
+    // 标题信息量大，计两次
+    format!("{}\n{}\n{}", title, title, content)
+}
+
+impl ClassifierModel {
+    // 在标注样本上训练；样本需要至少覆盖一个类别
+    pub fn train(examples: &[LabeledExample]) -> Result<Self, anyhow::Error> {
+        let categories: BTreeSet<&str> = examples.iter().flat_map(|e| e.categories.iter().map(String::as_str)).collect();
+        if examples.is_empty() || categories.is_empty() {
+            anyhow::bail!("No labeled articles to train on");
+        }
+
+        let documents: Vec<Vec<String>> = examples
+            .iter()
+            .map(|e| tokenize(&document_text(&e.title, &e.content)))
+            .collect();
+
+        // 词表：样本足够多时去掉只出现在一篇文章中的词，再按文档频率截断
+        let mut df: HashMap<&str, usize> = HashMap::new();
+        for tokens in &documents {
+            let unique: BTreeSet<&str> = tokens.iter().map(String::as_str).collect();
+            for term in unique {
+                *df.entry(term).or_insert(0) += 1;
+            }
+        }
+        let min_df = if documents.len() >= 50 { 2 } else { 1 };
+        let mut vocabulary: Vec<(&str, usize)> = df.into_iter().filter(|(_, count)| *count >= min_df).collect();
+        vocabulary.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        vocabulary.truncate(MAX_VOCABULARY);
+        let n = documents.len() as f64;
+        let idf: HashMap<String, f64> = vocabulary
+            .into_iter()
+            .map(|(term, count)| (term.to_string(), ((1.0 + n) / (1.0 + count as f64)).ln() + 1.0))
+            .collect();
+
+        let vectors: Vec<Vec<(String, f64)>> = documents.iter().map(|tokens| features(tokens, &idf)).collect();
+
+        let categories = categories
+            .into_iter()
+            .map(|category| {
+                let labels: Vec<bool> = examples.iter().map(|e| e.categories.iter().any(|c| c == category)).collect();
+                train_category(category, &vectors, &labels)
+            })
+            .collect();
+
+        Ok(ClassifierModel {
+            trained_at: Utc::now(),
+            training_examples: examples.len(),
+            idf,
+            categories,
+        })
+    }
+
+    pub fn category_names(&self) -> Vec<&str> {
+        self.categories.iter().map(|c| c.category.as_str()).collect()
+    }
+
+    // 按概率从高到低返回类别：概率最高的类别总会返回，其余类别需达到阈值
+    pub fn classify(&self, title: &str, content: &str) -> Vec<CategoryScore> {
+        let vector = features(&tokenize(&document_text(title, content)), &self.idf);
+        if vector.is_empty() {
+            return vec![CategoryScore { category: UNCATEGORIZED.to_string(), confidence: 0.0 }];
+        }
+
+        let mut scores: Vec<CategoryScore> = self
+            .categories
+            .iter()
+            .map(|c| {
+                let z = c.bias + vector.iter().map(|(term, w)| c.weights.get(term).unwrap_or(&0.0) * w).sum::<f64>();
+                CategoryScore { category: c.category.clone(), confidence: sigmoid(z) }
+            })
+            .collect();
+        scores.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.category.cmp(&b.category)));
+
+        let mut labels: Vec<CategoryScore> = scores.into_iter().take(MAX_LABELS).collect();
+        let mut index = 0;
+        labels.retain(|score| {
+            index += 1;
+            index == 1 || score.confidence >= SECONDARY_LABEL_MIN_CONFIDENCE
+        });
+        labels
+    }
+
+    pub fn load(path: &Path) -> Result<Self, anyhow::Error> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), anyhow::Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // 先写临时文件再改名，运行中的爬虫不会读到写了一半的模型
+        let tmp_path = path.with_extension("json.part");
+        std::fs::write(&tmp_path, serde_json::to_vec(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+// 带 L2 正则的随机梯度下降；正样本按类别比例加权，避免小类别被忽略
+fn train_category(category: &str, vectors: &[Vec<(String, f64)>], labels: &[bool]) -> CategoryWeights {
+    let positives = labels.iter().filter(|&&l| l).count().max(1) as f64;
+    let negatives = (labels.len() as f64 - positives).max(1.0);
+    let positive_weight = (negatives / positives).clamp(1.0, MAX_POSITIVE_WEIGHT);
+
+    let mut weights: HashMap<String, f64> = HashMap::new();
+    let mut bias = 0.0;
+    let mut order: Vec<usize> = (0..vectors.len()).collect();
+    // 固定随机种子，同样的数据训练出同样的模型
+    let mut rng = StdRng::seed_from_u64(42);
+
+    for epoch in 0..EPOCHS {
+        order.shuffle(&mut rng);
+        let rate = LEARNING_RATE / (1.0 + epoch as f64 * 0.1);
+        for &i in &order {
+            let vector = &vectors[i];
+            let z = bias + vector.iter().map(|(term, w)| weights.get(term).unwrap_or(&0.0) * w).sum::<f64>();
+            let target = if labels[i] { 1.0 } else { 0.0 };
+            let sample_weight = if labels[i] { positive_weight } else { 1.0 };
+            let gradient = (sigmoid(z) - target) * sample_weight;
+            for (term, w) in vector {
+                let weight = weights.entry(term.clone()).or_insert(0.0);
+                *weight -= rate * (gradient * w + L2_PENALTY * *weight);
+            }
+            bias -= rate * gradient;
+        }
+    }
+
+    weights.retain(|_, w| w.abs() > 1e-6);
+    CategoryWeights { category: category.to_string(), bias, weights }
+}
+
+// 当前使用的分类器
+pub enum Classifier {
+    Trained(ClassifierModel),
+    Keywords,
+}
+
+impl Classifier {
+    pub fn classify(&self, title: &str, content: &str) -> Vec<CategoryScore> {
+        match self {
+            Classifier::Trained(model) => model.classify(title, content),
+            Classifier::Keywords => classify_by_keywords(&format!("{}\n{}", title, content)),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Classifier::Trained(model) => format!(
+                "trained model ({} categories, {} examples, trained at {})",
+                model.categories.len(),
+                model.training_examples,
+                model.trained_at
+            ),
+            Classifier::Keywords => "keyword rules".to_string(),
+        }
+    }
+}
+
+// 模型文件位置，可用 CLASSIFIER_MODEL_PATH 覆盖
+pub fn classifier_model_path() -> PathBuf {
+    std::env::var("CLASSIFIER_MODEL_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from(DEFAULT_MODEL_PATH))
+}
+
+fn load_classifier() -> Classifier {
+    let path = classifier_model_path();
+    if !path.exists() {
+        return Classifier::Keywords;
+    }
+    match ClassifierModel::load(&path) {
+        Ok(model) => Classifier::Trained(model),
+        Err(e) => {
+            log::error!("Failed to load classifier model {}: {}; falling back to keyword rules", path.display(), e);
+            Classifier::Keywords
+        }
+    }
+}
+
+static CLASSIFIER: Lazy<RwLock<Arc<Classifier>>> = Lazy::new(|| RwLock::new(Arc::new(load_classifier())));
+
+pub fn get_classifier() -> Arc<Classifier> {
+    Arc::clone(&CLASSIFIER.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+// 重新读取模型文件；训练命令写入新模型后，下一次爬取开始时生效
+pub fn reload_classifier() {
+    let classifier = Arc::new(load_classifier());
+    log::info!("Using {} for article categories", classifier.describe());
+    *CLASSIFIER.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = classifier;
+}
+
+// 训练结果
+#[derive(Debug)]
+pub struct TrainingReport {
+    pub examples: usize,
+    pub manual_examples: usize, // 人工标注
+    pub source_examples: usize, // 按新闻源栏目自动标注
+    pub seed_examples: usize,   // 取自 articles.categories
+    pub categories: Vec<(String, usize)>, // 类别及其样本数
+    pub holdout_examples: usize,
+    pub holdout_accuracy: Option<f64>, // 概率最高的类别属于标注类别的比例
+    pub model_path: PathBuf,
+}
+
+// 训练并保存模型。样本依次取自人工标注（article_category_labels）和新闻源栏目（articles.source_category），
+// 同一篇文章只取优先级最高的一种标注。
+// seed_from_existing 为 true 时把 articles.categories（此前分类器的结果）也作为样本，用于冷启动
+pub async fn train_classifier(db_pool: &DbPool, seed_from_existing: bool) -> Result<TrainingReport, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let mut examples = get_labeled_examples(&client).await?;
+    let manual_examples = examples.len();
+
+    let mut labeled: BTreeSet<uuid::Uuid> = examples.iter().map(|e| e.article_id).collect();
+    let source_labeled: Vec<LabeledExample> = get_source_labeled_examples(&client, None)
+        .await?
+        .into_iter()
+        .filter(|e| labeled.insert(e.article_id))
+        .collect();
+    let source_examples = source_labeled.len();
+    examples.extend(source_labeled);
+
+    let mut seed_examples = 0;
+    if seed_from_existing {
+        let seeds: Vec<LabeledExample> = get_seed_examples(&client, UNCATEGORIZED)
+            .await?
+            .into_iter()
+            .filter(|e| labeled.insert(e.article_id))
+            .collect();
+        seed_examples = seeds.len();
+        examples.extend(seeds);
+    }
+    drop(client);
+    if examples.is_empty() {
+        anyhow::bail!("No labeled articles found; label some articles or pass --seed-from-existing");
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for category in examples.iter().flat_map(|e| e.categories.iter()) {
+        *counts.entry(category.clone()).or_insert(0) += 1;
+    }
+    let mut categories: Vec<(String, usize)> = counts.into_iter().collect();
+    categories.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    // 每 5 篇留出 1 篇评估，再用全部样本训练最终模型
+    // 训练只在命令行中运行，直接在当前线程计算
+    let (holdout_examples, holdout_accuracy) = {
+        let (train, holdout): (Vec<_>, Vec<_>) =
+            examples.iter().cloned().enumerate().partition(|(i, _)| i % 5 != 4);
+        let train: Vec<LabeledExample> = train.into_iter().map(|(_, e)| e).collect();
+        let holdout: Vec<LabeledExample> = holdout.into_iter().map(|(_, e)| e).collect();
+        let accuracy = match ClassifierModel::train(&train) {
+            Ok(model) if !holdout.is_empty() => {
+                let correct = holdout
+                    .iter()
+                    .filter(|e| {
+                        let predicted = model.classify(&e.title, &e.content);
+                        predicted.first().is_some_and(|top| e.categories.contains(&top.category))
+                    })
+                    .count();
+                Some(correct as f64 / holdout.len() as f64)
+            }
+            _ => None,
+        };
+        (holdout.len(), accuracy)
+    };
+
+    let model = ClassifierModel::train(&examples)?;
+    let model_path = classifier_model_path();
+    model.save(&model_path)?;
+
+    Ok(TrainingReport {
+        examples: examples.len(),
+        manual_examples,
+        source_examples,
+        seed_examples,
+        categories,
+        holdout_examples,
+        holdout_accuracy,
+        model_path,
+    })
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct CategoryCount {
+    pub category: String,
+    pub count: usize,
+}
+
+// 某个栏目类别下分类器的表现
+#[derive(Serialize, Debug, ToSchema)]
+pub struct SourceCategoryAgreement {
+    pub source_category: String,
+    pub articles: usize,
+    pub agreed: usize,
+    pub predicted: Vec<CategoryCount>, // 分类器首选类别的分布，从多到少
+}
+
+// 分类器首选类别与新闻源栏目类别的一致程度
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ClassifierAgreement {
+    pub classifier: String,
+    pub articles: usize,
+    pub agreed: usize,
+    pub agreement_rate: f64,
+    pub by_source_category: Vec<SourceCategoryAgreement>,
+}
+
+#[derive(Serialize, Debug, ToSchema)]
+pub struct ClassifierAgreementReport {
+    pub keyword_rules: ClassifierAgreement,
+    // 已训练模型的结果；还没有模型时为空。模型本身用栏目类别训练，这里的一致率偏乐观
+    pub trained_model: Option<ClassifierAgreement>,
+}
+
+// 用带栏目类别的文章衡量分类器：首选类别等于栏目类别即算一致
+pub fn measure_agreement(classifier: &Classifier, examples: &[LabeledExample]) -> ClassifierAgreement {
+    let mut by_source: HashMap<&str, HashMap<String, usize>> = HashMap::new();
+    for example in examples {
+        let Some(source_category) = example.categories.first() else {
+            continue;
+        };
+        let predicted = classifier
+            .classify(&example.title, &example.content)
+            .into_iter()
+            .next()
+            .map(|score| score.category)
+            .unwrap_or_else(|| UNCATEGORIZED.to_string());
+        *by_source.entry(source_category.as_str()).or_default().entry(predicted).or_insert(0) += 1;
+    }
+
+    let mut by_source_category: Vec<SourceCategoryAgreement> = by_source
+        .into_iter()
+        .map(|(source_category, predicted)| {
+            let mut predicted: Vec<CategoryCount> = predicted
+                .into_iter()
+                .map(|(category, count)| CategoryCount { category, count })
+                .collect();
+            predicted.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.category.cmp(&b.category)));
+            SourceCategoryAgreement {
+                source_category: source_category.to_string(),
+                articles: predicted.iter().map(|p| p.count).sum(),
+                agreed: predicted.iter().filter(|p| p.category == source_category).map(|p| p.count).sum(),
+                predicted,
+            }
+        })
+        .collect();
+    by_source_category.sort_by(|a, b| b.articles.cmp(&a.articles).then_with(|| a.source_category.cmp(&b.source_category)));
+
+    let articles: usize = by_source_category.iter().map(|c| c.articles).sum();
+    let agreed: usize = by_source_category.iter().map(|c| c.agreed).sum();
+    ClassifierAgreement {
+        classifier: classifier.describe(),
+        articles,
+        agreed,
+        agreement_rate: if articles > 0 { agreed as f64 / articles as f64 } else { 0.0 },
+        by_source_category,
+    }
+}
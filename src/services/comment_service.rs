@@ -1,10 +1,14 @@
 use uuid::Uuid;
 
+use crate::db::article_queries::{lock_article, lock_visible_article};
 use crate::db::connection::DbPool;
 // use crate::db::comment_queries::{insert_comment, get_comments_by_article_id, delete_comment_by_id_and_user, find_comment_by_id};
-use crate::db::comment_queries::{create_comment, get_comments_by_article_id, delete_comment, get_comment_by_id, get_comments_by_user_id};
+use crate::db::comment_queries::{create_comment, get_comments_by_article_id, delete_comment, get_comment_by_id, lock_comment_by_id, get_comments_by_user_id, count_comments_by_article_id, count_comments_by_user_id};
+use crate::db::unit_of_work::{Isolation, UnitOfWork};
 
 use crate::models::article::{CreateCommentSchema, Comment};
+use crate::models::pagination::{Page, Pagination};
+use crate::utils::cursor::TimeCursor;
 use crate::errors::ServiceError;
 
 pub async fn post_comment_service(
@@ -12,59 +16,70 @@ pub async fn post_comment_service(
     user_id: Uuid,
     comment_data: CreateCommentSchema,
 ) -> Result<Comment, ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
-
-    // Optionally, first check if the article_id exists in the articles table
-    // match crate::db::article_queries::find_article_by_id(&client, comment_data.article_id).await? {
-    //     Some(_) => { /* Article exists, proceed */ }
-    //     None => return Err(ServiceError::NotFound("Article to comment on not found".to_string())),
-    // }
+    // 评论和文章的评论数在同一个事务中更新；先锁文章再检查被回复的评论，与删除评论的加锁顺序一致
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    if !lock_visible_article(&uow, comment_data.article_id).await? {
+        return Err(ServiceError::NotFound("Article to comment on not found".to_string()));
+    }
+    if let Some(parent_id) = comment_data.parent_comment_id {
+        match get_comment_by_id(&uow, parent_id).await? {
+            Some(parent) if parent.article_id == comment_data.article_id => {}
+            Some(_) => return Err(ServiceError::BadRequest("Parent comment belongs to another article".to_string())),
+            None => return Err(ServiceError::NotFound("Parent comment not found".to_string())),
+        }
+    }
 
-    // insert_comment(&client, user_id, comment_data.article_id, &comment_data.content).await
-    create_comment(&client, user_id, comment_data.article_id, &(comment_data.username.clone()),comment_data).await
+    let comment = create_comment(&uow, user_id, comment_data.article_id, &(comment_data.username.clone()),comment_data).await
         .map_err(|e| {
             log::error!("Failed to post comment to DB: {}", e);
             ServiceError::InternalServerError("Failed to post comment".to_string())
-        })
+        })?;
+    uow.commit().await?;
+    Ok(comment)
 }
 
 pub async fn get_comments_for_article_service(
     pool: &DbPool,
     article_id: Uuid,
-) -> Result<Vec<Comment>, ServiceError> {
+    pagination: &Pagination,
+) -> Result<Page<Comment>, ServiceError> {
+    let after: Option<TimeCursor> = pagination.cursor()?;
     let client = pool.get().await.map_err(|e| {
         log::error!("Failed to get DB client from pool: {}", e);
         ServiceError::InternalServerError("Database connection error".to_string())
     })?;
-    
-    // 设置默认分页参数（例如：第1页，每页10条）
-    let page = 1;
-    let limit = 10;
-    
-    get_comments_by_article_id(&client, article_id, page, limit).await.map_err(|e| {
-        log::error!("Failed to get comments from DB: {}", e);
-        ServiceError::InternalServerError("Failed to retrieve comments".to_string())
-    })
+
+    let comments = get_comments_by_article_id(&client, article_id, after.as_ref(), pagination.offset(), pagination.fetch_limit()).await;
+    let total = count_comments_by_article_id(&client, article_id).await;
+    match (comments, total) {
+        (Ok(comments), Ok(total)) => Ok(pagination.page_of(comments, total, |c| TimeCursor { at: c.created_at, id: c.id })),
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("Failed to get comments from DB: {}", e);
+            Err(ServiceError::InternalServerError("Failed to retrieve comments".to_string()))
+        }
+    }
 }
 
 pub async fn get_user_comments_service(
     pool: &DbPool,
     user_id: Uuid,
-    page: i64,
-    limit: i64,
-) -> Result<Vec<Comment>, ServiceError> {
+    pagination: &Pagination,
+) -> Result<Page<Comment>, ServiceError> {
+    let after: Option<TimeCursor> = pagination.cursor()?;
     let client = pool.get().await.map_err(|e| {
         log::error!("Failed to get DB client from pool: {}", e);
         ServiceError::InternalServerError("Database connection error".to_string())
     })?;
-    
-    get_comments_by_user_id(&client, user_id, page, limit).await.map_err(|e| {
-        log::error!("Failed to get user comments from DB: {}", e);
-        ServiceError::InternalServerError("Failed to retrieve user comments".to_string())
-    })
+
+    let comments = get_comments_by_user_id(&client, user_id, after.as_ref(), pagination.offset(), pagination.fetch_limit()).await;
+    let total = count_comments_by_user_id(&client, user_id).await;
+    match (comments, total) {
+        (Ok(comments), Ok(total)) => Ok(pagination.page_of(comments, total, |c| TimeCursor { at: c.created_at, id: c.id })),
+        (Err(e), _) | (_, Err(e)) => {
+            log::error!("Failed to get user comments from DB: {}", e);
+            Err(ServiceError::InternalServerError("Failed to retrieve user comments".to_string()))
+        }
+    }
 }
 
 pub async fn delete_comment_service(
@@ -72,16 +87,14 @@ pub async fn delete_comment_service(
     user_id: Uuid, // ID of the user requesting deletion
     comment_id: Uuid,
 ) -> Result<(), ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
-
-    // Optional: Check if the comment exists and if the user owns it or is an admin
-    // This logic can be more complex depending on your permission model.
-    // For simplicity, we assume the DB query handles ownership or admin rights.
-    // match find_comment_by_id(&client, comment_id).await? {
-    match get_comment_by_id(&client, comment_id).await? {
+    // 检查权限和删除在同一个事务中：先锁文章（删除会更新它的评论数），再锁评论后检查；
+    // 隐藏和已删除文章下的评论也可以删除
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    let Some(comment) = get_comment_by_id(&uow, comment_id).await? else {
+        return Err(ServiceError::NotFound("Comment not found".to_string()));
+    };
+    lock_article(&uow, comment.article_id).await?;
+    match lock_comment_by_id(&uow, comment_id).await? {
         Some(comment) => {
             if comment.user_id != user_id {
                 // Add admin check here if needed: e.g., check if user_id has admin role
@@ -91,16 +104,9 @@ pub async fn delete_comment_service(
         None => return Err(ServiceError::NotFound("Comment not found".to_string())),
     }
 
-    // let rows_affected = delete_comment_by_id_and_user(&client, comment_id, user_id).await.map_err(|e| {
-    let rows_affected = delete_comment(&client, comment_id, user_id).await.map_err(|e| {
+    delete_comment(&uow, comment_id, user_id).await.map_err(|e| {
         log::error!("Failed to delete comment from DB: {}", e);
         ServiceError::InternalServerError("Failed to delete comment".to_string())
     })?;
-
-    if rows_affected == 0 {
-        // This could mean the comment didn't exist or the user_id didn't match (if query includes user_id check for deletion)
-        Err(ServiceError::NotFound("Comment not found or permission denied for deletion".to_string()))
-    } else {
-        Ok(())
-    }
+    uow.commit().await
 }
@@ -1,4 +1,3 @@
-use reqwest::Client as HttpClient;
 use scraper::{Html, Selector};
 use tokio_cron_scheduler::{Job, JobScheduler, JobSchedulerError};
 use tokio::task;
@@ -9,146 +8,62 @@ use std::time::Duration;
 use rand::Rng;
 
 use crate::db::connection::DbPool;
-use crate::db::article_queries::insert_article;
-use crate::db::article_queries::save_image_to_db;
-use crate::db::article_queries::load_image_from_db;
+use crate::db::article_queries::{insert_article, insert_articles, InsertOutcome, RecrawlMode};
+use crate::db::category_queries::replace_classifier_labels;
+use crate::db::search_queries::replace_search_terms;
+use crate::db::tag_queries::replace_article_tags;
+use crate::db::image_queries::{count_article_images, insert_article_image, insert_rejected_image};
+use crate::db::crawl_queries::{start_crawl_run, insert_crawl_source_stats, finish_crawl_run, fail_unfinished_crawl_runs};
+use crate::db::unit_of_work::{Isolation, UnitOfWork};
 use crate::models::article::Article;
 use crate::models::article::NewsImage;
-use crate::config::app_config::Config; // To get cron expression if needed here, or pass it
+use crate::utils::{html_sanitizer, readability};
+use crate::services::http_fetcher::{FetchError, HttpFetcher, ReqwestFetcher};
+use crate::services::image_service::{self, ImageRejection, StoredImage, MAX_IMAGE_BYTES};
+use crate::services::storage::{build_storage, ImageStorage};
+use crate::services::classifier_service::{get_classifier, reload_classifier, CategoryScore};
+use crate::services::category_service::{load_taxonomy, CategoryTaxonomy};
+use crate::services::search_service::index_terms;
+use crate::services::suggest_service::rebuild_suggestions;
+use crate::services::summary_service::summarize;
+use crate::services::tagging_service::extract_tags;
+use crate::models::crawl::{CrawlSourceStats, CrawlerStatus, CurrentCrawlRun};
+use crate::config::app_config::StorageConfig;
+use crate::errors::ServiceError;
 
 use url::Url;
 use anyhow::{anyhow, Context};
 
-use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
 use std::sync::Arc;
-use once_cell::sync::Lazy;
-use std::path::{Path, PathBuf};
-use tokio::fs::{self, File};
-use tokio::io::AsyncWriteExt;
-
-struct NewsClassifier {
-    keyword_map: HashMap<&'static str, &'static str>,
-}
-
-impl NewsClassifier {
-    fn new() -> Self {
-        let mut keyword_map = HashMap::new();
-        
-        // 政治类关键词
-        keyword_map.insert("政治", "政治");
-        keyword_map.insert("政府", "政治");
-        keyword_map.insert("选举", "政治");
-        keyword_map.insert("政策", "政治");
-        keyword_map.insert("外交", "政治");
-        
-        // 经济类关键词
-        keyword_map.insert("经济", "经济");
-        keyword_map.insert("金融", "经济");
-        keyword_map.insert("股市", "经济");
-        keyword_map.insert("贸易", "经济");
-        keyword_map.insert("GDP", "经济");
-        
-        // 科技类关键词
-        keyword_map.insert("科技", "科技");
-        keyword_map.insert("人工智能", "科技");
-        keyword_map.insert("互联网", "科技");
-        keyword_map.insert("5G", "科技");
-        keyword_map.insert("量子", "科技");
-        
-        // 体育类关键词
-        keyword_map.insert("体育", "体育");
-        keyword_map.insert("足球", "体育");
-        keyword_map.insert("篮球", "体育");
-        keyword_map.insert("奥运会", "体育");
-        keyword_map.insert("冠军", "体育");
-        
-        // 娱乐类关键词
-        keyword_map.insert("娱乐", "娱乐");
-        keyword_map.insert("电影", "娱乐");
-        keyword_map.insert("明星", "娱乐");
-        keyword_map.insert("音乐", "娱乐");
-        keyword_map.insert("综艺", "娱乐");
-        
-        // 健康类关键词
-        keyword_map.insert("健康", "健康");
-        keyword_map.insert("医疗", "健康");
-        keyword_map.insert("疫苗", "健康");
-        keyword_map.insert("养生", "健康");
-        keyword_map.insert("疾病", "健康");
-        
-        // 教育类关键词
-        keyword_map.insert("教育", "教育");
-        keyword_map.insert("学校", "教育");
-        keyword_map.insert("学生", "教育");
-        keyword_map.insert("高考", "教育");
-        keyword_map.insert("教师", "教育");
-        
-        // 环境类关键词
-        keyword_map.insert("环境", "环境");
-        keyword_map.insert("气候", "环境");
-        keyword_map.insert("污染", "环境");
-        keyword_map.insert("环保", "环境");
-        keyword_map.insert("碳中和", "环境");
-        
-        // 国际类关键词
-        keyword_map.insert("国际", "国际");
-        keyword_map.insert("联合国", "国际");
-        keyword_map.insert("外交", "国际");
-        keyword_map.insert("全球", "国际");
-        keyword_map.insert("世界", "国际");
-        
-        // 社会类关键词
-        keyword_map.insert("社会", "社会");
-        keyword_map.insert("民生", "社会");
-        keyword_map.insert("社区", "社会");
-        keyword_map.insert("公共", "社会");
-        keyword_map.insert("公民", "社会");
-
-        NewsClassifier { keyword_map }
-    }
-    
-    fn classify(&self, content: &str) -> &'static str {
-        let mut category_counts: HashMap<&'static str, u32> = HashMap::new();
-        
-        for (keyword, category) in &self.keyword_map {
-            if content.contains(keyword) {
-                *category_counts.entry(category).or_insert(0) += 1;
-            }
-        }
-        
-        category_counts
-            .into_iter()
-            .max_by_key(|&(_, count)| count)
-            .map(|(category, _)| category)
-            .unwrap_or("其他")
-    }
-}
 
-static CLASSIFIER: Lazy<Arc<NewsClassifier>> = Lazy::new(|| {
-    Arc::new(NewsClassifier::new())
-});
-
-pub fn get_classifier() -> Arc<NewsClassifier> {
-    Arc::clone(&CLASSIFIER)
-}
+use futures_util::FutureExt;
 
 // Basic structure for a news source configuration
 #[derive(Debug, Clone)]
-struct NewsSource {
-    name: String,
-    url: String,
-    article_selector: String,       // CSS selector for individual article links/blocks
-    link_selector: String,          // CSS selector for the href within an article block
-    title_selector: String,         // CSS selector for the title within an article page
-    content_selector: String,       // CSS selector for the content within an article page
+pub struct NewsSource {
+    pub name: String,
+    pub url: String,
+    pub article_selector: String,       // CSS selector for individual article links/blocks
+    pub link_selector: String,          // CSS selector for the href within an article block
+    pub title_selector: String,         // CSS selector for the title within an article page
+    pub content_selector: String,       // CSS selector for the content within an article page
     // category_selector: Option(String), // Optional: selector for category
     // date_selector: Option<String>,     // Optional: selector for publish date
-    image_selector:String,
-    // catelabel:String,
+    pub image_selector:String,
+    pub category_hint: Option<String>,  // 该链接选择器对应的栏目类别（如人民网财经栏目为“经济”），没有明确栏目时为 None
+    pub content_mode: ContentMode,
+}
+
+// 正文的获取方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContentMode {
+    Selector,    // 优先使用 title/content_selector，匹配不到时退回通用正文抽取
+    Readability, // 只使用通用正文抽取，新增新闻源时只需配置列表页选择器
 }
 
 // Hardcoded list of news sources for now. Ideally, this would come from a config file or DB.
-fn get_news_sources() -> Vec<NewsSource> {
+pub fn get_news_sources() -> Vec<NewsSource> {
     vec![
         // Example: A generic news site structure (replace with actual selectors for target sites)
         // This is highly dependent on the target website's HTML structure.
@@ -169,9 +84,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "https://www.zaobao.com/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.row.justify-content-center div.col-12.col-xl-4.col-lg-4.reset-row-margin div.pdb10.relative.real-article a".to_string(),
+            category_hint: None,
             title_selector: "div.row.reset-row-margin h1.article-title".to_string(),
             content_selector: "div.row.reset-row-margin article.article-body".to_string(),
             image_selector:"div.row.reset-row-margin div.article-banner img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -179,9 +96,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "https://www.zaobao.com/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.row.justify-content-center div.summary-list-wrapper.line-lg.after-line a".to_string(),
+            category_hint: None,
             title_selector: "div.row.reset-row-margin h1.article-title".to_string(),
             content_selector: "div.row.reset-row-margin article.article-body".to_string(),
             image_selector:"div.row.reset-row-margin div.article-banner img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -189,9 +108,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "https://www.zaobao.com/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.float-lg-left.float-none.col-xl-8.col-lg-8.col-12.order-6 div.category.after-line.pdb15-lg div.normal-item a".to_string(),
+            category_hint: None,
             title_selector: "div.row.reset-row-margin h1.article-title".to_string(),
             content_selector: "div.row.reset-row-margin article.article-body".to_string(),
             image_selector:"div.row.reset-row-margin div.article-banner img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
 
@@ -213,9 +134,12 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "https://www.huanqiu.com/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "dl a".to_string(),
-            title_selector: "div.layout.rm_txt.cf h1".to_string(),
-            content_selector: "div.rm_txt_con.cf p".to_string(),
-            image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            category_hint: None,
+            // 文章页结构不统一，标题和正文交给通用抽取
+            title_selector: String::new(),
+            content_selector: String::new(),
+            image_selector: String::new(),
+            content_mode: ContentMode::Readability,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
 
@@ -235,9 +159,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.section_Comment.cf h3 a".to_string(),
+            category_hint: None,
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -245,9 +171,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.section_Comment.cf h4 a".to_string(),
+            category_hint: None,
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -255,9 +183,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Finance.cf.pdao1 li a".to_string(),
+            category_hint: Some("经济".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -265,9 +195,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Finance.cf.pdao1 p a".to_string(),
+            category_hint: Some("经济".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -275,9 +207,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.science.cf.pdao2 li a".to_string(),
+            category_hint: Some("科技".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -285,9 +219,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.science.cf.pdao2 p a".to_string(),
+            category_hint: Some("科技".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -295,9 +231,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.International_Military.cf.pdao3 li a".to_string(),
+            category_hint: Some("国际".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -305,9 +243,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.International_Military.cf.pdao3 p a".to_string(),
+            category_hint: Some("国际".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -315,19 +255,23 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Local_interview.cf.pdao4 li a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
             name: "people".to_string(),
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
-            link_selector: "div.div.layout.Local_interview.cf.pdao4 p a".to_string(),
+            link_selector: "div.layout.Local_interview.cf.pdao4 p a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -335,9 +279,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Local_interview.cf.pdao5 li a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -345,9 +291,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Local_interview.cf.pdao5 p a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -355,9 +303,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Local_interview.cf.pdao6 li a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         NewsSource {
@@ -365,9 +315,11 @@ fn get_news_sources() -> Vec<NewsSource> {
             url: "http://www.people.com.cn/".to_string() , // URL to a page listing multiple articles
             article_selector: "div.article-item".to_string(),
             link_selector: "div.layout.Local_interview.cf.pdao6 p a".to_string(),
+            category_hint: Some("社会".to_string()),
             title_selector: "div.layout.rm_txt.cf h1".to_string(),
             content_selector: "div.rm_txt_con.cf p".to_string(),
             image_selector:"div.layout.rm_txt.cf div.col.col-1.fl div.rm_txt_con.cf img".to_string(),
+            content_mode: ContentMode::Selector,
             // category_selector:"div.layout.route.cf a:nth-child(2)".to_string(),
         },
         // NewsSource {
@@ -463,306 +415,927 @@ fn get_news_sources() -> Vec<NewsSource> {
 }
 
 
-async fn fetch_article_urls(client: &HttpClient, source: &NewsSource) -> Result<Vec<String>, anyhow::Error> {
-    log::info!("Fetching article URLs from: {}", source.url);
-    let response = client.get(&source.url).send().await?.text().await?;
-    let document = Html::parse_document(&response);
-
-    // for line in document.html().lines() {
-    // println!("{}", line);
-    // // 可以添加延迟，避免刷屏太快
-    // std::thread::sleep(std::time::Duration::from_millis(100));
-    // }
-
-    // let article_item_selector = Selector::parse(&source.article_selector).unwrap();
-    // println!("Start :{}",&source.link_selector);
-    let link_selector = Selector::parse(&source.link_selector).unwrap();
-    let mut urls = Vec::new();
+// 单篇文章的处理结果，用于统计每个新闻源的产出
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArticleOutcome {
+    Inserted,
+    Updated, // 已有文章的标题或正文有变化，已更新
+    Duplicate, // 已有文章，内容没有变化
+    ParseFailed,
+    StoreFailed,
+}
+
+// 爬取单个新闻源期间累计的计数
+#[derive(Debug, Default)]
+struct SourceCrawlCounters {
+    urls_found: i32,
+    articles_inserted: i32,
+    articles_updated: i32,
+    duplicates: i32,
+    parse_failures: i32,
+    http_errors: i32,
+    last_error: Option<String>,
+}
 
-    let mut count=0;
-    // println!("Start :{}",count);
+impl SourceCrawlCounters {
+    fn record_outcome(&mut self, outcome: ArticleOutcome) {
+        match outcome {
+            ArticleOutcome::Inserted => self.articles_inserted += 1,
+            ArticleOutcome::Updated => self.articles_updated += 1,
+            ArticleOutcome::Duplicate => self.duplicates += 1,
+            ArticleOutcome::ParseFailed => self.parse_failures += 1,
+            ArticleOutcome::StoreFailed => {}
+        }
+    }
+
+    fn record_error(&mut self, e: &anyhow::Error) {
+        if e.downcast_ref::<FetchError>().is_some() {
+            self.http_errors += 1;
+        }
+        self.last_error = Some(e.to_string());
+    }
+
+    // 出过错且一篇文章也没有入库（包括列表页抓取失败）
+    fn failed(&self) -> bool {
+        self.last_error.is_some() && self.articles_inserted + self.articles_updated + self.duplicates == 0
+    }
+}
+
+// 从列表页 HTML 中提取文章链接（转换为绝对地址）
+pub fn extract_article_urls(listing_html: &str, source: &NewsSource) -> Result<Vec<String>, anyhow::Error> {
+    let document = Html::parse_document(listing_html);
+    let link_selector = Selector::parse(&source.link_selector).map_err(|e| anyhow!("Invalid link selector: {}", e))?;
+    let base_url = Url::parse(&source.url).with_context(|| format!("Invalid base URL: {}", source.url))?;
+
+    let mut urls = Vec::new();
     for link_element in document.select(&link_selector) {
-        // println!("Start :{}",count);
-        // if let Some(link_element) = element.select(&link_selector).next() {
-            if let Some(href) = link_element.value().attr("href") {
-                // println!("{}",href.to_string());
-                // Ensure the URL is absolute
-                let absolute_url = if href.starts_with("http")  {
-                    href.to_string()
-                } else {
-                    // Attempt to join with base URL (simplistic)
-                    // let base_url = url::Url::parse(&source.url).map_err(|_| reqwest::Error::custom("Invalid base URL"))?;
-                    let base_url = url::Url::parse(&source.url)
-                        .with_context(|| format!("Invalid base URL: {}", source.url))?;
-                    // base_url.join(href).map_err(|_| reqwest::Error::custom("Failed to join URL"))?.to_string()
-                    base_url.join(href).with_context(|| format!("Invalid base URL: {}", source.url))?.to_string()
-                };
-                urls.push(absolute_url);
-
-                count=count+1;
-            }
-        // }
-        if count>=6
-        {
-            //break;
+        if let Some(href) = link_element.value().attr("href") {
+            // Ensure the URL is absolute
+            let absolute_url = if href.starts_with("http") {
+                href.to_string()
+            } else {
+                base_url.join(href).with_context(|| format!("Invalid article link {} on {}", href, source.url))?.to_string()
+            };
+            urls.push(absolute_url);
         }
     }
+    Ok(urls)
+}
+
+pub async fn fetch_article_urls(fetcher: &dyn HttpFetcher, source: &NewsSource) -> Result<Vec<String>, anyhow::Error> {
+    log::info!("Fetching article URLs from: {}", source.url);
+    let listing_html = fetcher.get_text(&source.url).await?;
+    let urls = extract_article_urls(&listing_html, source)?;
     log::info!("Found {} article URLs from {}", urls.len(), source.name);
     Ok(urls)
 }
 
-async fn download_image(http_client: &HttpClient, url: &str, base_url: &str) -> Result<NewsImage, anyhow::Error> {
-    let dir_path = Path::new(".")
-        .join("storage")
-        .join("img");
+// 每篇文章最多保存的图片数
+const MAX_IMAGES_PER_ARTICLE: usize = 10;
+// 每批写入数据库的文章数
+const ARTICLE_BATCH_SIZE: usize = 10;
 
-    fs::create_dir_all(&dir_path).await?;
+// 图片下载失败的两种情况：网络/存储错误只记日志，内容未通过校验的写入 rejected_images
+enum ImageDownloadError {
+    Rejected {
+        rejection: ImageRejection,
+        declared_content_type: Option<String>,
+        byte_size: Option<usize>,
+    },
+    Failed(anyhow::Error),
+}
 
+// 下载图片，校验通过后按内容哈希保存（同时生成缩略图）
+async fn download_image(
+    fetcher: &dyn HttpFetcher,
+    storage: &dyn ImageStorage,
+    url: &str,
+) -> Result<StoredImage, ImageDownloadError> {
     log::info!("Downloading image from: {}", url);
-    log::info!("Downloading image from base_url: {}", base_url);
-    let response = http_client.get(url).send().await?;
-    
-    if !response.status().is_success() {
-        return Err(anyhow!("Failed to download image: {}", response.status()));
-    }
-    
-    let content_type = response.headers()
-        .get("content-type")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("application/octet-stream")
-        .to_string();
-
-    let file_ext = match content_type.split('/').last() {
-        Some("jpeg") | Some("jpg") => "jpg",
-        Some("png") => "png",
-        Some("gif") => "gif",
-        Some("webp") => "webp",
-        _ => {
-            log::warn!("Unknown content type: {}, defaulting to jpg", content_type);
-            "jpg"
+    let body = match fetcher.get_bytes(url, MAX_IMAGE_BYTES).await {
+        Ok(body) => body,
+        Err(FetchError::TooLarge { limit }) => {
+            return Err(ImageDownloadError::Rejected {
+                rejection: ImageRejection::TooLarge { limit },
+                declared_content_type: None,
+                byte_size: None,
+            });
         }
+        Err(e) => return Err(ImageDownloadError::Failed(e.into())),
     };
-    let filename = format!(
-        "{}.{}",
-        chrono::Local::now().format("%H%M%S"),
-        //md5::compute(url),
-        //hash,
-        file_ext
-    );
-    let filepath = dir_path.join(&filename);
-    filepath.display();
-
-    let image_data = response.bytes().await?.to_vec();
-    let mut file = File::create(&filepath).await?;
-    file.write_all(&image_data).await?;
-    log::info!("Image saved to: {}", filepath.display());
-
-    Ok(NewsImage {
-        id: 0,  // 将由数据库分配
-        url: base_url.to_string(),
-        // image_data,
-        content_type: content_type,
-        file_path: filepath.display().to_string(),
-        // article_id: Uuid::nil(),  // 将在保存时设置
-    })
+
+    let byte_size = body.bytes.len();
+    let validated = image_service::validate_image(body.bytes).map_err(|rejection| ImageDownloadError::Rejected {
+        rejection,
+        declared_content_type: body.content_type.clone(),
+        byte_size: Some(byte_size),
+    })?;
+    let stored = image_service::store_image(storage, &validated).await.map_err(ImageDownloadError::Failed)?;
+    log::info!("Image saved to: {}", stored.storage_path);
+    Ok(stored)
 }
 
-async fn fetch_and_parse_article(
-    http_client: &HttpClient,
-    db_pool: &DbPool,
+// 文章中的一张图片
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedImage {
+    pub url: String, // 绝对地址
+    pub caption: Option<String>,
+}
+
+// 从文章页解析出的内容，尚未入库
+#[derive(Debug, Clone)]
+pub struct ParsedArticle {
+    pub url: String,
+    pub title: String,
+    pub content: String,
+    pub content_html: Option<String>,
+    pub summary: String,
+    pub images: Vec<ParsedImage>, // 按页面中的顺序
+    pub categories: Vec<CategoryScore>, // 按置信度从高到低，至少一个
+    pub source_category: Option<String>, // 新闻源栏目给出的类别，见 NewsSource::category_hint
+}
+
+// 解析文章页；标题或正文缺失时返回 None
+pub fn parse_article(page_html: &str, article_url: &str, source: &NewsSource) -> Result<Option<ParsedArticle>, anyhow::Error> {
+    let document = Html::parse_document(page_html);
+    let base_url = Url::parse(article_url)?;
+
+    // 通用抽取得到的正文会带上其中的图片，选择器没有找到图片时使用
+    let (title, content, raw_html, extracted_images) = match source.content_mode {
+        ContentMode::Selector => {
+            let title_selector = Selector::parse(&source.title_selector).map_err(|e| anyhow::anyhow!("Invalid title selector: {}",e))?;
+            let content_selector = Selector::parse(&source.content_selector).map_err(|e| anyhow::anyhow!("Invalid content selector: {}",e))?;
+
+            let title = document
+                .select(&title_selector)
+                .next()
+                .map(|el| el.text().collect::<String>().trim().to_string())
+                .filter(|t| !t.is_empty())
+                .or_else(|| readability::extract_title(&document));
+
+            let mut content_parts = Vec::new();
+            let mut html_parts = Vec::new();
+            for element in document.select(&content_selector) {
+                content_parts.push(element.text().collect::<String>());
+                html_parts.push(element.html());
+            }
+            let content = content_parts.join("\n").trim().to_string();
+
+            // 选择器失效（例如页面改版）时退回通用正文抽取，而不是直接丢弃文章
+            if content.is_empty() {
+                let extracted = readability::extract_content(&document);
+                if !extracted.paragraphs.is_empty() {
+                    log::warn!("Content selector \"{}\" matched nothing for {}, used readability fallback", source.content_selector, article_url);
+                }
+                (title, extracted.paragraphs.join("\n"), extracted.html, extracted.images)
+            } else {
+                (title, content, html_parts.concat(), Vec::new())
+            }
+        }
+        ContentMode::Readability => {
+            let extracted = readability::extract_content(&document);
+            (readability::extract_title(&document), extracted.paragraphs.join("\n"), extracted.html, extracted.images)
+        }
+    };
+
+    let Some(title) = title.filter(|_| !content.is_empty()) else {
+        log::warn!("Could not parse title or content for URL: {}", article_url);
+        return Ok(None); // Skip if essential parts are missing
+    };
+
+    // 只配置了列表页选择器的新闻源可以不填 image_selector，此时使用通用抽取找到的图片
+    let mut found = Vec::new();
+    if !source.image_selector.is_empty() {
+        let image_selector = Selector::parse(&source.image_selector).map_err(|e| anyhow::anyhow!("Invalid image selector: {}",e))?;
+        for img_element in document.select(&image_selector) {
+            if let Some(src) = img_element.value().attr("src").filter(|src| !src.trim().is_empty()) {
+                found.push((src.trim().to_string(), readability::image_caption(&img_element)));
+            }
+        }
+    }
+    if found.is_empty() {
+        found = extracted_images.into_iter().map(|image| (image.src, image.caption)).collect();
+    }
+
+    let mut images: Vec<ParsedImage> = Vec::new();
+    for (src, caption) in found {
+        let url = base_url.join(&src)?.to_string();
+        // 同一张图在页面中出现多次时只保留第一次
+        if !images.iter().any(|image| image.url == url) {
+            images.push(ParsedImage { url, caption });
+        }
+    }
+
+    let categories = get_classifier().classify(&title, &content);
+
+    Ok(Some(ParsedArticle {
+        url: article_url.to_string(),
+        title,
+        // 保留标题、列表、链接、图片等结构，供阅读页渲染；纯文本 content 仍用于分类和推荐
+        content_html: Some(html_sanitizer::sanitize_article_html(&raw_html, article_url)).filter(|h| !h.is_empty()),
+        summary: summarize(&content),
+        content,
+        images,
+        categories,
+        source_category: source.category_hint.clone(),
+    }))
+}
+
+// 下载并解析一篇文章，不写数据库
+pub async fn fetch_article(
+    fetcher: &dyn HttpFetcher,
     article_url: &str,
     source: &NewsSource,
-)  -> Result<(), anyhow::Error> {
+) -> Result<Option<ParsedArticle>, anyhow::Error> {
     log::info!("Fetching article content from: {}", article_url);
-    let response_text = http_client.get(article_url) .send().await?.text().await?;
-    let document = Html::parse_document(&response_text);
-
-    let title_selector = Selector::parse(&source.title_selector).map_err(|e| anyhow::anyhow!("Invalid title selector: {}",e))?;
-    let content_selector = Selector::parse(&source.content_selector).map_err(|e| anyhow::anyhow!("Invalid content selector: {}",e))?;
-    // let cate_selector = Selector::parse(&source.category_selector).map_err(|e| anyhow!("Invalid cate selector: {}", e))?;
-    
-    let title = document
-        .select(&title_selector)
-        .next()
-        .map(|el| el.text().collect::<String>().trim().to_string())
-        .unwrap_or_else(|| "Title not found".to_string());
-
-    // let cate = document
-    //     .select(&cate_selector)
-    //     .next()
-    //     .map(|el| el.text().collect::<String>().trim().to_string())
-    //     .unwrap_or_else(|| "Cate not found".to_string());
-
-    let mut content_parts = Vec::new();
-    for element in document.select(&content_selector) {
-        content_parts.push(element.text().collect::<String>());
-    }
-    let content = content_parts.join("\n").trim().to_string();
-
-    if title == "Title not found" || content.is_empty() {
-        log::warn!("Could not parse title or content for URL: {}", article_url);
-        return Ok(()); // Skip if essential parts are missing
-    }
+    let page_html = fetcher.get_text(article_url).await?;
+    parse_article(&page_html, article_url, source)
+}
 
-    let classifier=get_classifier();
-    let kind=classifier.classify(&content);
-    // println!("{}",&kind);
+// 把分类结果和栏目类别换成类别表中的名称；同一类别出现多次时保留置信度最高的一个
+fn resolve_parsed_categories(parsed: &mut ParsedArticle, taxonomy: &CategoryTaxonomy) {
+    let names = taxonomy.resolve_labels(parsed.categories.iter().map(|score| score.category.as_str()));
+    parsed.categories = names
+        .into_iter()
+        .map(|name| {
+            let confidence = parsed
+                .categories
+                .iter()
+                .filter(|score| taxonomy.resolve(&score.category).is_some_and(|c| c.name_zh == name))
+                .map(|score| score.confidence)
+                .fold(0.0, f64::max);
+            CategoryScore { category: name, confidence }
+        })
+        .collect();
+    parsed.source_category = parsed
+        .source_category
+        .as_deref()
+        .and_then(|hint| taxonomy.resolve(hint))
+        .map(|category| category.name_zh.clone());
+}
+
+// 已解析、等待入库的文章
+struct PendingArticle {
+    article: Article,
+    categories: Vec<CategoryScore>, // 分类器给出的类别置信度，新文章入库后保存
+    images: Vec<ParsedImage>,
+}
+
+// 下载并解析一篇文章，生成待入库的文章；解析失败时返回 None
+async fn prepare_article(
+    fetcher: &dyn HttpFetcher,
+    taxonomy: Option<&CategoryTaxonomy>,
+    article_url: &str,
+    source: &NewsSource,
+) -> Result<Option<PendingArticle>, anyhow::Error> {
+    let Some(mut parsed) = fetch_article(fetcher, article_url, source).await? else {
+        return Ok(None);
+    };
+    if let Some(taxonomy) = taxonomy {
+        resolve_parsed_categories(&mut parsed, taxonomy);
+    }
 
     let article = Article {
         id: Uuid::new_v4(),
-        title,
-        content,
-        url: article_url.to_string(),
+        title: parsed.title,
+        content: parsed.content,
+        content_html: parsed.content_html,
+        summary: Some(parsed.summary).filter(|s| !s.is_empty()),
+        url: parsed.url,
         source_name: Some(source.name.clone()),
-        categories: Some(vec![kind.to_string()]), // Placeholder, implement category extraction if needed
+        categories: Some(parsed.categories.iter().map(|score| score.category.clone()).collect()),
+        source_category: parsed.source_category.clone(),
         published_at: Some(Utc::now()), // Placeholder, implement date extraction if needed
         crawled_at: Utc::now(),
         like_count: Some(0),
         comment_count: Some(0),
         favorite_count: Some(0),
+        cover_image_id: None,
+        tags: Vec::new(),
     };
+    Ok(Some(PendingArticle { article, categories: parsed.categories, images: parsed.images }))
+}
 
-    let client = db_pool.get().await.map_err(|e| anyhow::anyhow!("DB Pool error: {}", e))?;
-    match insert_article(&client, &article).await {
-        Ok(_) => log::info!("Successfully inserted article: {}", article.title),
+// 整批文章在一个事务中用一条多行 INSERT 写入；整批失败时回滚，再逐篇写入，只放弃出错的那篇。
+// 按输入顺序返回每篇的结果
+pub async fn insert_crawled_articles(
+    db_pool: &DbPool,
+    articles: &[Article],
+    mode: RecrawlMode,
+) -> Result<Vec<Result<InsertOutcome, tokio_postgres::Error>>, anyhow::Error> {
+    let batch = async {
+        let uow = UnitOfWork::begin(db_pool, Isolation::ReadCommitted).await?;
+        let outcomes = insert_articles(&uow, articles, mode).await?;
+        uow.commit().await?;
+        Ok::<_, ServiceError>(outcomes)
+    };
+    match batch.await {
+        Ok(outcomes) => Ok(outcomes.into_iter().map(Ok).collect()),
         Err(e) => {
-            if e.to_string().contains("duplicate key value violates unique constraint") {
-                log::warn!("Article already exists (based on URL or title): {} - {}", article.url, article.title);
-            } else {
-                log::error!("Failed to insert article \"{}\": {}", article.title, e);
+            log::warn!("Batch insert of {} articles failed, retrying one by one: {}", articles.len(), e);
+            let client = db_pool.get().await.map_err(|e| anyhow::anyhow!("DB Pool error: {}", e))?;
+            let mut results = Vec::with_capacity(articles.len());
+            for article in articles {
+                results.push(insert_article(&client, article, mode).await);
             }
+            Ok(results)
         }
     }
+}
+
+// 写入一批文章，再逐篇完成入库后的处理，按输入顺序返回每篇的结果
+async fn store_articles(
+    fetcher: &dyn HttpFetcher,
+    db_pool: &DbPool,
+    storage: &dyn ImageStorage,
+    pending: &[PendingArticle],
+    mode: RecrawlMode,
+) -> Result<Vec<ArticleOutcome>, anyhow::Error> {
+    let articles: Vec<Article> = pending.iter().map(|p| p.article.clone()).collect();
+    let results = insert_crawled_articles(db_pool, &articles, mode).await?;
 
-    // 提取并保存图片
-    // let title_selector = Selector::parse(&source.title_selector).map_err(|e| anyhow::anyhow!("Invalid title selector: {}",e))?;
-    let image_selector = Selector::parse(&source.image_selector).map_err(|e| anyhow::anyhow!("Invalid image selector: {}",e))?;
-    let mut image_urls = Vec::new();
+    let client = db_pool.get().await.map_err(|e| anyhow::anyhow!("DB Pool error: {}", e))?;
+    let mut outcomes = Vec::with_capacity(pending.len());
+    for (pending, result) in pending.iter().zip(results) {
+        outcomes.push(finish_article(fetcher, &client, storage, pending, result).await);
+    }
+    Ok(outcomes)
+}
 
-    // log::info!("Fetching article content from: {}", article_url);
-    // 每个url仅保存一张图片
-    let mut img_num = 1;
-    let mut img_i = 0;
-    // 收集所有图片URL
-    for img_element in document.select(&image_selector) {
-        if img_i >= img_num
-        {
-            break;
+// 记录写入结果，保存新文章的类别置信度，为新增和更新的文章重建标签和检索索引，下载还没有的图片
+async fn finish_article(
+    fetcher: &dyn HttpFetcher,
+    client: &tokio_postgres::Client,
+    storage: &dyn ImageStorage,
+    pending: &PendingArticle,
+    result: Result<InsertOutcome, tokio_postgres::Error>,
+) -> ArticleOutcome {
+    let article = &pending.article;
+    let (outcome, article_id) = match result {
+        Ok(InsertOutcome::Inserted(stored)) => {
+            log::info!("Inserted article: {}", article.title);
+            (ArticleOutcome::Inserted, stored.id)
         }
-        img_i=img_i+1;
-        if let Some(src) = img_element.value().attr("src") {
-            let absolute_url = if src.starts_with("http") {
-                src.to_string()
-            } else {
-                let base_url = Url::parse(article_url)?;
-                base_url.join(src)?.to_string()
-            };
-            image_urls.push(absolute_url);
-        }
-    }
-
-    // 下载并保存图片
-    for image_url in image_urls {
-        match load_image_from_db(&client, &article_url).await
-        {
-            Ok(Some(_)) => {break;},
-            Ok(None) => {},
-            Err(_) => todo!(),
-        }
-        match download_image(http_client, &image_url,&article_url).await {
-            Ok(mut news_image) => {
-                // news_image.article_id = article.id;
-                match save_image_to_db(&client, &news_image).await {
-                    Ok(_) => {
-                        //log::info!("Load test:");
-                        // match load_image_from_db(&client, &article_url).await {
-                        // Ok(Some(img)) => {
-                        //     log::info!("✅ Loaded image: ID={}", 
-                        //         img.id);
-                        // },
-                        // Ok(None) => log::warn!("⚠️ Image not found in DB"),
-                        // Err(e) => log::error!("❌ Load failed: {}", e),
-                        // }
-                        log::info!("Saved image for article: {}", article.title)},
-                    Err(e) => {
-                        load_image_from_db(&client, &article_url);
-                        log::error!("Failed to save image: {}", e)},
+        // 已有文章的标题或正文变了（更正、补充报道），旧版本已存入修订历史
+        Ok(InsertOutcome::Updated(stored)) => {
+            log::info!("Updated article: {} - {}", article.url, article.title);
+            (ArticleOutcome::Updated, stored.id)
+        }
+        Ok(InsertOutcome::AlreadyExisted(stored)) => {
+            log::info!("Article already exists: {} - {}", article.url, article.title);
+            (ArticleOutcome::Duplicate, stored.id)
+        }
+        Err(e) => {
+            log::error!("Failed to store article \"{}\": {}", article.title, e);
+            return ArticleOutcome::StoreFailed;
+        }
+    };
+
+    if matches!(outcome, ArticleOutcome::Inserted) {
+        if let Err(e) = replace_classifier_labels(client, article_id, &pending.categories).await {
+            log::error!("Failed to store category confidences for {}: {}", article.url, e);
+        }
+    }
+    // 标签和检索索引跟随标题和正文；更新的文章沿用原有类别，人工标注不会被覆盖
+    if matches!(outcome, ArticleOutcome::Inserted | ArticleOutcome::Updated) {
+        let tags = extract_tags(&article.title, &article.content);
+        if let Err(e) = replace_article_tags(client, article_id, &tags).await {
+            log::error!("Failed to store tags for {}: {}", article.url, e);
+        }
+        let (terms, length) = index_terms(&article.title, &article.content);
+        if let Err(e) = replace_search_terms(client, article_id, &terms, length).await {
+            log::error!("Failed to index {} for search: {}", article.url, e);
+        }
+    }
+
+    // 已有图片的文章不再重复下载；旧数据升级后没有图片的文章会在这里补全
+    match count_article_images(client, article_id).await {
+        Ok(0) => {}
+        Ok(_) => return outcome,
+        Err(e) => {
+            log::error!("Failed to look up images for {}: {}", article.url, e);
+            return outcome;
+        }
+    }
+
+    let mut position = 0;
+    for (i, image) in pending.images.iter().take(MAX_IMAGES_PER_ARTICLE).enumerate() {
+        if i > 0 {
+            // 添加延迟以避免被封锁
+            let delay_ms = rand::thread_rng().gen_range(1000..3000);
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+        }
+
+        let stored = match download_image(fetcher, storage, &image.url).await {
+            Ok(stored) => stored,
+            Err(ImageDownloadError::Rejected { rejection, declared_content_type, byte_size }) => {
+                log::warn!("Rejected image {}: {}", image.url, rejection);
+                if let Err(e) = insert_rejected_image(
+                    client,
+                    Some(article_id),
+                    &image.url,
+                    rejection.reason(),
+                    &rejection.to_string(),
+                    declared_content_type.as_deref(),
+                    byte_size.map(|size| size as i64),
+                ).await {
+                    log::error!("Failed to record rejected image {}: {}", image.url, e);
                 }
-                // log::info!("Load test:");
-                // load_image_from_db(&client, &article_url);
+                continue;
+            }
+            Err(ImageDownloadError::Failed(e)) => {
+                log::error!("Failed to download image {}: {}", image.url, e);
+                continue;
+            }
+        };
+
+        let news_image = NewsImage {
+            id: 0, // 将由数据库分配
+            article_id,
+            position,
+            source_url: image.url.clone(),
+            caption: image.caption.clone(),
+            content_hash: stored.content_hash,
+            content_type: stored.content_type,
+            width: stored.width,
+            height: stored.height,
+            storage_path: stored.storage_path,
+            created_at: Utc::now(),
+        };
+        match insert_article_image(client, &news_image).await {
+            Ok(()) => {
+                log::info!("Saved image {} for article: {}", position, article.title);
+                position += 1;
             }
-            Err(e) => log::error!("Failed to download image {}: {}", image_url, e),
+            Err(e) => log::error!("Failed to save image {}: {}", image.url, e),
         }
-        
-        // 添加延迟以避免被封锁
-        let delay_ms = rand::thread_rng().gen_range(1000..3000);
-        tokio::time::sleep(Duration::from_millis(delay_ms)).await;
     }
 
-    Ok(())
+    outcome
 }
 
-async fn run_crawler_once(db_pool: DbPool) {
-    log::info!("Crawler job started.");
-    let http_client = HttpClient::builder() 
-        .timeout(Duration::from_secs(30))
-        // .user_agent(format!("NewsBot/1.0 (+{}; {}@example.com)", روسيا_VERSION, "bot_admin")) // Be a good bot
-        .user_agent(format!("NewsBot/1.0 (+{}; {}@example.com)", "1.0", "bot_admin")) // Be a good bot
-        .build()
-        .expect("Failed to build HTTP client");
+// 写入一批待入库的文章并累计结果
+async fn store_batch(
+    fetcher: &dyn HttpFetcher,
+    db_pool: &DbPool,
+    storage: &dyn ImageStorage,
+    batch: &[PendingArticle],
+    counters: &mut SourceCrawlCounters,
+) {
+    match store_articles(fetcher, db_pool, storage, batch, RecrawlMode::Scheduled).await {
+        Ok(outcomes) => outcomes.into_iter().for_each(|outcome| counters.record_outcome(outcome)),
+        Err(e) => {
+            log::error!("Failed to store {} articles: {}", batch.len(), e);
+            counters.record_error(&e);
+        }
+    }
+}
 
-    let sources = get_news_sources();
+// 谁触发了本次爬取
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrawlTrigger {
+    Schedule,
+    Manual,
+}
+
+impl CrawlTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrawlTrigger::Schedule => "schedule",
+            CrawlTrigger::Manual => "manual",
+        }
+    }
+}
+
+// 配置中所有新闻源的名称（去重，保持顺序）
+pub fn news_source_names() -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for source in get_news_sources() {
+        if !names.contains(&source.name) {
+            names.push(source.name);
+        }
+    }
+    names
+}
+
+// source_filter 为 Some 时只爬取该名称下的新闻源
+async fn run_crawler_once(
+    db_pool: DbPool,
+    storage_config: StorageConfig,
+    source_filter: Option<String>,
+    trigger: CrawlTrigger,
+) {
+    log::info!("Crawler job started ({}, source: {}).", trigger.as_str(), source_filter.as_deref().unwrap_or("all"));
+
+    // 记录运行开始；数据库不可用时照常爬取，只是不留运行记录
+    let run_id = match db_pool.get().await {
+        Ok(client) => match start_crawl_run(&client, trigger.as_str(), Utc::now()).await {
+            Ok(id) => Some(id),
+            Err(e) => {
+                log::error!("Failed to record crawl run start: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            log::error!("DB Pool error while recording crawl run: {}", e);
+            None
+        }
+    };
+
+    // 中途 panic 时同样把运行记为失败，记录之后再继续抛出
+    let result = AssertUnwindSafe(crawl_sources(&db_pool, &storage_config, source_filter, run_id))
+        .catch_unwind()
+        .await;
+    let status = match &result {
+        Ok(Ok(())) => "completed",
+        Ok(Err(e)) => {
+            log::error!("Crawl run failed: {:#}", e);
+            "failed"
+        }
+        Err(_) => {
+            log::error!("Crawl run panicked");
+            "failed"
+        }
+    };
+
+    if let Some(run_id) = run_id {
+        match db_pool.get().await {
+            Ok(client) => {
+                if let Err(e) = finish_crawl_run(&client, run_id, status, Utc::now()).await {
+                    log::error!("Failed to record crawl run finish: {}", e);
+                }
+            }
+            Err(e) => log::error!("DB Pool error while finishing crawl run: {}", e),
+        }
+    }
+    if let Err(panic) = result {
+        std::panic::resume_unwind(panic);
+    }
+    log::info!("Crawler job finished.");
+}
+
+// 依次爬取各新闻源；无法开始爬取或所有新闻源都出错时返回错误
+async fn crawl_sources(
+    db_pool: &DbPool,
+    storage_config: &StorageConfig,
+    source_filter: Option<String>,
+    run_id: Option<Uuid>,
+) -> Result<(), anyhow::Error> {
+    let fetcher = ReqwestFetcher::new().context("Failed to build HTTP client")?;
+    // 存储客户端要在爬虫自己的运行时里创建
+    let storage = build_storage(storage_config).context("Failed to open image storage")?;
+    // 训练命令写入的新模型和编辑修改的类别表都从下一次爬取开始生效
+    reload_classifier();
+    let taxonomy = load_crawl_taxonomy(db_pool).await;
+
+    let sources = get_news_sources()
+        .into_iter()
+        .filter(|source| source_filter.as_ref().is_none_or(|name| &source.name == name));
+    let mut sources_crawled = 0;
+    let mut sources_failed = 0;
     for source in sources {
         log::info!("Processing source: {}", source.name);
-        match fetch_article_urls(&http_client, &source) .await {
+        let source_started_at = Utc::now();
+        let mut counters = SourceCrawlCounters::default();
+        match fetch_article_urls(&fetcher, &source).await {
             Ok(urls) => {
+                counters.urls_found = urls.len() as i32;
+                // 解析好的文章攒够一批再一起写入
+                let mut pending = Vec::with_capacity(ARTICLE_BATCH_SIZE);
                 for (i, url) in urls.iter().enumerate() {
                     if i > 0 { // Add delay between fetching individual articles from the same source
                         let delay_ms = rand::thread_rng().gen_range(1000..5000); // 1-5 seconds
                         tokio::time::sleep(Duration::from_millis(delay_ms)).await;
                     }
-                    if let Err(e) = fetch_and_parse_article(&http_client, &db_pool, url, &source) .await {
-                        log::error!("Error processing article {}: {}", url, e);
+                    match prepare_article(&fetcher, taxonomy.as_ref(), url, &source).await {
+                        Ok(Some(article)) => pending.push(article),
+                        Ok(None) => counters.record_outcome(ArticleOutcome::ParseFailed),
+                        Err(e) => {
+                            log::error!("Error processing article {}: {}", url, e);
+                            counters.record_error(&e);
+                        }
                     }
+                    if pending.len() >= ARTICLE_BATCH_SIZE {
+                        store_batch(&fetcher, db_pool, storage.as_ref(), &pending, &mut counters).await;
+                        pending.clear();
+                    }
+                }
+                if !pending.is_empty() {
+                    store_batch(&fetcher, db_pool, storage.as_ref(), &pending, &mut counters).await;
                 }
             }
             Err(e) => {
                 log::error!("Failed to fetch article URLs for source {}: {}", source.name, e);
+                counters.record_error(&e);
             }
         }
+
+        let changed = counters.articles_inserted + counters.articles_updated;
+        sources_crawled += 1;
+        if counters.failed() {
+            sources_failed += 1;
+        }
+        if let Some(run_id) = run_id {
+            record_source_stats(db_pool, run_id, &source, source_started_at, counters).await;
+        }
+        // 新增和更新的文章的标题和标签加入搜索自动补全
+        if changed > 0 {
+            match db_pool.get().await {
+                Ok(client) => {
+                    if let Err(e) = rebuild_suggestions(&client).await {
+                        log::error!("Failed to rebuild search suggestions: {}", e);
+                    }
+                }
+                Err(e) => log::error!("DB Pool error while rebuilding search suggestions: {}", e),
+            }
+        }
+
         // Add a longer delay between processing different sources
         let source_delay_ms = rand::thread_rng().gen_range(5000..15000); // 5-15 seconds
         tokio::time::sleep(Duration::from_millis(source_delay_ms)).await;
     }
-    log::info!("Crawler job finished.");
+
+    if sources_crawled > 0 && sources_failed == sources_crawled {
+        return Err(anyhow!("All {} sources failed", sources_crawled));
+    }
+    Ok(())
 }
 
-pub async fn init_crawler_scheduler(pool: DbPool, cron_expression: &str) -> Result<(), JobSchedulerError> {
-    let sched = JobScheduler::new().await?;
-    let job_pool = pool.clone();
+// 类别表读取失败或为空时返回 None，直接使用分类器给出的名称
+async fn load_crawl_taxonomy(db_pool: &DbPool) -> Option<CategoryTaxonomy> {
+    let taxonomy = match db_pool.get().await {
+        Ok(client) => load_taxonomy(&client).await.map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    match taxonomy {
+        Ok(taxonomy) if !taxonomy.categories().is_empty() => Some(taxonomy),
+        Ok(_) => None,
+        Err(e) => {
+            log::error!("Failed to load the category taxonomy: {}", e);
+            None
+        }
+    }
+}
+
+// 文章对应的新闻源配置：优先按来源名称，找不到时按文章地址的域名
+pub fn find_news_source(article_url: &str, source_name: Option<&str>) -> Option<NewsSource> {
+    let sources = get_news_sources();
+    let host = |url: &str| Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_string));
+    let article_host = host(article_url);
+    sources
+        .iter()
+        .find(|source| Some(source.name.as_str()) == source_name)
+        .or_else(|| sources.iter().find(|source| article_host.is_some() && host(&source.url) == article_host))
+        .cloned()
+}
 
-    let job = Job::new_async(cron_expression, move |_uuid, _l| {
-        let current_pool = job_pool.clone();
+// 重新抓取一篇已有文章（管理员操作）。内容有变化时用抓取结果覆盖（包括管理员编辑过的文章）、保存修订并重建标签和检索索引，返回是否有变化
+pub async fn recrawl_article(
+    db_pool: &DbPool,
+    storage: &dyn ImageStorage,
+    article_url: &str,
+    source: &NewsSource,
+) -> Result<bool, anyhow::Error> {
+    let fetcher = ReqwestFetcher::new()?;
+    let taxonomy = load_crawl_taxonomy(db_pool).await;
+    let Some(pending) = prepare_article(&fetcher, taxonomy.as_ref(), article_url, source).await? else {
+        return Err(anyhow!("Could not extract an article from {}", article_url));
+    };
+    match store_articles(&fetcher, db_pool, storage, std::slice::from_ref(&pending), RecrawlMode::Requested).await?.pop() {
+        Some(ArticleOutcome::Updated) => Ok(true),
+        Some(ArticleOutcome::Inserted | ArticleOutcome::Duplicate) => Ok(false),
+        _ => Err(anyhow!("Failed to store the re-crawled article {}", article_url)),
+    }
+}
+
+async fn record_source_stats(
+    db_pool: &DbPool,
+    run_id: Uuid,
+    source: &NewsSource,
+    started_at: chrono::DateTime<Utc>,
+    counters: SourceCrawlCounters,
+) {
+    if counters.urls_found > 0 && counters.articles_inserted + counters.articles_updated + counters.duplicates == 0 {
+        log::warn!(
+            "Source {} ({}) yielded no articles from {} URLs; selectors may be broken",
+            source.name, source.link_selector, counters.urls_found
+        );
+    }
+
+    let stats = CrawlSourceStats {
+        id: Uuid::new_v4(),
+        run_id,
+        source_name: source.name.clone(),
+        listing_url: source.url.clone(),
+        link_selector: source.link_selector.clone(),
+        started_at,
+        finished_at: Utc::now(),
+        urls_found: counters.urls_found,
+        articles_inserted: counters.articles_inserted,
+        articles_updated: counters.articles_updated,
+        duplicates: counters.duplicates,
+        parse_failures: counters.parse_failures,
+        http_errors: counters.http_errors,
+        last_error: counters.last_error,
+    };
+
+    // 分源统计和运行汇总一起写入
+    let recorded = async {
+        let uow = UnitOfWork::begin(db_pool, Isolation::ReadCommitted).await?;
+        insert_crawl_source_stats(&uow, &stats).await?;
+        uow.commit().await
+    };
+    if let Err(e) = recorded.await {
+        log::error!("Failed to record crawl stats for source {}: {}", source.name, e);
+    }
+}
+
+struct ScheduleState {
+    job_id: Option<Uuid>, // None while the schedule is paused
+    cron_expression: String,
+}
+
+// 保证同一时间只有一次爬取在运行；scheduled 与手动触发共用
+type RunSlot = Arc<std::sync::Mutex<Option<CurrentCrawlRun>>>;
+
+// 爬取结束（包括 panic）时释放运行槽
+struct RunSlotGuard(RunSlot);
+
+impl Drop for RunSlotGuard {
+    fn drop(&mut self) {
+        // 锁被 panic 污染时同样清空，否则之后的爬取都会被当作已在运行而拒绝
+        let mut slot = self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *slot = None;
+    }
+}
+
+// 占用运行槽并在独立线程上执行一次爬取；已有爬取在运行时返回 false
+fn spawn_crawl(
+    pool: DbPool,
+    storage_config: StorageConfig,
+    run_slot: RunSlot,
+    source_filter: Option<String>,
+    trigger: CrawlTrigger,
+) -> bool {
+    {
+        let mut slot = run_slot.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if slot.is_some() {
+            return false;
+        }
+        *slot = Some(CurrentCrawlRun {
+            trigger: trigger.as_str().to_string(),
+            source: source_filter.clone(),
+            started_at: Utc::now(),
+        });
+    }
+
+    // 使用 spawn_blocking + block_on 来规避 Non-Send 问题
+    task::spawn_blocking(move || {
+        let _guard = RunSlotGuard(run_slot);
+        let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
+        rt.block_on(run_crawler_once(pool, storage_config, source_filter, trigger));
+    });
+    true
+}
+
+fn build_crawl_job(
+    cron_expression: &str,
+    pool: DbPool,
+    storage_config: StorageConfig,
+    run_slot: RunSlot,
+) -> Result<Job, JobSchedulerError> {
+    Job::new_async(cron_expression, move |_uuid, _l| {
+        let current_pool = pool.clone();
+        let current_storage = storage_config.clone();
+        let current_slot = run_slot.clone();
         Box::pin(async move {
-            // 使用 spawn_blocking + block_on 来规避 Non-Send 问题
-            task::spawn_blocking(move || {
-                let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
-
-                // 显式忽略返回值以满足返回类型为 ()
-                let _ = rt.block_on(async move {
-                    run_crawler_once(current_pool).await
-                });
-            });
-            // 必须显式返回 unit 类型
-            ()
+            if !spawn_crawl(current_pool, current_storage, current_slot, None, CrawlTrigger::Schedule) {
+                log::info!("Skipping scheduled crawl: a crawl is already in progress");
+            }
         })
-    })?;
+    })
+}
+
+// 爬虫调度与手动控制的入口，注册为 actix 的 app_data
+#[derive(Clone)]
+pub struct CrawlerController {
+    pool: DbPool,
+    storage_config: StorageConfig,
+    scheduler: JobScheduler,
+    schedule: Arc<tokio::sync::Mutex<ScheduleState>>,
+    run_slot: RunSlot,
+}
+
+impl CrawlerController {
+    // 立即触发一次爬取；已有爬取在运行时返回 false
+    pub fn trigger_run(&self, source_filter: Option<String>) -> bool {
+        spawn_crawl(
+            self.pool.clone(),
+            self.storage_config.clone(),
+            self.run_slot.clone(),
+            source_filter,
+            CrawlTrigger::Manual,
+        )
+    }
+
+    pub async fn pause(&self) -> Result<(), JobSchedulerError> {
+        let mut schedule = self.schedule.lock().await;
+        if let Some(job_id) = schedule.job_id.take() {
+            self.scheduler.remove(&job_id).await?;
+            log::info!("Crawler schedule paused");
+        }
+        Ok(())
+    }
+
+    pub async fn resume(&self) -> Result<(), JobSchedulerError> {
+        let mut schedule = self.schedule.lock().await;
+        if schedule.job_id.is_none() {
+            let job = build_crawl_job(
+                &schedule.cron_expression,
+                self.pool.clone(),
+                self.storage_config.clone(),
+                self.run_slot.clone(),
+            )?;
+            schedule.job_id = Some(self.scheduler.add(job).await?);
+            log::info!("Crawler schedule resumed with cron: {}", schedule.cron_expression);
+        }
+        Ok(())
+    }
+
+    // 更换 cron 表达式；表达式无效时保持原调度不变。暂停状态下只记录新表达式
+    pub async fn reschedule(&self, cron_expression: &str) -> Result<(), JobSchedulerError> {
+        let job = build_crawl_job(
+            cron_expression,
+            self.pool.clone(),
+            self.storage_config.clone(),
+            self.run_slot.clone(),
+        )?;
+        let mut schedule = self.schedule.lock().await;
+        if let Some(old_job_id) = schedule.job_id {
+            self.scheduler.remove(&old_job_id).await?;
+            schedule.job_id = Some(self.scheduler.add(job).await?);
+        }
+        schedule.cron_expression = cron_expression.to_string();
+        log::info!("Crawler schedule changed to cron: {}", cron_expression);
+        Ok(())
+    }
+
+    pub async fn status(&self) -> CrawlerStatus {
+        let current_run = self.run_slot
+            .lock()
+            .map(|slot| slot.clone())
+            .unwrap_or(None);
+        let mut scheduler = self.scheduler.clone();
+        let schedule = self.schedule.lock().await;
+        let next_run_at = match schedule.job_id {
+            Some(job_id) => scheduler.next_tick_for_job(job_id).await.ok().flatten(),
+            None => None,
+        };
+
+        CrawlerStatus {
+            running: current_run.is_some(),
+            current_run,
+            paused: schedule.job_id.is_none(),
+            cron_expression: schedule.cron_expression.clone(),
+            next_run_at,
+        }
+    }
+}
 
-    sched.add(job).await?;
+pub async fn init_crawler_scheduler(
+    pool: DbPool,
+    storage_config: StorageConfig,
+    cron_expression: &str,
+) -> Result<CrawlerController, JobSchedulerError> {
+    // 上次进程退出时没有结束的运行不会再结束，在开始调度前标记为失败
+    match pool.get().await {
+        Ok(client) => match fail_unfinished_crawl_runs(&client).await {
+            Ok(0) => {}
+            Ok(count) => log::warn!("Marked {} unfinished crawl runs from a previous process as failed", count),
+            Err(e) => log::error!("Failed to close unfinished crawl runs: {}", e),
+        },
+        Err(e) => log::error!("DB Pool error while closing unfinished crawl runs: {}", e),
+    }
+
+    let sched = JobScheduler::new().await?;
+    let run_slot: RunSlot = Arc::new(std::sync::Mutex::new(None));
+
+    let job = build_crawl_job(cron_expression, pool.clone(), storage_config.clone(), run_slot.clone())?;
+    let job_id = sched.add(job).await?;
     log::info!(
         "Crawler scheduler initialized. Job added with cron: {}",
         cron_expression
     );
     sched.start().await?;
-    Ok(())
+
+    Ok(CrawlerController {
+        pool,
+        storage_config,
+        scheduler: sched,
+        schedule: Arc::new(tokio::sync::Mutex::new(ScheduleState {
+            job_id: Some(job_id),
+            cron_expression: cron_expression.to_string(),
+        })),
+        run_slot,
+    })
 }
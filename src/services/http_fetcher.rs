@@ -0,0 +1,140 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// 爬虫访问网络的唯一入口；线上使用 reqwest，测试中回放录制好的页面
+
+#[derive(Debug, thiserror::Error)]
+pub enum FetchError {
+    #[error(transparent)]
+    Request(#[from] reqwest::Error),
+    #[error("No fixture recorded for {0}")]
+    MissingFixture(String),
+    #[error("Failed to read fixture {path}: {source}")]
+    Fixture {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Response body exceeds {limit} bytes")]
+    TooLarge { limit: usize },
+}
+
+// 二进制响应（图片等）
+#[derive(Debug, Clone)]
+pub struct FetchedBody {
+    pub bytes: Vec<u8>,
+    pub content_type: Option<String>,
+}
+
+#[async_trait]
+pub trait HttpFetcher: Send + Sync {
+    // 获取页面文本，非 2xx 状态视为错误
+    async fn get_text(&self, url: &str) -> Result<String, FetchError>;
+
+    // 获取二进制内容及其 Content-Type，非 2xx 状态视为错误；
+    // 响应体超过 max_bytes 时立即中断并返回 TooLarge
+    async fn get_bytes(&self, url: &str, max_bytes: usize) -> Result<FetchedBody, FetchError>;
+}
+
+pub struct ReqwestFetcher {
+    client: reqwest::Client,
+}
+
+impl ReqwestFetcher {
+    pub fn new() -> Result<Self, reqwest::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .user_agent(format!("NewsBot/1.0 (+{}; {}@example.com)", "1.0", "bot_admin")) // Be a good bot
+            .build()?;
+        Ok(ReqwestFetcher { client })
+    }
+}
+
+#[async_trait]
+impl HttpFetcher for ReqwestFetcher {
+    async fn get_text(&self, url: &str) -> Result<String, FetchError> {
+        // text() 会按响应头中的 charset 解码（人民网等站点使用 GBK）
+        Ok(self.client.get(url).send().await?.error_for_status()?.text().await?)
+    }
+
+    async fn get_bytes(&self, url: &str, max_bytes: usize) -> Result<FetchedBody, FetchError> {
+        let mut response = self.client.get(url).send().await?.error_for_status()?;
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|h| h.to_str().ok())
+            .map(|s| s.to_string());
+
+        // Content-Length 可能缺失或不准确，声明超限时直接放弃，否则边读边计数
+        if response.content_length().is_some_and(|len| len > max_bytes as u64) {
+            return Err(FetchError::TooLarge { limit: max_bytes });
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(FetchError::TooLarge { limit: max_bytes });
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(FetchedBody { bytes, content_type })
+    }
+}
+
+// manifest.json 中的一条记录：URL 对应的本地文件
+#[derive(Debug, Clone, Deserialize)]
+pub struct FixtureEntry {
+    pub url: String,
+    pub file: String,
+    pub content_type: Option<String>,
+}
+
+// 按 manifest.json 回放录制好的响应，没有录制的 URL 返回 MissingFixture
+pub struct FixtureFetcher {
+    root: PathBuf,
+    routes: HashMap<String, FixtureEntry>,
+}
+
+impl FixtureFetcher {
+    pub fn from_dir(root: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let root = root.as_ref().to_path_buf();
+        let manifest_path = root.join("manifest.json");
+        let manifest = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", manifest_path.display(), e))?;
+        let entries: Vec<FixtureEntry> = serde_json::from_str(&manifest)?;
+        let routes = entries.into_iter().map(|entry| (entry.url.clone(), entry)).collect();
+        Ok(FixtureFetcher { root, routes })
+    }
+
+    fn read(&self, url: &str) -> Result<(Vec<u8>, &FixtureEntry), FetchError> {
+        let entry = self
+            .routes
+            .get(url)
+            .ok_or_else(|| FetchError::MissingFixture(url.to_string()))?;
+        let path = self.root.join(&entry.file);
+        let bytes = std::fs::read(&path).map_err(|source| FetchError::Fixture {
+            path: path.display().to_string(),
+            source,
+        })?;
+        Ok((bytes, entry))
+    }
+}
+
+#[async_trait]
+impl HttpFetcher for FixtureFetcher {
+    async fn get_text(&self, url: &str) -> Result<String, FetchError> {
+        let (bytes, _) = self.read(url)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    async fn get_bytes(&self, url: &str, max_bytes: usize) -> Result<FetchedBody, FetchError> {
+        let (bytes, entry) = self.read(url)?;
+        if bytes.len() > max_bytes {
+            return Err(FetchError::TooLarge { limit: max_bytes });
+        }
+        Ok(FetchedBody { bytes, content_type: entry.content_type.clone() })
+    }
+}
@@ -0,0 +1,591 @@
+use image::{imageops::FilterType, DynamicImage, ImageFormat, ImageReader, Limits};
+use sha2::{Digest, Sha256};
+
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use crate::db::connection::DbPool;
+use crate::db::image_queries::{list_all_images, update_image_storage_path};
+use crate::services::storage::{ImageStorage, StorageError};
+use crate::utils::html_sanitizer::sanitize_svg;
+
+// 图片按内容的 SHA-256 存储，键为 <前两位>/<hash>.<ext>，
+// 缩略图的键为 thumbs/<宽度>/<前两位>/<hash>.jpg，
+// 按请求缩放、转码的派生图缓存在 variants/<前两位>/ 下。
+// 同一张图被多篇文章引用时只保存一份。键相对于存储后端的根（见 services::storage）
+
+// 早期版本把本地相对路径 storage/img/<键> 直接写进了 news_images.storage_path
+const LEGACY_PATH_PREFIX: &str = "storage/img/";
+
+// 生成的缩略图宽度（像素）；原图不比该宽度大时不生成，直接使用原图
+pub const THUMBNAIL_WIDTHS: &[u32] = &[240, 640];
+
+const THUMBNAIL_JPEG_QUALITY: u8 = 80;
+
+// 单张图片允许下载的最大字节数
+pub const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+// 单边最大像素与总像素上限，防止解码炸弹
+const MAX_DIMENSION: u32 = 10_000;
+const MAX_PIXELS: u64 = 40_000_000;
+
+// 小于该尺寸的通常是统计像素或占位图
+const MIN_DIMENSION: u32 = 16;
+
+// 解码时允许分配的最大内存
+const MAX_DECODE_ALLOC: u64 = 512 * 1024 * 1024;
+
+// 图片被拒绝的原因，reason() 的取值写入 rejected_images.reason
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ImageRejection {
+    #[error("image exceeds {limit} bytes")]
+    TooLarge { limit: usize },
+    #[error("content is not a supported image format")]
+    NotAnImage,
+    #[error("image could not be decoded: {0}")]
+    Undecodable(String),
+    #[error("image dimensions {width}x{height} exceed the limit")]
+    DimensionsTooLarge { width: u32, height: u32 },
+    #[error("image dimensions {width}x{height} are too small")]
+    TooSmall { width: u32, height: u32 },
+}
+
+impl ImageRejection {
+    pub fn reason(&self) -> &'static str {
+        match self {
+            ImageRejection::TooLarge { .. } => "too_large",
+            ImageRejection::NotAnImage => "not_an_image",
+            ImageRejection::Undecodable(_) => "undecodable",
+            ImageRejection::DimensionsTooLarge { .. } => "dimensions_too_large",
+            ImageRejection::TooSmall { .. } => "too_small",
+        }
+    }
+}
+
+// 按文件头识别出的图片格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageKind {
+    Jpeg,
+    Png,
+    Gif,
+    WebP,
+    Svg,
+}
+
+impl ImageKind {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ImageKind::Jpeg => "image/jpeg",
+            ImageKind::Png => "image/png",
+            ImageKind::Gif => "image/gif",
+            ImageKind::WebP => "image/webp",
+            ImageKind::Svg => "image/svg+xml",
+        }
+    }
+
+    fn raster_format(&self) -> Option<ImageFormat> {
+        match self {
+            ImageKind::Jpeg => Some(ImageFormat::Jpeg),
+            ImageKind::Png => Some(ImageFormat::Png),
+            ImageKind::Gif => Some(ImageFormat::Gif),
+            ImageKind::WebP => Some(ImageFormat::WebP),
+            ImageKind::Svg => None,
+        }
+    }
+}
+
+// 通过校验、可以保存的图片；SVG 的 bytes 是清洗后的内容
+pub struct ValidatedImage {
+    pub kind: ImageKind,
+    pub bytes: Vec<u8>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    decoded: Option<DynamicImage>,
+}
+
+// 保存后的图片信息
+#[derive(Debug, Clone)]
+pub struct StoredImage {
+    pub content_hash: String,
+    pub content_type: String,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub storage_path: String,
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn extension_for(content_type: &str) -> &'static str {
+    match content_type {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        _ => "jpg",
+    }
+}
+
+pub fn original_key(hash: &str, content_type: &str) -> String {
+    format!("{}/{}.{}", &hash[..2], hash, extension_for(content_type))
+}
+
+pub fn thumbnail_key(hash: &str, width: u32) -> String {
+    format!("thumbs/{}/{}/{}.jpg", width, &hash[..2], hash)
+}
+
+// news_images.storage_path 对应的存储键，兼容旧的本地路径写法
+pub fn storage_key(storage_path: &str) -> &str {
+    storage_path.strip_prefix(LEGACY_PATH_PREFIX).unwrap_or(storage_path)
+}
+
+// 只看文件头魔数，不信任响应头中的 Content-Type
+pub fn sniff_image_kind(bytes: &[u8]) -> Option<ImageKind> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(ImageKind::Jpeg);
+    }
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(ImageKind::Png);
+    }
+    if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        return Some(ImageKind::Gif);
+    }
+    if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        return Some(ImageKind::WebP);
+    }
+    if looks_like_svg(bytes) {
+        return Some(ImageKind::Svg);
+    }
+    None
+}
+
+// SVG 是文本：跳过 BOM、XML 声明、DOCTYPE 和注释后应以 <svg 开头
+fn looks_like_svg(bytes: &[u8]) -> bool {
+    let head = &bytes[..bytes.len().min(4096)];
+    let Ok(text) = std::str::from_utf8(head).or_else(|e| std::str::from_utf8(&head[..e.valid_up_to()])) else {
+        return false;
+    };
+    let mut rest = text.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        if rest.starts_with("<?") {
+            rest = match rest.find("?>") {
+                Some(end) => rest[end + 2..].trim_start(),
+                None => return false,
+            };
+        } else if rest.starts_with("<!--") {
+            rest = match rest.find("-->") {
+                Some(end) => rest[end + 3..].trim_start(),
+                None => return false,
+            };
+        } else if rest.starts_with("<!") {
+            rest = match rest.find('>') {
+                Some(end) => rest[end + 1..].trim_start(),
+                None => return false,
+            };
+        } else {
+            return rest.starts_with("<svg");
+        }
+    }
+}
+
+fn check_dimensions(width: u32, height: u32) -> Result<(), ImageRejection> {
+    if width > MAX_DIMENSION || height > MAX_DIMENSION || width as u64 * height as u64 > MAX_PIXELS {
+        return Err(ImageRejection::DimensionsTooLarge { width, height });
+    }
+    if width < MIN_DIMENSION || height < MIN_DIMENSION {
+        return Err(ImageRejection::TooSmall { width, height });
+    }
+    Ok(())
+}
+
+// 校验下载到的内容：大小、格式、尺寸，并完整解码一次；SVG 清洗掉脚本后保存
+pub fn validate_image(bytes: Vec<u8>) -> Result<ValidatedImage, ImageRejection> {
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(ImageRejection::TooLarge { limit: MAX_IMAGE_BYTES });
+    }
+    let kind = sniff_image_kind(&bytes).ok_or(ImageRejection::NotAnImage)?;
+
+    let Some(format) = kind.raster_format() else {
+        let text = String::from_utf8_lossy(&bytes);
+        let cleaned = sanitize_svg(&text).ok_or(ImageRejection::NotAnImage)?;
+        return Ok(ValidatedImage { kind, bytes: cleaned.into_bytes(), width: None, height: None, decoded: None });
+    };
+
+    // 先只读文件头拿到尺寸，超限的图片不进入解码
+    let (width, height) = ImageReader::with_format(Cursor::new(&bytes), format)
+        .into_dimensions()
+        .map_err(|e| ImageRejection::Undecodable(e.to_string()))?;
+    check_dimensions(width, height)?;
+
+    let mut reader = ImageReader::with_format(Cursor::new(&bytes), format);
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    reader.limits(limits);
+    let decoded = reader.decode().map_err(|e| ImageRejection::Undecodable(e.to_string()))?;
+
+    Ok(ValidatedImage { kind, bytes, width: Some(width), height: Some(height), decoded: Some(decoded) })
+}
+
+fn encode_thumbnail(image: &DynamicImage, width: u32) -> Result<Vec<u8>, image::ImageError> {
+    let thumbnail = image.resize(width, u32::MAX, FilterType::Lanczos3);
+    let mut buffer = Vec::new();
+    let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, THUMBNAIL_JPEG_QUALITY);
+    // JPEG 不支持透明通道，统一转为 RGB
+    DynamicImage::ImageRgb8(thumbnail.to_rgb8()).write_with_encoder(encoder)?;
+    Ok(buffer)
+}
+
+async fn write_if_missing(
+    storage: &dyn ImageStorage,
+    key: &str,
+    bytes: &[u8],
+    content_type: &str,
+) -> Result<(), StorageError> {
+    if storage.exists(key).await? {
+        return Ok(());
+    }
+    storage.put(key, bytes, content_type).await
+}
+
+// 保存校验过的原图并生成缩略图；已存在相同内容的文件时不重复写入
+pub async fn store_image(storage: &dyn ImageStorage, image: &ValidatedImage) -> Result<StoredImage, anyhow::Error> {
+    let hash = content_hash(&image.bytes);
+    let content_type = image.kind.content_type().to_string();
+    let key = original_key(&hash, &content_type);
+    write_if_missing(storage, &key, &image.bytes, &content_type).await?;
+
+    // SVG 没有解码结果，不生成缩略图
+    if let Some(decoded) = &image.decoded {
+        for &width in THUMBNAIL_WIDTHS.iter().filter(|&&w| w < decoded.width()) {
+            let thumb_key = thumbnail_key(&hash, width);
+            if storage.exists(&thumb_key).await? {
+                continue;
+            }
+            match encode_thumbnail(decoded, width) {
+                Ok(thumb) => storage.put(&thumb_key, &thumb, "image/jpeg").await?,
+                Err(e) => log::warn!("Failed to create {}px thumbnail for {}: {}", width, hash, e),
+            }
+        }
+    }
+
+    Ok(StoredImage {
+        content_hash: hash,
+        content_type,
+        width: image.width.map(|w| w as i32),
+        height: image.height.map(|h| h as i32),
+        storage_path: key,
+    })
+}
+
+// 按请求生成的派生图（缩放、转码）缓存在 variants/ 下，键包含尺寸、裁剪方式和格式
+
+// 派生图单边最大尺寸；不会放大超过原图
+pub const MAX_VARIANT_DIMENSION: u32 = 2048;
+
+const VARIANT_JPEG_QUALITY: u8 = 80;
+const VARIANT_AVIF_QUALITY: u8 = 70;
+// AVIF 编码较慢，取偏快的速度档（1-10）
+const VARIANT_AVIF_SPEED: u8 = 8;
+
+// 同时给出宽和高时的缩放方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FitMode {
+    // 等比缩放到框内
+    #[default]
+    Contain,
+    // 等比缩放铺满后居中裁剪
+    Cover,
+    // 拉伸到指定尺寸
+    Fill,
+}
+
+impl FitMode {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FitMode::Contain => "contain",
+            FitMode::Cover => "cover",
+            FitMode::Fill => "fill",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VariantRequest {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: FitMode,
+}
+
+impl VariantRequest {
+    fn is_resize(&self) -> bool {
+        self.width.is_some() || self.height.is_some()
+    }
+}
+
+// 实际返回给客户端的格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Original,
+    Avif,
+    WebP,
+    Jpeg,
+    Png,
+}
+
+impl OutputFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            OutputFormat::Avif => "image/avif",
+            OutputFormat::WebP => "image/webp",
+            OutputFormat::Png => "image/png",
+            OutputFormat::Jpeg | OutputFormat::Original => "image/jpeg",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Avif => "avif",
+            OutputFormat::WebP => "webp",
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg | OutputFormat::Original => "jpg",
+        }
+    }
+}
+
+// Accept 头中明确列出（q > 0）的类型；*/* 不算，避免给不支持的客户端返回 AVIF/WebP
+fn accepts(accept: &str, mime: &str) -> bool {
+    accept.split(',').any(|part| {
+        let mut params = part.split(';');
+        if params.next().map(str::trim) != Some(mime) {
+            return false;
+        }
+        params
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .all(|q| q.trim().parse::<f32>().map(|q| q > 0.0).unwrap_or(true))
+    })
+}
+
+// 按 Accept 头选择输出格式：AVIF > WebP > 原格式/JPEG。
+// SVG 和不缩放的 GIF（可能是动图）总是原样返回；不缩放且客户端不支持更优格式时也返回原图
+pub fn negotiate_format(accept: Option<&str>, original_content_type: &str, request: &VariantRequest) -> OutputFormat {
+    let resize = request.is_resize();
+    match original_content_type {
+        "image/svg+xml" => return OutputFormat::Original,
+        "image/gif" if !resize => return OutputFormat::Original,
+        _ => {}
+    }
+
+    let accept = accept.unwrap_or("");
+    if accepts(accept, "image/avif") && original_content_type != "image/avif" {
+        return OutputFormat::Avif;
+    }
+    if accepts(accept, "image/webp") {
+        return if !resize && original_content_type == "image/webp" { OutputFormat::Original } else { OutputFormat::WebP };
+    }
+    if !resize && matches!(original_content_type, "image/jpeg" | "image/png") {
+        return OutputFormat::Original;
+    }
+    // PNG 可能带透明通道，缩放后仍用 PNG
+    if original_content_type == "image/png" {
+        OutputFormat::Png
+    } else {
+        OutputFormat::Jpeg
+    }
+}
+
+pub fn variant_key(hash: &str, request: &VariantRequest, format: OutputFormat) -> String {
+    format!(
+        "variants/{}/{}-{}x{}-{}.{}",
+        &hash[..2],
+        hash,
+        request.width.unwrap_or(0),
+        request.height.unwrap_or(0),
+        request.fit.as_str(),
+        format.extension()
+    )
+}
+
+// 返回给客户端的图片内容
+#[derive(Debug, Clone)]
+pub struct ImageVariant {
+    pub bytes: Vec<u8>,
+    pub content_type: String,
+    pub etag: String, // 已带引号的强 ETag
+}
+
+fn resize(image: &DynamicImage, request: &VariantRequest) -> DynamicImage {
+    // 不放大：目标尺寸超过原图时按原图尺寸处理
+    let width = request.width.map(|w| w.min(image.width()));
+    let height = request.height.map(|h| h.min(image.height()));
+    match (width, height) {
+        (Some(w), None) => image.resize(w, u32::MAX, FilterType::Lanczos3),
+        (None, Some(h)) => image.resize(u32::MAX, h, FilterType::Lanczos3),
+        (Some(w), Some(h)) => match request.fit {
+            FitMode::Contain => image.resize(w, h, FilterType::Lanczos3),
+            FitMode::Cover => image.resize_to_fill(w, h, FilterType::Lanczos3),
+            FitMode::Fill => image.resize_exact(w, h, FilterType::Lanczos3),
+        },
+        (None, None) => image.clone(),
+    }
+}
+
+fn encode_variant(image: &DynamicImage, format: OutputFormat) -> Result<Vec<u8>, image::ImageError> {
+    let mut buffer = Vec::new();
+    match format {
+        OutputFormat::Avif => {
+            let encoder = image::codecs::avif::AvifEncoder::new_with_speed_quality(
+                &mut buffer,
+                VARIANT_AVIF_SPEED,
+                VARIANT_AVIF_QUALITY,
+            );
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)?;
+        }
+        OutputFormat::WebP => {
+            // image 只提供无损 WebP 编码
+            let encoder = image::codecs::webp::WebPEncoder::new_lossless(&mut buffer);
+            DynamicImage::ImageRgba8(image.to_rgba8()).write_with_encoder(encoder)?;
+        }
+        OutputFormat::Png => {
+            let encoder = image::codecs::png::PngEncoder::new(&mut buffer);
+            image.write_with_encoder(encoder)?;
+        }
+        OutputFormat::Jpeg | OutputFormat::Original => {
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut buffer, VARIANT_JPEG_QUALITY);
+            DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(encoder)?;
+        }
+    }
+    Ok(buffer)
+}
+
+// 返回请求对应的图片，派生图不存在时生成并写入缓存。
+// 原图不存在时返回 None；缩放或编码失败时记日志并退回原图
+pub async fn render_variant(
+    storage: &dyn ImageStorage,
+    hash: &str,
+    storage_path: &str,
+    content_type: &str,
+    request: &VariantRequest,
+    accept: Option<&str>,
+) -> Result<Option<ImageVariant>, StorageError> {
+    let original_etag = format!("\"{}\"", hash);
+    let format = negotiate_format(accept, content_type, request);
+    if format == OutputFormat::Original {
+        return Ok(storage.get(storage_key(storage_path)).await?.map(|bytes| ImageVariant {
+            bytes,
+            content_type: content_type.to_string(),
+            etag: original_etag,
+        }));
+    }
+
+    let key = variant_key(hash, request, format);
+    let variant_etag = format!("\"{}\"", key.rsplit('/').next().unwrap_or(&key));
+    let variant = |bytes| ImageVariant {
+        bytes,
+        content_type: format.content_type().to_string(),
+        etag: variant_etag.clone(),
+    };
+    if let Some(bytes) = storage.get(&key).await? {
+        return Ok(Some(variant(bytes)));
+    }
+
+    // 下载时预生成的 JPEG 缩略图可以直接用
+    if format == OutputFormat::Jpeg && request.height.is_none() {
+        if let Some(width) = request.width.filter(|w| THUMBNAIL_WIDTHS.contains(w)) {
+            if let Some(bytes) = storage.get(&thumbnail_key(hash, width)).await? {
+                return Ok(Some(variant(bytes)));
+            }
+        }
+    }
+
+    let Some(original) = storage.get(storage_key(storage_path)).await? else {
+        return Ok(None);
+    };
+    let request = *request;
+    let encoded = tokio::task::spawn_blocking(move || {
+        let encoded = image::load_from_memory(&original)
+            .map_err(|e| e.to_string())
+            .and_then(|decoded| encode_variant(&resize(&decoded, &request), format).map_err(|e| e.to_string()));
+        (original, encoded)
+    })
+    .await;
+
+    match encoded {
+        Ok((_, Ok(encoded))) => {
+            // 缓存写入失败不影响本次返回
+            if let Err(e) = storage.put(&key, &encoded, format.content_type()).await {
+                log::warn!("Failed to cache image variant {}: {}", key, e);
+            }
+            Ok(Some(variant(encoded)))
+        }
+        Ok((original, Err(e))) => {
+            log::warn!("Failed to render {} variant of {}: {}", format.extension(), hash, e);
+            Ok(Some(ImageVariant { bytes: original, content_type: content_type.to_string(), etag: original_etag }))
+        }
+        Err(e) => Err(StorageError::Io(std::io::Error::other(format!(
+            "Image rendering task for {} panicked: {}",
+            hash, e
+        )))),
+    }
+}
+
+// 存储迁移的结果统计
+#[derive(Debug, Default)]
+pub struct StorageMigrationReport {
+    pub images: usize,          // news_images 中的记录数
+    pub copied: usize,          // 复制的对象数（原图和缩略图）
+    pub already_present: usize, // 目标中已存在的对象数
+    pub missing: usize,         // 源中找不到的原图数
+    pub paths_rewritten: usize, // 旧路径改写为存储键的记录数
+}
+
+// 把 news_images 引用的原图和缩略图从一个存储复制到另一个，并把旧的本地路径改写为存储键。
+// 派生图缓存不复制，会在新存储上按需重新生成。可以重复执行
+pub async fn migrate_storage(
+    db_pool: &DbPool,
+    from: &dyn ImageStorage,
+    to: &dyn ImageStorage,
+) -> Result<StorageMigrationReport, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let images = list_all_images(&client).await?;
+    let mut report = StorageMigrationReport { images: images.len(), ..Default::default() };
+    let mut seen = HashSet::new();
+
+    for image in &images {
+        let key = storage_key(&image.storage_path);
+        if key != image.storage_path {
+            update_image_storage_path(&client, image.id, key).await?;
+            report.paths_rewritten += 1;
+        }
+
+        let thumbnails = THUMBNAIL_WIDTHS
+            .iter()
+            .map(|&width| (thumbnail_key(&image.content_hash, width), "image/jpeg"));
+        let objects = std::iter::once((key.to_string(), image.content_type.as_str())).chain(thumbnails);
+        for (index, (object_key, content_type)) in objects.enumerate() {
+            if !seen.insert(object_key.clone()) {
+                continue;
+            }
+            if to.exists(&object_key).await? {
+                report.already_present += 1;
+                continue;
+            }
+            match from.get(&object_key).await? {
+                Some(bytes) => {
+                    to.put(&object_key, &bytes, content_type).await?;
+                    report.copied += 1;
+                }
+                // 缩略图只在原图足够大时才生成，缺失是正常的
+                None if index == 0 => {
+                    log::warn!("Image {} is missing from {}: {}", image.id, from.describe(), object_key);
+                    report.missing += 1;
+                }
+                None => {}
+            }
+        }
+    }
+    Ok(report)
+}
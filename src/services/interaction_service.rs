@@ -1,31 +1,54 @@
 use uuid::Uuid;
 
+use crate::db::article_queries::{lock_article, lock_visible_article};
 use crate::db::connection::DbPool;
 use crate::db::interaction_queries::{
     upsert_user_interaction, get_article_interaction_stats, get_user_interactions,
     add_to_favorites, remove_from_favorites, get_user_favorites,
-    upsert_reading_history, get_user_feedback_history
+    upsert_reading_history, get_user_feedback_history,
+    count_user_interactions, count_user_favorites, recount_article_counters
 };
+use crate::db::unit_of_work::{Isolation, UnitOfWork};
 use crate::models::article::{
     CreateInteractionSchema, UserInteraction, ArticleInteractionStats,
     CreateFavoriteSchema, UserFavorite, CreateReadingHistorySchema,
     ReadingHistory, UserFeedbackHistory, InteractionResponse
 };
+use crate::models::pagination::{Page, Pagination};
 use crate::errors::ServiceError;
+use crate::utils::cursor::TimeCursor;
 
-// 创建或更新用户互动
+// 在事务中锁住要改动计数的文章，文章不存在时返回 404（见 article_queries::lock_article）。
+// visible_only 时隐藏和已删除的文章也返回 404：新的点赞和收藏只接受可见的文章，取消收藏则不限
+async fn lock_article_for_counters(uow: &UnitOfWork, article_id: Uuid, visible_only: bool) -> Result<(), ServiceError> {
+    let locked = if visible_only {
+        lock_visible_article(uow, article_id).await
+    } else {
+        lock_article(uow, article_id).await
+    };
+    let found = locked.map_err(|e| {
+        log::error!("Failed to lock article {}: {}", article_id, e);
+        ServiceError::InternalServerError("Failed to process interaction".to_string())
+    })?;
+    if found {
+        Ok(())
+    } else {
+        Err(ServiceError::NotFound("Article not found".to_string()))
+    }
+}
+
+// 创建或更新用户互动；互动和文章计数在同一个事务中更新
 pub async fn create_or_update_interaction_service(
     pool: &DbPool,
     user_id: Uuid,
     interaction_data: CreateInteractionSchema,
 ) -> Result<InteractionResponse, ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    lock_article_for_counters(&uow, interaction_data.article_id, true).await?;
 
-    match upsert_user_interaction(&client, user_id, interaction_data).await {
+    match upsert_user_interaction(&uow, user_id, interaction_data).await {
         Ok(interaction) => {
+            uow.commit().await?;
             let message = if interaction.is_active {
                 format!("{}成功", match interaction.interaction_type.as_str() {
                     "like" => "点赞",
@@ -75,38 +98,42 @@ pub async fn get_article_stats_service(
 pub async fn get_user_interactions_service(
     pool: &DbPool,
     user_id: Uuid,
-    interaction_type: Option<String>,
-    page: i64,
-    limit: i64,
-) -> Result<Vec<UserInteraction>, ServiceError> {
+    interaction_type: Option<&str>,
+    pagination: &Pagination,
+) -> Result<Page<UserInteraction>, ServiceError> {
+    let after: Option<TimeCursor> = pagination.cursor()?;
     let client = pool.get().await.map_err(|e| {
         log::error!("Failed to get DB client from pool: {}", e);
         ServiceError::InternalServerError("Database connection error".to_string())
     })?;
 
-    get_user_interactions(&client, user_id, interaction_type, page, limit).await
-        .map_err(|e| {
+    let interactions = get_user_interactions(&client, user_id, interaction_type, after.as_ref(), pagination.offset(), pagination.fetch_limit()).await;
+    let total = count_user_interactions(&client, user_id, interaction_type).await;
+    match (interactions, total) {
+        (Ok(interactions), Ok(total)) => Ok(pagination.page_of(interactions, total, |i| TimeCursor { at: i.updated_at, id: i.id })),
+        (Err(e), _) | (_, Err(e)) => {
             log::error!("Failed to get user interactions: {}", e);
-            ServiceError::InternalServerError("Failed to get user interactions".to_string())
-        })
+            Err(ServiceError::InternalServerError("Failed to get user interactions".to_string()))
+        }
+    }
 }
 
-// 添加到收藏夹
+// 添加到收藏夹；收藏和文章计数在同一个事务中更新
 pub async fn add_to_favorites_service(
     pool: &DbPool,
     user_id: Uuid,
     favorite_data: CreateFavoriteSchema,
 ) -> Result<UserFavorite, ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    lock_article_for_counters(&uow, favorite_data.article_id, true).await?;
 
-    add_to_favorites(&client, user_id, favorite_data).await
+    let favorite = add_to_favorites(&uow, user_id, favorite_data).await
         .map_err(|e| {
             log::error!("Failed to add to favorites: {}", e);
             ServiceError::InternalServerError("Failed to add to favorites".to_string())
-        })
+        })?;
+    uow.commit().await?;
+    Ok(favorite)
 }
 
 // 从收藏夹移除
@@ -115,42 +142,43 @@ pub async fn remove_from_favorites_service(
     user_id: Uuid,
     article_id: Uuid,
 ) -> Result<(), ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    lock_article_for_counters(&uow, article_id, false).await?;
 
-    let rows_affected = remove_from_favorites(&client, user_id, article_id).await
+    let rows_affected = remove_from_favorites(&uow, user_id, article_id).await
         .map_err(|e| {
             log::error!("Failed to remove from favorites: {}", e);
             ServiceError::InternalServerError("Failed to remove from favorites".to_string())
         })?;
 
     if rows_affected == 0 {
-        Err(ServiceError::NotFound("Favorite not found".to_string()))
-    } else {
-        Ok(())
+        return Err(ServiceError::NotFound("Favorite not found".to_string()));
     }
+    uow.commit().await
 }
 
 // 获取用户收藏夹
 pub async fn get_user_favorites_service(
     pool: &DbPool,
     user_id: Uuid,
-    folder_name: Option<String>,
-    page: i64,
-    limit: i64,
-) -> Result<Vec<UserFavorite>, ServiceError> {
+    folder_name: Option<&str>,
+    pagination: &Pagination,
+) -> Result<Page<UserFavorite>, ServiceError> {
+    let after: Option<TimeCursor> = pagination.cursor()?;
     let client = pool.get().await.map_err(|e| {
         log::error!("Failed to get DB client from pool: {}", e);
         ServiceError::InternalServerError("Database connection error".to_string())
     })?;
 
-    get_user_favorites(&client, user_id, folder_name, page, limit).await
-        .map_err(|e| {
+    let favorites = get_user_favorites(&client, user_id, folder_name, after.as_ref(), pagination.offset(), pagination.fetch_limit()).await;
+    let total = count_user_favorites(&client, user_id, folder_name).await;
+    match (favorites, total) {
+        (Ok(favorites), Ok(total)) => Ok(pagination.page_of(favorites, total, |f| TimeCursor { at: f.created_at, id: f.id })),
+        (Err(e), _) | (_, Err(e)) => {
             log::error!("Failed to get user favorites: {}", e);
-            ServiceError::InternalServerError("Failed to get user favorites".to_string())
-        })
+            Err(ServiceError::InternalServerError("Failed to get user favorites".to_string()))
+        }
+    }
 }
 
 // 记录阅读历史
@@ -178,14 +206,31 @@ pub async fn get_user_feedback_history_service(
     page: i64,
     limit: i64,
 ) -> Result<UserFeedbackHistory, ServiceError> {
-    let client = pool.get().await.map_err(|e| {
-        log::error!("Failed to get DB client from pool: {}", e);
-        ServiceError::InternalServerError("Database connection error".to_string())
-    })?;
+    // 反馈、评论、互动和总数分四次查询，在同一个快照中读取，结果彼此一致
+    let uow = UnitOfWork::read_only(pool, Isolation::RepeatableRead).await?;
 
-    get_user_feedback_history(&client, user_id, page, limit).await
+    let history = get_user_feedback_history(&uow, user_id, page, limit).await
         .map_err(|e| {
             log::error!("Failed to get user feedback history: {}", e);
             ServiceError::InternalServerError("Failed to get user feedback history".to_string())
-        })
-} 
\ No newline at end of file
+        })?;
+    uow.commit().await?;
+    Ok(history)
+}
+
+// 按明细表重算所有文章的点赞、收藏、评论计数，修正触发器之外的改动造成的偏差；返回修正的文章数。
+// 分批执行，每批一个语句，不会长时间锁住整张文章表
+pub async fn repair_article_counters(db_pool: &DbPool, batch_size: i64) -> Result<usize, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let mut repaired = 0;
+    let mut after = None;
+    loop {
+        let (last_id, fixed) = recount_article_counters(&client, after, batch_size).await?;
+        let Some(last_id) = last_id else {
+            break;
+        };
+        repaired += fixed as usize;
+        after = Some(last_id);
+    }
+    Ok(repaired)
+}
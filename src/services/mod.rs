@@ -5,3 +5,13 @@ pub mod recommendation_service;
 pub mod feedback_service;
 pub mod comment_service;
 pub mod interaction_service;
+pub mod http_fetcher;
+pub mod image_service;
+pub mod storage;
+pub mod classifier_service;
+pub mod category_service;
+pub mod tagging_service;
+pub mod summary_service;
+pub mod search_service;
+pub mod suggest_service;
+pub mod article_service;
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use crate::db::connection::DbPool;
+use crate::db::search_queries::{get_unindexed_articles, replace_search_terms};
+use crate::utils::tokenizer::tokenize_for_search;
+
+// 全文检索：入库时用 jieba 搜索模式分词建立倒排索引（article_search_terms），
+// 查询时按 BM25 排序，并在 Rust 中生成带高亮的标题和摘录。
+
+// 标题中的词计入词频时的权重
+const TITLE_WEIGHT: i32 = 3;
+// 超过该长度的词不入索引（多为连在一起的英文或网址）
+const MAX_TERM_CHARS: usize = 50;
+// 一次查询最多使用的检索词数
+pub const MAX_QUERY_TERMS: usize = 10;
+// 摘录的长度和命中词之前保留的字数
+const SNIPPET_CHARS: usize = 120;
+const SNIPPET_LEAD_CHARS: usize = 30;
+
+// 搜索日志中查询串的最大长度
+const MAX_LOGGED_QUERY_CHARS: usize = 100;
+
+const HIGHLIGHT_START: &str = "<em>";
+const HIGHLIGHT_END: &str = "</em>";
+
+// 一篇文章的索引词及加权词频，以及加权后的文档长度
+pub fn index_terms(title: &str, content: &str) -> (Vec<(String, i32)>, i32) {
+    let mut counts: HashMap<String, i32> = HashMap::new();
+    for (text, weight) in [(title, TITLE_WEIGHT), (content, 1)] {
+        for term in tokenize_for_search(text) {
+            if term.chars().count() <= MAX_TERM_CHARS {
+                *counts.entry(term).or_insert(0) += weight;
+            }
+        }
+    }
+    let length = counts.values().sum();
+    let mut terms: Vec<(String, i32)> = counts.into_iter().collect();
+    terms.sort();
+    (terms, length)
+}
+
+// 查询串切成检索词，去重并保持顺序。查询和索引都用搜索模式分词：jieba 只给长词切出两三个字的短词，
+// 查询“人民银行”时靠其中的“人民”“银行”命中正文里的“中国人民银行”
+pub fn query_terms(query: &str) -> Vec<String> {
+    let mut terms: Vec<String> = Vec::new();
+    for term in tokenize_for_search(query) {
+        if term.chars().count() <= MAX_TERM_CHARS && !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms.truncate(MAX_QUERY_TERMS);
+    terms
+}
+
+// 写入搜索日志前统一格式：合并空白、转小写、截断到 100 字
+pub fn normalize_query(query: &str) -> String {
+    let collapsed = query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    collapsed.chars().take(MAX_LOGGED_QUERY_CHARS).collect()
+}
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+// 文本中命中检索词的字符区间 [start, end)，不区分英文大小写，长词优先，区间不重叠
+fn match_ranges(chars: &[char], terms: &[String]) -> Vec<(usize, usize)> {
+    let lowered: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let mut patterns: Vec<Vec<char>> = terms.iter().map(|t| t.chars().collect()).filter(|p: &Vec<char>| !p.is_empty()).collect();
+    patterns.sort_by_key(|p| std::cmp::Reverse(p.len()));
+
+    let mut ranges = Vec::new();
+    let mut i = 0;
+    while i < lowered.len() {
+        match patterns.iter().find(|p| lowered[i..].starts_with(p)) {
+            Some(pattern) => {
+                ranges.push((i, i + pattern.len()));
+                i += pattern.len();
+            }
+            None => i += 1,
+        }
+    }
+    ranges
+}
+
+// 转义 HTML 并用 <em> 标出命中的检索词
+fn render_highlighted(chars: &[char], ranges: &[(usize, usize)], from: usize, to: usize) -> String {
+    let mut out = String::new();
+    let mut position = from;
+    for &(start, end) in ranges.iter().filter(|&&(start, end)| start >= from && end <= to) {
+        escape_html(&chars[position..start].iter().collect::<String>(), &mut out);
+        out.push_str(HIGHLIGHT_START);
+        escape_html(&chars[start..end].iter().collect::<String>(), &mut out);
+        out.push_str(HIGHLIGHT_END);
+        position = end;
+    }
+    escape_html(&chars[position..to].iter().collect::<String>(), &mut out);
+    out
+}
+
+// 整段文本高亮（用于标题）
+pub fn highlight(text: &str, terms: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let ranges = match_ranges(&chars, terms);
+    render_highlighted(&chars, &ranges, 0, chars.len())
+}
+
+// 从第一个命中处附近截取一段正文并高亮；没有命中时取开头
+pub fn snippet(text: &str, terms: &[String]) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let ranges = match_ranges(&chars, terms);
+    let start = ranges
+        .first()
+        .map(|&(start, _)| start.saturating_sub(SNIPPET_LEAD_CHARS))
+        .unwrap_or(0);
+    let end = (start + SNIPPET_CHARS).min(chars.len());
+    // 截断处不能切开命中词
+    let end = ranges.iter().find(|&&(s, e)| s < end && e > end).map(|&(_, e)| e).unwrap_or(end);
+
+    let mut out = String::new();
+    if start > 0 {
+        out.push('…');
+    }
+    out.push_str(render_highlighted(&chars, &ranges, start, end).trim());
+    if end < chars.len() {
+        out.push('…');
+    }
+    out
+}
+
+// 为还没有索引的文章建立索引（升级前入库的旧数据），返回处理的文章数
+pub async fn index_missing_articles(db_pool: &DbPool, batch_size: i64) -> Result<usize, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let mut indexed = 0;
+    let mut after = None;
+    loop {
+        let articles = get_unindexed_articles(&client, after, batch_size).await?;
+        let Some((last_id, _, _)) = articles.last() else {
+            break;
+        };
+        after = Some(*last_id);
+        for (article_id, title, content) in &articles {
+            let (terms, length) = index_terms(title, content);
+            replace_search_terms(&client, *article_id, &terms, length).await?;
+            indexed += 1;
+        }
+        log::info!("Indexed {} articles so far", indexed);
+    }
+    Ok(indexed)
+}
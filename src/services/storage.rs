@@ -0,0 +1,279 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::fs;
+
+use crate::config::app_config::{S3Config, StorageConfig};
+
+// 图片文件的存放位置。键是相对路径，例如 ab/<hash>.jpg、thumbs/240/ab/<hash>.jpg，
+// 与具体后端无关，news_images.storage_path 保存的就是这个键
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Storage request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Storage returned {status} for {key}: {body}")]
+    Status { status: u16, key: String, body: String },
+    #[error("Invalid storage configuration: {0}")]
+    Config(String),
+}
+
+#[async_trait]
+pub trait ImageStorage: Send + Sync {
+    // 写入对象；已存在时覆盖
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), StorageError>;
+
+    // 读取对象，不存在时返回 None
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError>;
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError>;
+
+    // 用于日志，例如 local:storage/img 或 s3:bucket
+    fn describe(&self) -> String;
+}
+
+// 按配置创建存储后端。S3 后端内部持有 reqwest::Client，
+// 应在使用它的运行时中创建（爬虫有自己的运行时）
+pub fn build_storage(config: &StorageConfig) -> Result<Arc<dyn ImageStorage>, StorageError> {
+    match config {
+        StorageConfig::Local { root } => Ok(Arc::new(LocalStorage::new(root))),
+        StorageConfig::S3(s3) => Ok(Arc::new(S3Storage::new(s3.clone())?)),
+    }
+}
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalStorage { root: root.into() }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, StorageError> {
+        // 键来自数据库，拒绝绝对路径和 .. 以免读到存储目录以外的文件
+        if key.starts_with('/') || key.split('/').any(|part| part == "..") {
+            return Err(StorageError::Config(format!("Invalid storage key {:?}", key)));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl ImageStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: &[u8], _content_type: &str) -> Result<(), StorageError> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        // 先写临时文件再改名，避免并发写入或中途失败留下半个文件
+        let tmp_path = path.with_extension("part");
+        fs::write(&tmp_path, bytes).await?;
+        fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        match fs::read(self.path_for(key)?).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        Ok(fs::try_exists(self.path_for(key)?).await?)
+    }
+
+    fn describe(&self) -> String {
+        format!("local:{}", self.root.display())
+    }
+}
+
+type HmacSha256 = Hmac<Sha256>;
+
+const EMPTY_PAYLOAD_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// 按 SigV4 规则编码 URI：保留 A-Z a-z 0-9 - _ . ~，路径中的 / 不编码
+fn uri_encode(value: &str, encode_slash: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => out.push(byte as char),
+            b'/' if !encode_slash => out.push('/'),
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    out
+}
+
+// 一个待签名的请求；headers 为小写名称，需包含 host、x-amz-date 和 x-amz-content-sha256
+pub struct SigV4Request<'a> {
+    pub method: &'a str,
+    pub canonical_uri: &'a str,
+    pub canonical_query: &'a str,
+    pub headers: &'a [(&'a str, &'a str)],
+    pub payload_sha256: &'a str,
+}
+
+// 计算 AWS Signature Version 4 的 Authorization 头（服务名固定为 s3）
+pub fn sigv4_authorization(
+    request: &SigV4Request,
+    access_key_id: &str,
+    secret_access_key: &str,
+    region: &str,
+    now: DateTime<Utc>,
+) -> String {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers: Vec<(String, String)> = request
+        .headers
+        .iter()
+        .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+    headers.sort();
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value)).collect();
+    let signed_headers = headers.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        request.method, request.canonical_uri, request.canonical_query, canonical_headers, signed_headers, request.payload_sha256
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let signing_key = [region, "s3", "aws4_request"]
+        .iter()
+        .fold(hmac(format!("AWS4{}", secret_access_key).as_bytes(), &date), |key, part| hmac(&key, part));
+    let signature = hex::encode(hmac(&signing_key, &string_to_sign));
+
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    )
+}
+
+// S3 兼容对象存储，直接用 reqwest 发送签名请求
+pub struct S3Storage {
+    config: S3Config,
+    endpoint: Url,
+    client: reqwest::Client,
+}
+
+impl S3Storage {
+    pub fn new(config: S3Config) -> Result<Self, StorageError> {
+        let endpoint = Url::parse(&config.endpoint)
+            .map_err(|e| StorageError::Config(format!("Invalid S3_ENDPOINT {:?}: {}", config.endpoint, e)))?;
+        if endpoint.host_str().is_none() {
+            return Err(StorageError::Config(format!("S3_ENDPOINT {:?} has no host", config.endpoint)));
+        }
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(60)).build()?;
+        Ok(S3Storage { config, endpoint, client })
+    }
+
+    // 返回请求 URL、Host 头和签名用的 URI
+    fn object_location(&self, key: &str) -> (String, String, String) {
+        let object_key = format!("{}{}", self.config.prefix, key);
+        let host = self.endpoint.host_str().unwrap_or_default();
+        let host = match self.endpoint.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        };
+        let (host, canonical_uri) = if self.config.path_style {
+            (host, format!("/{}/{}", uri_encode(&self.config.bucket, true), uri_encode(&object_key, false)))
+        } else {
+            (format!("{}.{}", self.config.bucket, host), format!("/{}", uri_encode(&object_key, false)))
+        };
+        let url = format!("{}://{}{}", self.endpoint.scheme(), host, canonical_uri);
+        (url, host, canonical_uri)
+    }
+
+    fn request(&self, method: reqwest::Method, key: &str, payload_sha256: &str) -> reqwest::RequestBuilder {
+        let (url, host, canonical_uri) = self.object_location(key);
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = sigv4_authorization(
+            &SigV4Request {
+                method: method.as_str(),
+                canonical_uri: &canonical_uri,
+                canonical_query: "",
+                headers: &[("host", &host), ("x-amz-content-sha256", payload_sha256), ("x-amz-date", &amz_date)],
+                payload_sha256,
+            },
+            &self.config.access_key_id,
+            &self.config.secret_access_key,
+            &self.config.region,
+            now,
+        );
+        self.client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_sha256)
+            .header("authorization", authorization)
+    }
+
+    async fn status_error(key: &str, response: reqwest::Response) -> StorageError {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        StorageError::Status { status, key: key.to_string(), body }
+    }
+}
+
+#[async_trait]
+impl ImageStorage for S3Storage {
+    async fn put(&self, key: &str, bytes: &[u8], content_type: &str) -> Result<(), StorageError> {
+        let payload_sha256 = hex::encode(Sha256::digest(bytes));
+        let response = self
+            .request(reqwest::Method::PUT, key, &payload_sha256)
+            .header("content-type", content_type)
+            .body(bytes.to_vec())
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(Self::status_error(key, response).await);
+        }
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, StorageError> {
+        let response = self.request(reqwest::Method::GET, key, EMPTY_PAYLOAD_SHA256).send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            _ => Err(Self::status_error(key, response).await),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StorageError> {
+        let response = self.request(reqwest::Method::HEAD, key, EMPTY_PAYLOAD_SHA256).send().await?;
+        match response.status() {
+            status if status.is_success() => Ok(true),
+            reqwest::StatusCode::NOT_FOUND => Ok(false),
+            _ => Err(Self::status_error(key, response).await),
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("s3:{}/{}", self.config.endpoint.trim_end_matches('/'), self.config.bucket)
+    }
+}
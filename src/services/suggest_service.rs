@@ -0,0 +1,166 @@
+use once_cell::sync::Lazy;
+use tokio_postgres::{Client, Error as PgError};
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+
+use crate::db::search_queries::{get_popular_queries, get_recent_titles};
+use crate::db::tag_queries::get_tag_counts;
+use crate::models::article::{SearchSuggestion, SuggestionKind};
+
+// 搜索框自动补全：文章标题、文章标签和热门搜索词放进一棵前缀树，
+// 每个节点预先保存以该前缀开头的得分最高的若干条，查询时只需沿前缀走到节点。
+// 有新文章入库后重建（见爬虫），服务启动时也会建一次。
+
+// 每个节点保存的候选数，也是接口 limit 的上限
+pub const MAX_SUGGESTIONS: usize = 20;
+// 参与补全的最新文章标题数和标签数
+const TITLE_SOURCE_LIMIT: i64 = 5000;
+const TAG_SOURCE_LIMIT: i64 = 5000;
+// 统计热门搜索词的时间范围（天）和条数
+const POPULAR_QUERY_DAYS: i32 = 30;
+const POPULAR_QUERY_LIMIT: i64 = 2000;
+// 各来源的得分权重：标题每条计 1 分，标签按文章数、搜索词按搜索次数乘以权重
+const TAG_WEIGHT: f64 = 2.0;
+const QUERY_WEIGHT: f64 = 3.0;
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: BTreeMap<char, usize>,
+    entry: Option<usize>, // 以该节点结尾的候选
+    top: Vec<usize>, // 子树中得分最高的候选，按得分从高到低
+}
+
+// 前缀树；匹配不区分英文大小写，候选保留原文
+#[derive(Debug)]
+pub struct SuggestionTrie {
+    nodes: Vec<TrieNode>,
+    entries: Vec<SearchSuggestion>,
+}
+
+impl Default for SuggestionTrie {
+    fn default() -> Self {
+        SuggestionTrie { nodes: vec![TrieNode::default()], entries: Vec::new() }
+    }
+}
+
+// 合并连续空白
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl SuggestionTrie {
+    // 由候选构建；同一文本出现多次时得分累加，种类取得分最高的那次
+    pub fn build(suggestions: impl IntoIterator<Item = SearchSuggestion>) -> Self {
+        let mut trie = SuggestionTrie::default();
+        for suggestion in suggestions {
+            trie.insert(suggestion);
+        }
+        trie.compute_top(0);
+        trie
+    }
+
+    fn insert(&mut self, mut suggestion: SearchSuggestion) {
+        suggestion.text = collapse_whitespace(&suggestion.text);
+        let key = suggestion.text.to_lowercase();
+        if key.is_empty() {
+            return;
+        }
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(TrieNode::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, child);
+                    child
+                }
+            };
+        }
+        match self.nodes[node].entry {
+            Some(index) => {
+                let existing = &mut self.entries[index];
+                if suggestion.score > existing.score {
+                    existing.kind = suggestion.kind;
+                    existing.text = suggestion.text;
+                }
+                existing.score += suggestion.score;
+            }
+            None => {
+                self.entries.push(suggestion);
+                self.nodes[node].entry = Some(self.entries.len() - 1);
+            }
+        }
+    }
+
+    // 后序遍历，合并子节点的候选；用显式栈避免长标题导致递归过深
+    fn compute_top(&mut self, root: usize) {
+        let mut order = Vec::new();
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            order.push(node);
+            stack.extend(self.nodes[node].children.values().copied());
+        }
+        for &node in order.iter().rev() {
+            let mut top: Vec<usize> = self.nodes[node].entry.into_iter().collect();
+            for &child in self.nodes[node].children.values() {
+                top.extend(self.nodes[child].top.iter().copied());
+            }
+            let entries = &self.entries;
+            top.sort_by(|&a, &b| {
+                entries[b].score.total_cmp(&entries[a].score).then_with(|| entries[a].text.cmp(&entries[b].text))
+            });
+            top.truncate(MAX_SUGGESTIONS);
+            self.nodes[node].top = top;
+        }
+    }
+
+    pub fn complete(&self, prefix: &str, limit: usize) -> Vec<SearchSuggestion> {
+        let key = collapse_whitespace(prefix).to_lowercase();
+        if key.is_empty() {
+            return Vec::new();
+        }
+        let mut node = 0;
+        for c in key.chars() {
+            match self.nodes[node].children.get(&c) {
+                Some(&child) => node = child,
+                None => return Vec::new(),
+            }
+        }
+        self.nodes[node].top.iter().take(limit).map(|&index| self.entries[index].clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+static SUGGESTIONS: Lazy<RwLock<Arc<SuggestionTrie>>> = Lazy::new(|| RwLock::new(Arc::new(SuggestionTrie::default())));
+
+pub fn get_suggestions() -> Arc<SuggestionTrie> {
+    Arc::clone(&SUGGESTIONS.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+}
+
+// 从数据库重新读取标题、标签和热门搜索词并替换当前的前缀树
+pub async fn rebuild_suggestions(client: &Client) -> Result<(), PgError> {
+    let mut suggestions = Vec::new();
+    for title in get_recent_titles(client, TITLE_SOURCE_LIMIT).await? {
+        suggestions.push(SearchSuggestion { text: title, kind: SuggestionKind::Title, score: 1.0 });
+    }
+    for (tag, articles) in get_tag_counts(client, TAG_SOURCE_LIMIT).await? {
+        suggestions.push(SearchSuggestion { text: tag, kind: SuggestionKind::Tag, score: articles as f64 * TAG_WEIGHT });
+    }
+    for (query, searches) in get_popular_queries(client, POPULAR_QUERY_DAYS, POPULAR_QUERY_LIMIT).await? {
+        suggestions.push(SearchSuggestion { text: query, kind: SuggestionKind::Query, score: searches as f64 * QUERY_WEIGHT });
+    }
+
+    let trie = SuggestionTrie::build(suggestions);
+    log::info!("Rebuilt search suggestions with {} entries", trie.len());
+    *SUGGESTIONS.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(trie);
+    Ok(())
+}
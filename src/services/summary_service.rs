@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+
+use crate::db::article_queries::{get_unsummarized_articles, set_article_summary};
+use crate::db::connection::DbPool;
+use crate::utils::textrank::weighted_pagerank;
+use crate::utils::tokenizer::tokenize;
+
+// 抽取式摘要：按句子切分正文，以两句共有词数衡量相似度，在句子图上跑 TextRank，
+// 取得分最高的几句并按原文顺序拼接。
+
+// 摘要最多包含的句子数和字数
+const MAX_SUMMARY_SENTENCES: usize = 3;
+const MAX_SUMMARY_CHARS: usize = 200;
+// 参与排序的句子数上限，长文只看前面部分，避免句子图过大
+const MAX_RANKED_SENTENCES: usize = 80;
+
+const SENTENCE_TERMINATORS: &[char] = &['。', '！', '？', '!', '?', '\n'];
+// 紧跟在句末标点后的右引号、右括号属于同一句
+const CLOSING_MARKS: &[char] = &['”', '’', '」', '』', '）', ')', '"', '\''];
+
+// 按中文句末标点和换行切分句子，句末标点保留在句中
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        if SENTENCE_TERMINATORS.contains(&c) {
+            while let Some(&next) = chars.peek() {
+                if SENTENCE_TERMINATORS.contains(&next) || CLOSING_MARKS.contains(&next) {
+                    current.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            push_sentence(&mut sentences, &current);
+            current.clear();
+        }
+    }
+    push_sentence(&mut sentences, &current);
+    sentences
+}
+
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.trim();
+    if sentence.chars().any(char::is_alphanumeric) {
+        sentences.push(sentence.to_string());
+    }
+}
+
+// TextRank 原文中的句子相似度：共有词数 / (ln|A| + ln|B|)
+fn sentence_similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let denominator = (a.len() as f64).ln() + (b.len() as f64).ln();
+    if denominator <= 0.0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / denominator
+}
+
+// 超过 max_chars 时截断并加省略号
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+pub fn summarize(content: &str) -> String {
+    let sentences: Vec<String> = split_sentences(content).into_iter().take(MAX_RANKED_SENTENCES).collect();
+    if sentences.is_empty() {
+        return String::new();
+    }
+
+    let words: Vec<HashSet<String>> = sentences.iter().map(|s| tokenize(s).into_iter().collect()).collect();
+    let mut edges: Vec<Vec<(usize, f64)>> = vec![Vec::new(); sentences.len()];
+    for i in 0..sentences.len() {
+        for j in (i + 1)..sentences.len() {
+            let similarity = sentence_similarity(&words[i], &words[j]);
+            if similarity > 0.0 {
+                edges[i].push((j, similarity));
+                edges[j].push((i, similarity));
+            }
+        }
+    }
+    let scores = weighted_pagerank(&edges);
+
+    // 得分相同时靠前的句子优先（新闻导语通常在开头）
+    let mut ranked: Vec<usize> = (0..sentences.len()).collect();
+    ranked.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]).then(a.cmp(&b)));
+
+    let mut chosen = Vec::new();
+    let mut chars = 0;
+    for index in ranked {
+        if chosen.len() == MAX_SUMMARY_SENTENCES {
+            break;
+        }
+        let length = sentences[index].chars().count();
+        if chosen.is_empty() || chars + length <= MAX_SUMMARY_CHARS {
+            chosen.push(index);
+            chars += length;
+        }
+    }
+    chosen.sort_unstable();
+
+    let summary: String = chosen.iter().map(|&i| sentences[i].as_str()).collect();
+    truncate_chars(&summary, MAX_SUMMARY_CHARS)
+}
+
+// 为还没有摘要的文章生成摘要（升级前入库的旧数据），返回处理的文章数
+pub async fn summarize_missing_articles(db_pool: &DbPool, batch_size: i64) -> Result<usize, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let mut summarized = 0;
+    let mut after = None;
+    loop {
+        let articles = get_unsummarized_articles(&client, after, batch_size).await?;
+        let Some((last_id, _)) = articles.last() else {
+            break;
+        };
+        after = Some(*last_id);
+        for (article_id, content) in &articles {
+            set_article_summary(&client, *article_id, &summarize(content)).await?;
+            summarized += 1;
+        }
+        log::info!("Summarized {} articles so far", summarized);
+    }
+    Ok(summarized)
+}
@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use crate::db::connection::DbPool;
+use crate::db::tag_queries::{get_untagged_articles, replace_article_tags};
+use crate::models::article::TagKind;
+use crate::utils::textrank::weighted_pagerank;
+use crate::utils::tokenizer::{is_stopword, tag};
+
+// 文章标签：关键词用 TextRank 在名词类候选词的共现图上排序；
+// 人名、机构、地名来自 jieba 词性标注，再用常见机构名表和机构后缀规则补充。
+
+// 每篇文章保留的关键词数和每种实体的数量
+const MAX_KEYWORDS: usize = 8;
+const MAX_ENTITIES_PER_KIND: usize = 5;
+// 共现窗口：候选词序列中相距小于该值的两个词连一条边
+const COOCCURRENCE_WINDOW: usize = 5;
+// 标题在关键词统计中重复的次数，让标题中的词更容易排在前面
+const TITLE_REPEAT: usize = 2;
+const MAX_TAG_CHARS: usize = 30;
+
+// 关键词候选的词性：名词、专名、名动词和英文
+const KEYWORD_POS: &[&str] = &["n", "nr", "nrfg", "nrt", "ns", "nt", "nz", "vn", "eng"];
+
+// jieba 常常切不出或标错的机构名、地名
+const GAZETTEER: &[(&str, TagKind)] = &[
+    ("联合国", TagKind::Organization),
+    ("世界卫生组织", TagKind::Organization),
+    ("世卫组织", TagKind::Organization),
+    ("世界贸易组织", TagKind::Organization),
+    ("世贸组织", TagKind::Organization),
+    ("国际货币基金组织", TagKind::Organization),
+    ("世界银行", TagKind::Organization),
+    ("欧盟", TagKind::Organization),
+    ("北约", TagKind::Organization),
+    ("东盟", TagKind::Organization),
+    ("上合组织", TagKind::Organization),
+    ("国际奥委会", TagKind::Organization),
+    ("美联储", TagKind::Organization),
+    ("央行", TagKind::Organization),
+    ("国务院", TagKind::Organization),
+    ("全国人大", TagKind::Organization),
+    ("全国政协", TagKind::Organization),
+    ("外交部", TagKind::Organization),
+    ("商务部", TagKind::Organization),
+    ("财政部", TagKind::Organization),
+    ("教育部", TagKind::Organization),
+    ("国防部", TagKind::Organization),
+    ("工信部", TagKind::Organization),
+    ("国家统计局", TagKind::Organization),
+    ("国家发展改革委", TagKind::Organization),
+    ("国家发改委", TagKind::Organization),
+    ("证监会", TagKind::Organization),
+    ("长三角", TagKind::Place),
+    ("珠三角", TagKind::Place),
+    ("京津冀", TagKind::Place),
+    ("粤港澳大湾区", TagKind::Place),
+    ("大湾区", TagKind::Place),
+    ("中东", TagKind::Place),
+];
+
+// 以这些词结尾的词是机构名；单独出现时与前一个专名或地名合并（如“北京” + “大学”）
+const ORGANIZATION_SUFFIXES: &[&str] = &[
+    "公司", "集团", "大学", "学院", "银行", "医院", "委员会", "协会", "学会", "研究院", "研究所",
+    "组织", "基金会", "法院", "检察院", "交易所", "联合会", "中心",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedTag {
+    pub tag: String,
+    pub kind: TagKind,
+    pub weight: f64, // 同一种类内归一化到 (0, 1]
+}
+
+fn is_candidate_word(word: &str) -> bool {
+    let chars = word.chars().count();
+    (2..=MAX_TAG_CHARS).contains(&chars) && word.chars().any(char::is_alphabetic) && !is_stopword(word)
+}
+
+fn is_cjk_name(word: &str) -> bool {
+    let chars = word.chars().count();
+    (2..=4).contains(&chars) && word.chars().all(|c| ('\u{4e00}'..='\u{9fff}').contains(&c))
+}
+
+fn gazetteer_kind(word: &str) -> Option<TagKind> {
+    GAZETTEER.iter().find(|(name, _)| *name == word).map(|(_, kind)| *kind)
+}
+
+fn has_organization_suffix(word: &str) -> bool {
+    ORGANIZATION_SUFFIXES.iter().any(|suffix| word.len() > suffix.len() && word.ends_with(suffix))
+}
+
+// 标题和正文合并为一段文本，标题重复 TITLE_REPEAT 次
+fn tagged_words(title: &str, content: &str) -> Vec<(String, String)> {
+    let mut text = String::new();
+    for _ in 0..TITLE_REPEAT {
+        text.push_str(title);
+        text.push('\n');
+    }
+    text.push_str(content);
+    tag(&text)
+}
+
+// TextRank 关键词；权重为 PageRank 分数除以最高分
+pub fn extract_keywords(words: &[(String, String)], limit: usize) -> Vec<ExtractedTag> {
+    let candidates: Vec<String> = words
+        .iter()
+        .filter(|(word, pos)| KEYWORD_POS.contains(&pos.as_str()) && is_candidate_word(word))
+        .map(|(word, _)| word.to_lowercase())
+        .collect();
+
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut vocabulary: Vec<&str> = Vec::new();
+    for word in &candidates {
+        index.entry(word.as_str()).or_insert_with(|| {
+            vocabulary.push(word.as_str());
+            vocabulary.len() - 1
+        });
+    }
+
+    let mut weights: HashMap<(usize, usize), f64> = HashMap::new();
+    for (i, word) in candidates.iter().enumerate() {
+        let a = index[word.as_str()];
+        for other in candidates.iter().skip(i + 1).take(COOCCURRENCE_WINDOW - 1) {
+            let b = index[other.as_str()];
+            if a != b {
+                *weights.entry((a, b)).or_insert(0.0) += 1.0;
+                *weights.entry((b, a)).or_insert(0.0) += 1.0;
+            }
+        }
+    }
+    let mut edges: Vec<Vec<(usize, f64)>> = vec![Vec::new(); vocabulary.len()];
+    let mut sorted_weights: Vec<_> = weights.into_iter().collect();
+    sorted_weights.sort_by_key(|&(pair, _)| pair); // 固定求和顺序，结果可复现
+    for ((a, b), weight) in sorted_weights {
+        edges[a].push((b, weight));
+    }
+
+    let scores = weighted_pagerank(&edges);
+    let mut ranked: Vec<(usize, f64)> = scores.into_iter().enumerate().collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| vocabulary[a.0].cmp(vocabulary[b.0])));
+    let top = ranked.first().map(|&(_, score)| score).unwrap_or(0.0);
+    ranked
+        .into_iter()
+        .take(limit)
+        .filter(|&(_, score)| score > 0.0)
+        .map(|(i, score)| ExtractedTag { tag: vocabulary[i].to_string(), kind: TagKind::Keyword, weight: score / top })
+        .collect()
+}
+
+// 人名、机构、地名，每种按出现次数取前 limit 个；权重为次数除以该种类的最高次数
+pub fn extract_entities(words: &[(String, String)], limit: usize) -> Vec<ExtractedTag> {
+    let mut counts: HashMap<(String, TagKind), (usize, usize)> = HashMap::new(); // -> (次数, 首次出现位置)
+    let mut record = |name: String, kind: TagKind, position: usize| {
+        let entry = counts.entry((name, kind)).or_insert((0, position));
+        entry.0 += 1;
+    };
+
+    let mut i = 0;
+    while i < words.len() {
+        let (word, pos) = &words[i];
+        // 专名或地名后紧跟机构后缀时合并为一个机构名
+        if let Some((next, _)) = words.get(i + 1) {
+            if matches!(pos.as_str(), "ns" | "nt" | "nz") && ORGANIZATION_SUFFIXES.contains(&next.as_str()) {
+                let merged = format!("{}{}", word, next);
+                if merged.chars().count() <= MAX_TAG_CHARS {
+                    record(merged, TagKind::Organization, i);
+                    i += 2;
+                    continue;
+                }
+            }
+        }
+
+        let kind = if let Some(kind) = gazetteer_kind(word) {
+            Some(kind)
+        } else if !is_candidate_word(word) {
+            None
+        } else {
+            match pos.as_str() {
+                "nr" | "nrfg" | "nrt" if is_cjk_name(word) => Some(TagKind::Person),
+                "ns" => Some(TagKind::Place),
+                "nt" => Some(TagKind::Organization),
+                _ if has_organization_suffix(word) => Some(TagKind::Organization),
+                _ => None,
+            }
+        };
+        if let Some(kind) = kind {
+            record(word.clone(), kind, i);
+        }
+        i += 1;
+    }
+
+    let mut tags = Vec::new();
+    for kind in [TagKind::Person, TagKind::Organization, TagKind::Place] {
+        let mut entities: Vec<(&String, (usize, usize))> =
+            counts.iter().filter(|((_, k), _)| *k == kind).map(|((name, _), &stats)| (name, stats)).collect();
+        // 次数相同时先出现的在前
+        entities.sort_by(|a, b| b.1 .0.cmp(&a.1 .0).then(a.1 .1.cmp(&b.1 .1)));
+        let top = entities.first().map(|(_, (count, _))| *count).unwrap_or(1) as f64;
+        tags.extend(entities.into_iter().take(limit).map(|(name, (count, _))| ExtractedTag {
+            tag: name.clone(),
+            kind,
+            weight: count as f64 / top,
+        }));
+    }
+    tags
+}
+
+// 一篇文章的全部标签：关键词在前，之后是人名、机构、地名
+pub fn extract_tags(title: &str, content: &str) -> Vec<ExtractedTag> {
+    let words = tagged_words(title, content);
+    let mut tags = extract_keywords(&words, MAX_KEYWORDS);
+    tags.extend(extract_entities(&words, MAX_ENTITIES_PER_KIND));
+    tags
+}
+
+// 为还没有标签的文章补充标签（升级后的旧数据），返回处理的文章数
+pub async fn tag_untagged_articles(db_pool: &DbPool, batch_size: i64) -> Result<usize, anyhow::Error> {
+    let client = db_pool.get().await?;
+    let mut tagged = 0;
+    let mut after = None;
+    loop {
+        let articles = get_untagged_articles(&client, after, batch_size).await?;
+        let Some((last_id, _, _)) = articles.last() else {
+            break;
+        };
+        after = Some(*last_id);
+        for (article_id, title, content) in &articles {
+            let tags = extract_tags(title, content);
+            if !tags.is_empty() {
+                replace_article_tags(&client, *article_id, &tags).await?;
+                tagged += 1;
+            }
+        }
+        log::info!("Tagged {} articles so far", tagged);
+    }
+    Ok(tagged)
+}
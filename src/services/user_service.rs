@@ -2,9 +2,12 @@ use uuid::Uuid;
 // use actix_web::web; // Not directly used here, but often in handlers calling this
 
 use crate::db::connection::DbPool;
-use crate::db::user_queries::{find_user_by_id as db_find_user_by_id, update_user_profile as db_update_user_profile, find_user_by_email as db_find_user_by_email};
-use crate::models::user::{UserProfileResponse, UpdateUserProfileSchema};
+use crate::db::interaction_queries::remove_all_favorites;
+use crate::db::unit_of_work::{Isolation, UnitOfWork};
+use crate::db::user_queries::{find_user_by_id as db_find_user_by_id, update_user_profile as db_update_user_profile, find_user_by_email as db_find_user_by_email, lock_user_by_id, delete_user};
+use crate::models::user::{UserProfileResponse, UpdateUserProfileSchema, DeleteAccountSchema};
 use crate::errors::ServiceError;
+use crate::utils::hasher::verify_password;
 
 // Service to get user profile information
 pub async fn get_user_profile_service(pool: &DbPool, user_id: Uuid) -> Result<UserProfileResponse, ServiceError> {
@@ -55,3 +58,21 @@ pub async fn update_user_profile_service(
         }
     }
 }
+
+// 注销账号：确认密码后删除用户及其互动、收藏、评论、阅读历史和反馈，文章计数随之更新，全部在一个事务中完成。
+// 先单独清空收藏夹再删除用户：两处收藏在同一条语句中级联删除时计数触发器会多减一次（见 migrations/0003）
+pub async fn delete_account_service(pool: &DbPool, user_id: Uuid, confirmation: DeleteAccountSchema) -> Result<(), ServiceError> {
+    let uow = UnitOfWork::begin(pool, Isolation::ReadCommitted).await?;
+    let user = lock_user_by_id(&uow, user_id)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound("User not found".to_string()))?;
+    if !verify_password(&confirmation.password, &user.password_hash)? {
+        return Err(ServiceError::Unauthorized("Incorrect password".to_string()));
+    }
+
+    remove_all_favorites(&uow, user_id).await?;
+    delete_user(&uow, user_id).await?;
+    uow.commit().await?;
+    log::info!("Deleted account {} ({})", user.username, user_id);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::errors::ServiceError;
+
+// 键集翻页的游标：把列表最后一行的排序键序列化为 JSON 再做 base64url 编码。
+// 对客户端不透明，只能原样传回；下一页从排在该行之后的记录开始，翻页期间插入的新记录不会造成重复或遗漏。
+
+// 按 (时间, id) 倒序的列表（评论、互动、收藏）的翻页位置
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeCursor {
+    pub at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+pub fn encode_cursor<T: Serialize>(key: &T) -> String {
+    URL_SAFE_NO_PAD.encode(serde_json::to_vec(key).expect("cursor keys serialize to JSON"))
+}
+
+pub fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Result<T, ServiceError> {
+    URL_SAFE_NO_PAD
+        .decode(cursor.trim())
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or_else(|| ServiceError::BadRequest("Invalid cursor".to_string()))
+}
+
+// 查询参数中的游标；没给或为空表示从第一页开始
+pub fn parse_cursor<T: DeserializeOwned>(cursor: Option<&str>) -> Result<Option<T>, ServiceError> {
+    cursor.filter(|c| !c.trim().is_empty()).map(decode_cursor).transpose()
+}
+
+// 查询时多取一行（limit + 1）判断是否还有下一页：有则去掉多取的那一行，并用本页最后一行生成下一页的游标
+pub fn split_page<T, K: Serialize>(mut rows: Vec<T>, limit: i64, key: impl Fn(&T) -> K) -> (Vec<T>, Option<String>) {
+    let limit = usize::try_from(limit).unwrap_or(0);
+    if rows.len() <= limit {
+        return (rows, None);
+    }
+    rows.truncate(limit);
+    let next_cursor = rows.last().map(|row| encode_cursor(&key(row)));
+    (rows, next_cursor)
+}
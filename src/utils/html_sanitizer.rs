@@ -0,0 +1,127 @@
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, UrlRelative};
+use scraper::{ElementRef, Html, Node, Selector};
+use url::Url;
+
+// 文章正文允许保留的标签：标题、段落、列表、引用、链接、图片及图注、表格
+const ALLOWED_TAGS: &[&str] = &[
+    "p", "br", "hr", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "li", "blockquote", "pre", "code",
+    "strong", "em", "b", "i", "u", "sub", "sup", "a", "img", "figure", "figcaption",
+    "table", "caption", "thead", "tbody", "tr", "th", "td",
+];
+
+/// 按白名单清洗抓取到的文章 HTML 片段，相对链接和图片地址按文章 URL 改写为绝对地址。
+pub fn sanitize_article_html(fragment: &str, base_url: &str) -> String {
+    let tag_attributes: HashMap<&str, HashSet<&str>> = [
+        ("a", ["href", "title"].into_iter().collect()),
+        ("img", ["src", "alt", "title", "width", "height"].into_iter().collect()),
+        ("td", ["colspan", "rowspan"].into_iter().collect()),
+        ("th", ["colspan", "rowspan"].into_iter().collect()),
+    ]
+    .into_iter()
+    .collect();
+
+    let url_relative = match Url::parse(base_url) {
+        Ok(base) => UrlRelative::RewriteWithBase(base),
+        Err(_) => UrlRelative::Deny,
+    };
+
+    Builder::default()
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .tag_attributes(tag_attributes)
+        .generic_attributes(HashSet::new())
+        .url_schemes(["http", "https"].into_iter().collect())
+        .url_relative(url_relative)
+        .link_rel(Some("noopener noreferrer nofollow"))
+        .clean(fragment)
+        .to_string()
+        .trim()
+        .to_string()
+}
+
+// SVG 中可以执行脚本或嵌入 HTML 的元素，连同子树一起删除
+const SVG_BLOCKED_ELEMENTS: &[&str] = &[
+    "script", "foreignobject", "iframe", "embed", "object", "handler", "listener",
+];
+
+// 可能携带 URL 的属性，值为 javascript: 等可执行协议时删除
+const SVG_URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "formaction", "from", "to", "values"];
+
+fn escape_xml(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+fn is_executable_url(value: &str) -> bool {
+    let compact: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    compact.starts_with("javascript:") || compact.starts_with("vbscript:") || compact.starts_with("data:text/html")
+}
+
+fn write_svg_element(element: ElementRef, out: &mut String) {
+    let value = element.value();
+    let name = value.name();
+    if SVG_BLOCKED_ELEMENTS.contains(&name.to_ascii_lowercase().as_str()) {
+        return;
+    }
+    out.push('<');
+    out.push_str(name);
+    // 属性按名称排序输出，保证同一份 SVG 每次清洗结果相同（存储按内容哈希去重）
+    let mut attrs: Vec<_> = value.attrs.iter().collect();
+    attrs.sort_by(|(a, _), (b, _)| (a.prefix.as_deref(), &*a.local).cmp(&(b.prefix.as_deref(), &*b.local)));
+    for (attr, attr_value) in attrs {
+        let local: &str = &attr.local;
+        let lower = local.to_ascii_lowercase();
+        if lower.starts_with("on") || (SVG_URL_ATTRIBUTES.contains(&lower.as_str()) && is_executable_url(attr_value)) {
+            continue;
+        }
+        out.push(' ');
+        if let Some(prefix) = attr.prefix.as_deref().filter(|p| !p.is_empty()) {
+            out.push_str(prefix);
+            out.push(':');
+        }
+        out.push_str(local);
+        out.push_str("=\"");
+        escape_xml(attr_value, out);
+        out.push('"');
+    }
+    out.push('>');
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => escape_xml(text, out),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    write_svg_element(child, out);
+                }
+            }
+            // 注释、处理指令等不保留
+            _ => {}
+        }
+    }
+    out.push_str("</");
+    out.push_str(name);
+    out.push('>');
+}
+
+/// 清洗 SVG 图片：删除脚本、foreignObject、事件属性和 javascript: 链接，输出可独立使用的 SVG 文档。
+/// 找不到 <svg> 根元素时返回 None。
+pub fn sanitize_svg(svg: &str) -> Option<String> {
+    let fragment = Html::parse_fragment(svg);
+    let svg_selector = Selector::parse("svg").expect("static selector");
+    let root = fragment.select(&svg_selector).next()?;
+
+    let mut out = String::with_capacity(svg.len());
+    write_svg_element(root, &mut out);
+    Some(out)
+}
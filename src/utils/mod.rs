@@ -1,2 +1,7 @@
 pub mod jwt;
 pub mod hasher;
+pub mod readability;
+pub mod html_sanitizer;
+pub mod tokenizer;
+pub mod textrank;
+pub mod cursor;
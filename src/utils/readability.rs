@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use scraper::{ElementRef, Html, Node, Selector};
+
+// 通用正文抽取：当新闻源的 content_selector 失效或未配置时，按文本密度和链接密度
+// 给 DOM 节点打分，选出最可能是正文的容器，并按段落返回其中的文字。
+
+// 这些标签本身及其子树都不可能是正文
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "nav", "header", "footer", "aside", "form",
+    "iframe", "button", "select", "textarea", "svg", "template",
+];
+
+// 可以作为正文容器的标签
+const CONTAINER_TAGS: &[&str] = &["div", "article", "section", "main", "td", "body"];
+
+// 保留为独立段落的块级标签
+const BLOCK_TAGS: &[&str] = &["p", "h2", "h3", "h4", "h5", "h6", "blockquote", "pre"];
+
+// class/id 中出现这些词（按单词匹配，见 has_negative_hint）的节点视为导航、广告或页面杂项
+const NEGATIVE_HINTS: &[&str] = &[
+    "nav", "menu", "footer", "header", "side", "comment", "share", "social",
+    "advert", "ad", "ads", "banner", "promo", "sponsor", "related", "recommend",
+    "breadcrumb", "copyright", "login", "toolbar", "hot", "rank", "popup", "modal", "qrcode",
+];
+
+// class/id 中出现这些词的节点更可能是正文
+const POSITIVE_HINTS: &[&str] = &[
+    "article", "content", "body", "text", "main", "post", "entry", "story", "detail", "rm_txt", "zw",
+];
+
+// 计入打分的最短段落（按字符数，中文一个字算一个字符）
+const MIN_PARAGRAPH_CHARS: usize = 20;
+
+// 正文容器至少要有这么多分，否则认为页面没有可抽取的正文
+const MIN_CANDIDATE_SCORE: f64 = 10.0;
+
+fn tag_name<'a>(element: &ElementRef<'a>) -> &'a str {
+    element.value().name()
+}
+
+fn class_and_id(element: &ElementRef) -> String {
+    let value = element.value();
+    format!(
+        "{} {}",
+        value.attr("class").unwrap_or(""),
+        value.attr("id").unwrap_or("")
+    )
+    .to_lowercase()
+}
+
+// 把 class/id 拆成单词后匹配，避免 "photo" 命中 "hot"、"head-line" 命中 "ad" 之类的误判；
+// 三个字母以上的提示词也匹配前缀，例如 "navbar"、"advertisement"
+fn has_negative_hint(hints: &str) -> bool {
+    hints
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .any(|token| {
+            NEGATIVE_HINTS
+                .iter()
+                .any(|h| token == *h || (h.len() >= 3 && token.starts_with(h)))
+        })
+}
+
+fn hint_weight(element: &ElementRef) -> f64 {
+    let hints = class_and_id(element);
+    if hints.trim().is_empty() {
+        return 0.0;
+    }
+    let mut weight = 0.0;
+    if has_negative_hint(&hints) {
+        weight -= 25.0;
+    }
+    if POSITIVE_HINTS.iter().any(|h| hints.contains(h)) {
+        weight += 25.0;
+    }
+    weight
+}
+
+// 节点自身或祖先是否属于导航、脚本、广告等区域
+fn is_boilerplate(element: &ElementRef) -> bool {
+    let mut current = Some(*element);
+    while let Some(el) = current {
+        if SKIP_TAGS.contains(&tag_name(&el)) {
+            return true;
+        }
+        // body/html 上的 class 往往是整站主题名，不参与判断
+        if !matches!(tag_name(&el), "body" | "html") && hint_weight(&el) < 0.0 && !is_strongly_positive(&el) {
+            return true;
+        }
+        current = el.parent().and_then(ElementRef::wrap);
+    }
+    false
+}
+
+// 同时带有正负提示时（例如 "article-share"），只有明确的正文类名才算正文
+fn is_strongly_positive(element: &ElementRef) -> bool {
+    let hints = class_and_id(element);
+    ["article-body", "article-content", "rm_txt_con", "main-content", "post-content"]
+        .iter()
+        .any(|h| hints.contains(h))
+}
+
+// 收集节点下的可见文字，跳过脚本、样式等子树
+fn visible_text(element: &ElementRef) -> String {
+    let mut text = String::new();
+    collect_text(element, &mut text);
+    normalize_whitespace(&text)
+}
+
+fn collect_text(element: &ElementRef, out: &mut String) {
+    for child in element.children() {
+        match child.value() {
+            Node::Text(t) => out.push_str(t),
+            Node::Element(_) => {
+                if let Some(child_el) = ElementRef::wrap(child) {
+                    if !SKIP_TAGS.contains(&tag_name(&child_el)) {
+                        if tag_name(&child_el) == "br" {
+                            out.push('\n');
+                        } else {
+                            collect_text(&child_el, out);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+// 链接文字占全部文字的比例
+fn link_density(element: &ElementRef) -> f64 {
+    let total = char_count(&visible_text(element));
+    if total == 0 {
+        return 1.0;
+    }
+    let link_selector = Selector::parse("a").expect("static selector");
+    let link_chars: usize = element
+        .select(&link_selector)
+        .map(|a| char_count(&normalize_whitespace(&a.text().collect::<String>())))
+        .sum();
+    (link_chars as f64 / total as f64).min(1.0)
+}
+
+// 一个段落的基础分：逗号越多、越长越像正文
+fn paragraph_score(text: &str) -> f64 {
+    let commas = text.chars().filter(|c| matches!(c, '，' | ',' | '、' | '；' | ';')).count();
+    1.0 + commas as f64 + (char_count(text) as f64 / 100.0).min(3.0)
+}
+
+// 找出得分最高的正文容器
+fn best_candidate<'a>(document: &'a Html) -> Option<ElementRef<'a>> {
+    let paragraph_selector = Selector::parse("p, pre, td, blockquote").expect("static selector");
+    let mut scores = HashMap::new();
+
+    for paragraph in document.select(&paragraph_selector) {
+        if is_boilerplate(&paragraph) {
+            continue;
+        }
+        let text = visible_text(&paragraph);
+        if char_count(&text) < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let score = paragraph_score(&text);
+
+        // 分数传给父节点（全额）和祖父节点（一半）
+        let parent = paragraph.parent().and_then(ElementRef::wrap);
+        let grandparent = parent.and_then(|p| p.parent()).and_then(ElementRef::wrap);
+        for (ancestor, share) in [(parent, 1.0), (grandparent, 0.5)] {
+            if let Some(ancestor) = ancestor {
+                if !CONTAINER_TAGS.contains(&tag_name(&ancestor)) {
+                    continue;
+                }
+                let entry = scores.entry(ancestor.id()).or_insert_with(|| hint_weight(&ancestor));
+                *entry += score * share;
+            }
+        }
+    }
+
+    scores
+        .into_iter()
+        .filter_map(|(id, score)| {
+            let element = document.tree.get(id).and_then(ElementRef::wrap)?;
+            let adjusted = score * (1.0 - link_density(&element));
+            Some((element, adjusted))
+        })
+        .filter(|(_, score)| *score >= MIN_CANDIDATE_SCORE)
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(element, _)| element)
+}
+
+// 整块保留的结构化元素，文字取其全部可见文字
+const STRUCTURED_TAGS: &[&str] = &["ul", "ol", "table", "figure"];
+
+/// 通用抽取得到的正文：纯文本段落，以及保留结构的 HTML 片段（未清洗）
+#[derive(Debug, Default)]
+pub struct ExtractedContent {
+    pub paragraphs: Vec<String>,
+    pub html: String,
+    pub images: Vec<ExtractedImage>,
+}
+
+/// 正文中的图片，src 为页面中的原始地址（可能是相对地址）
+#[derive(Debug, Clone)]
+pub struct ExtractedImage {
+    pub src: String,
+    pub caption: Option<String>,
+}
+
+/// 图片说明：优先所在 figure 的 figcaption，其次 alt/title 属性
+pub fn image_caption(img: &ElementRef) -> Option<String> {
+    let figcaption_selector = Selector::parse("figcaption").expect("static selector");
+    let from_figure = img
+        .ancestors()
+        .filter_map(ElementRef::wrap)
+        .find(|el| tag_name(el) == "figure")
+        .and_then(|figure| figure.select(&figcaption_selector).next())
+        .map(|caption| visible_text(&caption));
+
+    from_figure
+        .into_iter()
+        .chain(["alt", "title"].iter().filter_map(|attr| img.value().attr(attr).map(normalize_whitespace)))
+        .find(|caption| !caption.is_empty())
+}
+
+// 记录块中的图片（按文档顺序）
+fn collect_images(block: &ElementRef, out: &mut ExtractedContent) {
+    let img_selector = Selector::parse("img").expect("static selector");
+    let images = std::iter::once(*block)
+        .filter(|el| tag_name(el) == "img")
+        .chain(block.select(&img_selector));
+    for img in images {
+        if let Some(src) = img.value().attr("src").filter(|src| !src.trim().is_empty()) {
+            out.images.push(ExtractedImage { src: src.trim().to_string(), caption: image_caption(&img) });
+        }
+    }
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+// 按文档顺序收集容器内的段落，跳过杂项区域和以链接为主的块
+fn collect_blocks(container: &ElementRef, out: &mut ExtractedContent) {
+    for child in container.children() {
+        let Some(child_el) = ElementRef::wrap(child) else {
+            if let Node::Text(t) = child.value() {
+                let text = normalize_whitespace(t);
+                if char_count(&text) >= MIN_PARAGRAPH_CHARS {
+                    out.html.push_str(&format!("<p>{}</p>", escape_text(&text)));
+                    out.paragraphs.push(text);
+                }
+            }
+            continue;
+        };
+
+        let tag = tag_name(&child_el);
+        if SKIP_TAGS.contains(&tag) {
+            continue;
+        }
+        if hint_weight(&child_el) < 0.0 && !is_strongly_positive(&child_el) {
+            continue;
+        }
+
+        if tag == "img" || tag == "figure" {
+            // 图片没有文字也要保留，图注计入正文
+            let caption = visible_text(&child_el);
+            out.html.push_str(&child_el.html());
+            collect_images(&child_el, out);
+            if !caption.is_empty() {
+                out.paragraphs.push(caption);
+            }
+        } else if BLOCK_TAGS.contains(&tag) || STRUCTURED_TAGS.contains(&tag) {
+            let text = visible_text(&child_el);
+            if text.is_empty() {
+                // 只包含图片的段落（常见于 <p><img></p>）
+                let before = out.images.len();
+                collect_images(&child_el, out);
+                if out.images.len() > before {
+                    out.html.push_str(&child_el.html());
+                }
+                continue;
+            }
+            if link_density(&child_el) > 0.5 {
+                continue;
+            }
+            let is_heading = tag.starts_with('h');
+            if is_heading || char_count(&text) >= 2 {
+                out.html.push_str(&child_el.html());
+                collect_images(&child_el, out);
+                out.paragraphs.push(text);
+            }
+        } else {
+            collect_blocks(&child_el, out);
+        }
+    }
+}
+
+/// 抽取页面正文；找不到可信的正文容器时返回空内容。
+pub fn extract_content(document: &Html) -> ExtractedContent {
+    let mut content = ExtractedContent::default();
+    if let Some(container) = best_candidate(document) {
+        collect_blocks(&container, &mut content);
+    }
+    content
+}
+
+/// 抽取页面标题：优先 og:title，其次唯一的 h1，最后 <title>（去掉 "_站点名" 之类的后缀）。
+pub fn extract_title(document: &Html) -> Option<String> {
+    let og_selector = Selector::parse(r#"meta[property="og:title"]"#).expect("static selector");
+    if let Some(title) = document
+        .select(&og_selector)
+        .next()
+        .and_then(|m| m.value().attr("content"))
+        .map(normalize_whitespace)
+        .filter(|t| !t.is_empty())
+    {
+        return Some(title);
+    }
+
+    let h1_selector = Selector::parse("h1").expect("static selector");
+    let h1s: Vec<_> = document
+        .select(&h1_selector)
+        .filter(|h| !is_boilerplate(h))
+        .map(|h| visible_text(&h))
+        .filter(|t| !t.is_empty())
+        .collect();
+    if h1s.len() == 1 {
+        return h1s.into_iter().next();
+    }
+
+    let title_selector = Selector::parse("title").expect("static selector");
+    document
+        .select(&title_selector)
+        .next()
+        .map(|t| normalize_whitespace(&t.text().collect::<String>()))
+        .map(|t| {
+            t.split(['_', '|', '－'])
+                .next()
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        })
+        .filter(|t| !t.is_empty())
+}
@@ -0,0 +1,31 @@
+// TextRank 使用的加权 PageRank。edges[i] 为节点 i 的邻接表 (j, 权重)，图按无向图处理，调用方需双向登记
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 50;
+const TOLERANCE: f64 = 1e-6;
+
+pub fn weighted_pagerank(edges: &[Vec<(usize, f64)>]) -> Vec<f64> {
+    let n = edges.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let out_weight: Vec<f64> = edges.iter().map(|neighbors| neighbors.iter().map(|(_, w)| w).sum()).collect();
+    let mut scores = vec![1.0; n];
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut next = vec![1.0 - DAMPING; n];
+        for (i, neighbors) in edges.iter().enumerate() {
+            if out_weight[i] == 0.0 {
+                continue;
+            }
+            for &(j, w) in neighbors {
+                next[j] += DAMPING * scores[i] * w / out_weight[i];
+            }
+        }
+        let delta: f64 = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+        scores = next;
+        if delta < TOLERANCE {
+            break;
+        }
+    }
+    scores
+}
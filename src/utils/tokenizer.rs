@@ -0,0 +1,60 @@
+use jieba_rs::Jieba;
+use once_cell::sync::Lazy;
+
+use std::collections::HashSet;
+
+// 中文分词（jieba），供分类器等文本特征使用。词典加载较慢，全局只加载一次
+static JIEBA: Lazy<Jieba> = Lazy::new(Jieba::new);
+
+// 新闻正文中高频但不表达主题的词
+const STOPWORDS: &[&str] = &[
+    "一个", "一些", "一样", "一直", "不是", "不会", "为了", "主要", "以及", "以来", "会议", "但是",
+    "今年", "今天", "他们", "以上", "作为", "其中", "具有", "内容", "出现", "可以", "可能", "同时",
+    "因为", "因此", "如果", "已经", "我们", "所以", "报道", "日前", "日电", "明年", "是否", "更加",
+    "有关", "来自", "此前", "没有", "然而", "相关", "目前", "而且", "自己", "记者", "认为", "表示",
+    "这个", "这些", "这是", "这样", "进行", "近日", "还是", "通过", "那么", "部分", "重要", "需要",
+    "非常", "方面", "之后", "之前", "之间", "其他", "去年", "开始", "此外", "正在", "继续", "一是",
+    "二是", "三是", "责编", "编辑", "来源", "原标题",
+];
+
+static STOPWORD_SET: Lazy<HashSet<&'static str>> = Lazy::new(|| STOPWORDS.iter().copied().collect());
+
+pub fn is_stopword(word: &str) -> bool {
+    STOPWORD_SET.contains(word)
+}
+
+// 分词并去掉标点、纯数字、单字和停用词；英文统一小写
+pub fn tokenize(text: &str) -> Vec<String> {
+    JIEBA
+        .cut(text, true)
+        .into_iter()
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| word.chars().count() >= 2)
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .filter(|word| !is_stopword(word))
+        .collect()
+}
+
+// 搜索引擎模式分词：长词之外再给出其中的短词（“中国人民银行”还会切出“人民”“银行”），
+// 用于建立全文检索索引，使查询短词也能命中长词；过滤规则与 tokenize 相同
+pub fn tokenize_for_search(text: &str) -> Vec<String> {
+    JIEBA
+        .cut_for_search(text, true)
+        .into_iter()
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| word.chars().count() >= 2)
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .filter(|word| !is_stopword(word))
+        .collect()
+}
+
+// 分词并标注词性（jieba 词性标记，如 n 名词、nr 人名、ns 地名、nt 机构名、v 动词）。
+// 保留全部词（含标点），调用方按需要过滤
+pub fn tag(text: &str) -> Vec<(String, String)> {
+    JIEBA
+        .tag(text, true)
+        .into_iter()
+        .map(|tag| (tag.word.trim().to_string(), tag.tag.to_string()))
+        .filter(|(word, _)| !word.is_empty())
+        .collect()
+}
@@ -282,8 +282,9 @@ function fetchRecommendations() {
     fetch(`/api/v1/news/articles?page=1&limit=100`)
         .then(response => response.json())
         .then(data => {
-            newsData = data;
-            renderNewsCards(data);
+            // 列表接口返回 { items, total, has_more, ... }
+            newsData = data.items || [];
+            renderNewsCards(newsData);
 
             // 如果有活动类别筛选，应用筛选
             if (activeCategory !== 'all') {
@@ -345,12 +346,14 @@ function renderNewsCards(news) {
         const hasFeedback = userFeedbackHistory && userFeedbackHistory.hasOwnProperty(item.id);
 
         // 处理图片URL - 如果没有图片，使用默认图片
-        const imageUrl = item.image_url || item.imageUrl || '/static/images/default-news.svg';
+        const imageUrl = item.thumbnail_url || item.image_url || item.imageUrl || '/static/images/default-news.svg';
 
         // 安全地获取字段值
         const title = item.title || '无标题';
-        const summary = item.summary || item.content ?
-            (item.content.length > 150 ? item.content.substring(0, 150) + '...' : item.content) :
+        // 列表接口只返回摘要；旧接口或缓存数据可能仍带完整正文
+        const text = item.summary || item.content || '';
+        const summary = text ?
+            (text.length > 150 ? text.substring(0, 150) + '...' : text) :
             '暂无摘要';
         const category = item.categories?.[0] || '未分类';
         const url = item.url || '#';
@@ -358,7 +361,7 @@ function renderNewsCards(news) {
 
         card.innerHTML = `
             <div class="news-card-image">
-                <img src="/api/v1/images?url=${item.url}" alt="${title}" 
+                <img src="${imageUrl}" alt="${title}" 
                 style="max-width:100%"
                 onerror="this.src='/static/images/default-news.svg'">
                 ${hasFeedback ? '<div class="feedback-badge">已反馈</div>' : ''}
@@ -471,7 +474,8 @@ function openNewsModal(newsItem) {
 
     // 处理内容
     if (modalContent) {
-        let contentHtml = `<p>${content.split('\n\n').join('</p><p>')}</p>`;
+        // 优先使用服务端清洗过的 HTML 正文，保留标题、列表、链接和图片
+        let contentHtml = newsItem.content_html || `<p>${content.split('\n\n').join('</p><p>')}</p>`;
 
         // 如果有关键词，添加关键词部分
         if (keywords.length > 0) {
@@ -795,7 +799,7 @@ async function loadUserInteractionsFromServer() {
         });
 
         if (likesResponse.ok) {
-            const likes = await likesResponse.json();
+            const likes = (await likesResponse.json()).items || [];
             likes.forEach(interaction => {
                 if (interaction.is_active) {
                     const button = document.querySelector(`[data-id="${interaction.article_id}"][data-action="like"]`);
@@ -819,7 +823,7 @@ async function loadUserInteractionsFromServer() {
         });
 
         if (favoritesResponse.ok) {
-            const favorites = await favoritesResponse.json();
+            const favorites = (await favoritesResponse.json()).items || [];
             favorites.forEach(favorite => {
                 const button = document.querySelector(`[data-id="${favorite.article_id}"][data-action="favorite"]`);
                 if (button) {
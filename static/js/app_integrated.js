@@ -10,18 +10,8 @@ let currentNewsId = null;
 let commentsData = [];
 let userInteractions = JSON.parse(localStorage.getItem('userInteractions') || '{}');
 
-// 分类相关的图标映射
+// 分类相关的图标映射，来自类别表（/api/v1/categories），加载失败时使用默认图标
 let categoryIcons = {
-    '政治': 'fas fa-landmark',
-    '经济': 'fas fa-chart-line',
-    '科技': 'fas fa-microchip',
-    '体育': 'fas fa-futbol',
-    '娱乐': 'fas fa-film',
-    '健康': 'fas fa-heartbeat',
-    '教育': 'fas fa-graduation-cap',
-    '环境': 'fas fa-leaf',
-    '国际': 'fas fa-globe',
-    '社会': 'fas fa-users',
     'all': 'fas fa-th-large'
 };
 
@@ -231,8 +221,8 @@ window.onload = function () {
         };
     }
 
-    // 初始化分类按钮
-    initCategoryButtons();
+    // 从类别表生成分类按钮
+    loadCategoryButtons();
 
     // 设置模态框关闭按钮事件
     const closeBtn = document.querySelector('.close-btn');
@@ -252,6 +242,40 @@ window.onload = function () {
     setupCommentForm();
 };
 
+// 从类别表生成顶层类别按钮，然后绑定事件
+function loadCategoryButtons() {
+    fetch('/api/v1/categories')
+        .then(response => {
+            if (!response.ok) {
+                throw new Error('获取类别失败');
+            }
+            return response.json();
+        })
+        .then(tree => {
+            const filter = document.querySelector('.category-filter');
+            if (filter) {
+                filter.querySelectorAll('.category-btn:not([data-category="all"])').forEach(btn => btn.remove());
+            }
+            tree.forEach(category => {
+                categoryIcons[category.name_zh] = category.icon || 'fas fa-tag';
+                if (!filter) {
+                    return;
+                }
+                const btn = document.createElement('button');
+                btn.className = 'category-btn';
+                btn.dataset.category = category.name_zh;
+                btn.title = category.name_en;
+                btn.textContent = category.name_zh;
+                filter.appendChild(btn);
+            });
+        })
+        .catch(error => {
+            // 保留页面中已有的按钮
+            console.error('Error fetching categories:', error);
+        })
+        .finally(initCategoryButtons);
+}
+
 // 初始化分类按钮
 function initCategoryButtons() {
     // 为每个分类按钮添加图标
@@ -310,8 +334,9 @@ function fetchRecommendations() {
     fetch(`/api/v1/news/articles?page=1&limit=100`)
         .then(response => response.json())
         .then(data => {
-            newsData = data;
-            renderNewsCards(data);
+            // 列表接口返回 { items, total, has_more, ... }
+            newsData = data.items || [];
+            renderNewsCards(newsData);
 
             // 如果有活动类别筛选，应用筛选
             if (activeCategory !== 'all') {
@@ -349,7 +374,7 @@ function filterNewsByCategory(category) {
     }
 
     // 否则，从服务器获取特定类别的新闻
-    fetch(`/api/v1/articles?category=${category}&page=1&limit=20`)
+    fetch(`/api/v1/articles?category=${encodeURIComponent(category)}&page=1&limit=20`)
         .then(response => {
             if (!response.ok) {
                 throw new Error(`获取${category}类新闻失败`);
@@ -357,8 +382,9 @@ function filterNewsByCategory(category) {
             return response.json();
         })
         .then(data => {
-            newsData = data;
-            renderNewsCards(data);
+            // 列表接口返回 { items, total, has_more, ... }
+            newsData = data.items || [];
+            renderNewsCards(newsData);
         })
         .catch(error => {
             console.error('Error fetching category news:', error);
@@ -490,12 +516,14 @@ function renderNewsCards(news) {
         const categoryIcon = categoryIcons[category] || 'fas fa-tag';
 
         // 处理图片URL - 如果没有图片，使用默认图片
-        const imageUrl = item.image_url || item.imageUrl || '/static/images/default-news.svg';
+        const imageUrl = item.thumbnail_url || item.image_url || item.imageUrl || '/static/images/default-news.svg';
 
         // 安全地获取字段值
         const title = item.title || '无标题';
-        const summary = item.summary || item.content ?
-            (item.content.length > 150 ? item.content.substring(0, 150) + '...' : item.content) :
+        // 列表接口只返回摘要；旧接口或缓存数据可能仍带完整正文
+        const text = item.summary || item.content || '';
+        const summary = text ?
+            (text.length > 150 ? text.substring(0, 150) + '...' : text) :
             '暂无摘要';
         const url = item.url || '#';
         const readCount = item.read_count || 0;
@@ -503,7 +531,7 @@ function renderNewsCards(news) {
 
         card.innerHTML = `
             <div class="news-card-image">
-                <img src="/api/v1/images?url=${item.url}" alt="${title}" 
+                <img src="${imageUrl}" alt="${title}" 
                 style="max-width:100%"
                 onerror="this.src='/static/images/default-news.svg'">
                 ${hasFeedback ? '<div class="feedback-badge">已反馈</div>' : ''}
@@ -565,6 +593,18 @@ function openNewsModal(newsItem) {
         return;
     }
 
+    // 列表数据只带摘要，先按 id 取完整文章
+    if (newsItem.id && !newsItem.content && !newsItem.content_html && !newsItem.fullContentLoaded) {
+        fetch(`/api/v1/news/articles/${newsItem.id}`)
+            .then(response => response.ok ? response.json() : Promise.reject(response.status))
+            .then(article => openNewsModal({ ...newsItem, ...article, fullContentLoaded: true }))
+            .catch(error => {
+                console.warn('加载文章正文失败:', error);
+                openNewsModal({ ...newsItem, content: newsItem.summary, fullContentLoaded: true });
+            });
+        return;
+    }
+
     // 设置新闻ID
     modal.dataset.newsId = newsItem.id || '';
     currentNewsId = newsItem.id;
@@ -606,7 +646,8 @@ function openNewsModal(newsItem) {
 
     // 处理内容
     if (modalContent) {
-        let contentHtml = `<p>${content.split('\n\n').join('</p><p>')}</p>`;
+        // 优先使用服务端清洗过的 HTML 正文，保留标题、列表、链接和图片
+        let contentHtml = newsItem.content_html || `<p>${content.split('\n\n').join('</p><p>')}</p>`;
 
         // 如果有关键词，添加关键词部分
         if (keywords.length > 0) {
@@ -913,7 +954,7 @@ async function loadUserInteractionsFromServer() {
         });
 
         if (likesResponse.ok) {
-            const likes = await likesResponse.json();
+            const likes = (await likesResponse.json()).items || [];
             likes.forEach(interaction => {
                 if (interaction.is_active) {
                     const button = document.querySelector(`[data-id="${interaction.article_id}"][data-action="like"]`);
@@ -937,7 +978,7 @@ async function loadUserInteractionsFromServer() {
         });
 
         if (favoritesResponse.ok) {
-            const favorites = await favoritesResponse.json();
+            const favorites = (await favoritesResponse.json()).items || [];
             favorites.forEach(favorite => {
                 const button = document.querySelector(`[data-id="${favorite.article_id}"][data-action="favorite"]`);
                 if (button) {
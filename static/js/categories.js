@@ -3,20 +3,45 @@
 // 全局变量
 // 避免变量冲突，使用更具体的命名
 let newsCategoryActive = 'all';
+// 图标来自类别表（/api/v1/categories），加载失败时使用默认图标
 let categoryIcons = {
-    '政治': 'fas fa-landmark',
-    '经济': 'fas fa-chart-line',
-    '科技': 'fas fa-microchip',
-    '体育': 'fas fa-futbol',
-    '娱乐': 'fas fa-film',
-    '健康': 'fas fa-heartbeat',
-    '教育': 'fas fa-graduation-cap',
-    '环境': 'fas fa-leaf',
-    '国际': 'fas fa-globe',
-    '社会': 'fas fa-users',
     'all': 'fas fa-th-large'
 };
 
+// 从类别表生成顶层类别按钮，然后绑定事件
+function loadCategoryButtons() {
+    fetch('/api/v1/categories')
+        .then(response => {
+            if (!response.ok) {
+                throw new Error('获取类别失败');
+            }
+            return response.json();
+        })
+        .then(tree => {
+            const filter = document.querySelector('.category-filter');
+            if (filter) {
+                filter.querySelectorAll('.category-btn:not([data-category="all"])').forEach(btn => btn.remove());
+            }
+            tree.forEach(category => {
+                categoryIcons[category.name_zh] = category.icon || 'fas fa-tag';
+                if (!filter) {
+                    return;
+                }
+                const btn = document.createElement('button');
+                btn.className = 'category-btn';
+                btn.dataset.category = category.name_zh;
+                btn.title = category.name_en;
+                btn.textContent = category.name_zh;
+                filter.appendChild(btn);
+            });
+        })
+        .catch(error => {
+            // 保留页面中已有的按钮
+            console.error('Error fetching categories:', error);
+        })
+        .finally(initCategoryButtons);
+}
+
 // 初始化分类按钮
 function initCategoryButtons() {
     // 为每个分类按钮添加图标
@@ -71,7 +96,7 @@ function filterNewsByCategory(category) {
 
     // 否则，从服务器获取特定类别的新闻
     // 修正API路径
-    fetch(`/api/v1/articles?category=${category}&page=1&limit=100`)
+    fetch(`/api/v1/articles?category=${encodeURIComponent(category)}&page=1&limit=100`)
         .then(response => {
             if (!response.ok) {
                 throw new Error(`获取${category}类新闻失败`);
@@ -79,15 +104,16 @@ function filterNewsByCategory(category) {
             return response.json();
         })
         .then(data => {
-            newsData = data;
-            renderNewsCards(data);
+            // 列表接口返回 { items, total, has_more, ... }
+            newsData = data.items || [];
+            renderNewsCards(newsData);
         })
         .catch(error => {
             console.error('Error fetching category news:', error);
 
             // 如果服务器获取失败，尝试从现有数据中筛选
             if (newsData && newsData.length > 0) {
-                const filteredNews = newsData.filter(news => news.category === category);
+                const filteredNews = newsData.filter(news => (news.categories || []).includes(category));
                 if (filteredNews.length > 0) {
                     renderNewsCards(filteredNews);
                 } else {
@@ -199,24 +225,26 @@ function renderNewsCards(news) {
         const hasFeedback = userFeedbackHistory && userFeedbackHistory.hasOwnProperty(item.id);
 
         // 获取分类图标
-        const categoryIcon = categoryIcons[item.category] || 'fas fa-tag';
+        const category = item.categories?.[0] || '未分类';
+        const categoryIcon = categoryIcons[category] || 'fas fa-tag';
 
         // 处理图片URL - 如果没有图片，使用默认图片
-        const imageUrl = item.image_url || item.imageUrl || '/static/images/default-news.svg';
+        const imageUrl = item.thumbnail_url || item.image_url || item.imageUrl || '/static/images/default-news.svg';
 
         // 安全地获取字段值
         const title = item.title || '无标题';
-        const summary = item.summary || item.content ?
-            (item.content.length > 150 ? item.content.substring(0, 150) + '...' : item.content) :
+        // 列表接口只返回摘要；旧接口或缓存数据可能仍带完整正文
+        const text = item.summary || item.content || '';
+        const summary = text ?
+            (text.length > 150 ? text.substring(0, 150) + '...' : text) :
             '暂无摘要';
-        const category = item.category || '未分类';
         const url = item.url || '#';
         const readCount = item.read_count || 0;
         const likeCount = item.like_count || 0;
 
         card.innerHTML = `
             <div class="news-card-image">
-                <img src="/api/v1/images?url=${item.url}" alt="${title}" 
+                <img src="${imageUrl}" alt="${title}" 
                 style="max-width:100%"
                 onerror="this.src='/static/images/default-news.svg'">
                 ${hasFeedback ? '<div class="feedback-badge">已反馈</div>' : ''}
@@ -361,5 +389,5 @@ function applyInteractionsToCards() {
 
 // 在页面加载时初始化分类按钮
 document.addEventListener('DOMContentLoaded', function () {
-    initCategoryButtons();
+    loadCategoryButtons();
 });
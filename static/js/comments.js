@@ -24,8 +24,9 @@ function loadComments(newsId) {
     fetch(`/api/v1/comments/article/${newsId}`)
         .then(response => response.json())
         .then(data => {
-            commentsData = data;
-            renderComments(data);
+            // 接口返回 { items, page, limit, total, has_more, next_cursor }
+            commentsData = data.items || [];
+            renderComments(commentsData);
         })
         .catch(error => {
             console.error('获取评论出错:', error);
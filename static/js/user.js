@@ -346,18 +346,18 @@ function loadFeedbackHistory() {
         
         // 处理评论数据
         if (commentsResponse.ok) {
-            feedbackData.comments = await commentsResponse.json();
+            feedbackData.comments = (await commentsResponse.json()).items || [];
         }
         
         // 处理点赞数据
         if (likesResponse.ok) {
-            const likes = await likesResponse.json();
+            const likes = (await likesResponse.json()).items || [];
             feedbackData.likes = likes.filter(like => like.is_active);
         }
         
         // 处理收藏数据
         if (favoritesResponse.ok) {
-            feedbackData.favorites = await favoritesResponse.json();
+            feedbackData.favorites = (await favoritesResponse.json()).items || [];
         }
         
         renderFeedbackHistory(feedbackData);
@@ -156,12 +156,12 @@ function createCategoryHeatChart() {
     }
     
     // 模拟各类别新闻热度数据，反映用户兴趣
-    const categories = ['科技', '娱乐', '体育', '财经', '健康'];
+    const categories = ['科技', '娱乐', '体育', '经济', '健康'];
     const data = {
         '科技': [85, 92, 88, 95, 89, 91, 97],
         '娱乐': [78, 82, 85, 79, 88, 84, 86],
         '体育': [65, 71, 68, 73, 76, 69, 74],
-        '财经': [72, 75, 78, 81, 77, 83, 85],
+        '经济': [72, 75, 78, 81, 77, 83, 85],
         '健康': [58, 62, 65, 61, 68, 71, 73]
     };
     
@@ -341,7 +341,7 @@ function createUserInterestHeatmap() {
     
     // 用户群体和内容类别
     const userSegments = ['学生群体', '白领群体', '中年群体', '老年群体', '专业人士'];
-    const categories = ['科技', '娱乐', '体育', '财经', '健康', '教育', '政治', '社会'];
+    const categories = ['科技', '娱乐', '体育', '经济', '健康', '教育', '政治', '社会'];
     
     // 热力图数据 - 反映不同用户群体对不同类别的兴趣程度
     const heatData = [
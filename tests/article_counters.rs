@@ -0,0 +1,149 @@
+// 文章的点赞、收藏计数：收藏夹和 favorite 互动两个入口收藏同一篇文章时只计一次（触发器和 recount-articles 的重算口径一致）。
+// 这些测试需要 PostgreSQL，运行方式见 common/mod.rs
+
+mod common;
+
+use tokio_postgres::Client;
+use uuid::Uuid;
+
+use news_recommendation_system::db::article_queries::{insert_article, RecrawlMode};
+use news_recommendation_system::db::interaction_queries::{add_to_favorites, remove_from_favorites, upsert_user_interaction};
+use news_recommendation_system::models::article::{CreateFavoriteSchema, CreateInteractionSchema};
+use news_recommendation_system::services::interaction_service::repair_article_counters;
+
+use common::test_pool;
+
+// 一篇测试文章和两个测试用户，结束时删除（互动和收藏随之级联删除）
+struct Fixture {
+    article_id: Uuid,
+    users: [Uuid; 2],
+}
+
+impl Fixture {
+    async fn create(client: &Client) -> Self {
+        let url = format!("https://example.com/counter-test/{}", Uuid::new_v4());
+        let article = common::article(url, "计数测试", "正文");
+        insert_article(client, &article, RecrawlMode::Scheduled).await.unwrap();
+
+        let mut users = [Uuid::nil(); 2];
+        for user in &mut users {
+            *user = Uuid::new_v4();
+            client
+                .execute(
+                    "INSERT INTO users (id, username, email, password_hash) VALUES ($1, $2, $3, 'x')",
+                    &[&*user, &format!("counter-{}", user.simple()), &format!("{}@example.com", user.simple())],
+                )
+                .await
+                .unwrap();
+        }
+        Fixture { article_id: article.id, users }
+    }
+
+    // (点赞数, 收藏数)
+    async fn counts(&self, client: &Client) -> (i32, i32) {
+        let row = client
+            .query_one("SELECT like_count, favorite_count FROM articles WHERE id = $1", &[&self.article_id])
+            .await
+            .unwrap();
+        (row.get(0), row.get(1))
+    }
+
+    async fn interact(&self, client: &Client, user: usize, interaction_type: &str, is_active: bool) {
+        let data = CreateInteractionSchema {
+            article_id: self.article_id,
+            interaction_type: interaction_type.to_string(),
+            is_active,
+        };
+        upsert_user_interaction(client, self.users[user], data).await.unwrap();
+    }
+
+    async fn add_to_folder(&self, client: &Client, user: usize) {
+        let data = CreateFavoriteSchema { article_id: self.article_id, folder_name: None };
+        add_to_favorites(client, self.users[user], data).await.unwrap();
+    }
+
+    async fn remove_from_folder(&self, client: &Client, user: usize) {
+        assert_eq!(remove_from_favorites(client, self.users[user], self.article_id).await.unwrap(), 1);
+    }
+
+    async fn clean_up(&self, client: &Client) {
+        client.execute("DELETE FROM articles WHERE id = $1", &[&self.article_id]).await.unwrap();
+        client.execute("DELETE FROM users WHERE id = ANY($1)", &[&self.users.to_vec()]).await.unwrap();
+    }
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn favorite_through_both_entry_points_counts_once() {
+    let pool = test_pool().await;
+    let client = pool.get().await.unwrap();
+    let fixture = Fixture::create(&client).await;
+
+    fixture.add_to_folder(&client, 0).await;
+    assert_eq!(fixture.counts(&client).await, (0, 1));
+    fixture.interact(&client, 0, "favorite", true).await;
+    assert_eq!(fixture.counts(&client).await, (0, 1));
+    fixture.interact(&client, 0, "like", true).await;
+    assert_eq!(fixture.counts(&client).await, (1, 1));
+    fixture.interact(&client, 1, "favorite", true).await;
+    assert_eq!(fixture.counts(&client).await, (1, 2));
+
+    // 另一处收藏还在时，取消其中一处不减少收藏数
+    fixture.remove_from_folder(&client, 0).await;
+    assert_eq!(fixture.counts(&client).await, (1, 2));
+    fixture.interact(&client, 0, "favorite", false).await;
+    assert_eq!(fixture.counts(&client).await, (1, 1));
+
+    fixture.add_to_folder(&client, 1).await;
+    fixture.interact(&client, 1, "favorite", false).await;
+    assert_eq!(fixture.counts(&client).await, (1, 1));
+    fixture.remove_from_folder(&client, 1).await;
+    fixture.interact(&client, 0, "like", false).await;
+    assert_eq!(fixture.counts(&client).await, (0, 0));
+
+    fixture.clean_up(&client).await;
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn deleting_a_user_with_both_favorites_counts_once() {
+    let pool = test_pool().await;
+    let client = pool.get().await.unwrap();
+    let fixture = Fixture::create(&client).await;
+
+    fixture.add_to_folder(&client, 0).await;
+    fixture.interact(&client, 0, "favorite", true).await;
+    fixture.interact(&client, 1, "favorite", true).await;
+    assert_eq!(fixture.counts(&client).await, (0, 2));
+
+    // 用户 0 的两处收藏在同一条语句中级联删除
+    client.execute("DELETE FROM users WHERE id = $1", &[&fixture.users[0]]).await.unwrap();
+    assert_eq!(fixture.counts(&client).await, (0, 1));
+
+    fixture.clean_up(&client).await;
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn recount_repairs_drift_with_the_same_deduplication() {
+    let pool = test_pool().await;
+    let client = pool.get().await.unwrap();
+    let fixture = Fixture::create(&client).await;
+
+    // 用户 0 在两处都收藏了，用户 1 只通过互动收藏并点赞
+    fixture.add_to_folder(&client, 0).await;
+    fixture.interact(&client, 0, "favorite", true).await;
+    fixture.interact(&client, 1, "favorite", true).await;
+    fixture.interact(&client, 1, "like", true).await;
+    assert_eq!(fixture.counts(&client).await, (1, 2));
+
+    client
+        .execute("UPDATE articles SET like_count = 5, favorite_count = 3 WHERE id = $1", &[&fixture.article_id])
+        .await
+        .unwrap();
+    // 每批一篇，逐批走完所有文章
+    assert!(repair_article_counters(&pool, 1).await.unwrap() >= 1);
+    assert_eq!(fixture.counts(&client).await, (1, 2));
+
+    fixture.clean_up(&client).await;
+}
@@ -0,0 +1,64 @@
+// 文章列表筛选条件生成的 SQL 和参数编号
+
+use chrono::{TimeZone, Utc};
+use news_recommendation_system::db::article_queries::ArticleFilter;
+use news_recommendation_system::models::article::ArticleSort;
+
+#[test]
+fn empty_filter_only_excludes_hidden_and_deleted_articles_and_sorts_newest_first() {
+    let filter = ArticleFilter::default();
+    let (clause, params) = filter.where_clause();
+    assert_eq!(clause, "WHERE articles.hidden_at IS NULL AND articles.deleted_at IS NULL");
+    assert!(params.is_empty());
+    assert_eq!(filter.order_by(), "ORDER BY COALESCE(published_at, crawled_at) DESC, id DESC");
+}
+
+#[test]
+fn every_condition_gets_its_own_numbered_parameter() {
+    let filter = ArticleFilter {
+        categories: Some(vec!["国际".to_string(), "军事".to_string()]),
+        source_name: Some("人民网".to_string()),
+        published_from: Some(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap()),
+        published_to: Some(Utc.with_ymd_and_hms(2024, 6, 30, 23, 59, 59).unwrap()),
+        has_image: Some(true),
+        tag: None,
+        sort: ArticleSort::MostLiked,
+    };
+    let (clause, params) = filter.where_clause();
+    assert_eq!(params.len(), 4);
+    assert_eq!(
+        clause,
+        "WHERE articles.hidden_at IS NULL AND articles.deleted_at IS NULL \
+         AND categories && $1 AND source_name = $2 AND published_at >= $3 AND published_at <= $4 \
+         AND EXISTS (SELECT 1 FROM news_images i WHERE i.article_id = articles.id)"
+    );
+    assert_eq!(filter.order_by(), "ORDER BY COALESCE(like_count, 0) DESC, COALESCE(published_at, crawled_at) DESC, id DESC");
+}
+
+#[test]
+fn has_image_false_selects_articles_without_images() {
+    let filter = ArticleFilter { has_image: Some(false), ..ArticleFilter::default() };
+    let (clause, params) = filter.where_clause();
+    assert!(clause.ends_with("AND NOT EXISTS (SELECT 1 FROM news_images i WHERE i.article_id = articles.id)"));
+    assert!(params.is_empty());
+}
+
+#[test]
+fn tag_condition_is_case_insensitive() {
+    let filter = ArticleFilter { tag: Some("OpenAI".to_string()), ..ArticleFilter::default() };
+    let (clause, params) = filter.where_clause();
+    assert_eq!(params.len(), 1);
+    assert!(clause.contains("lower(t.tag) = lower($1)"));
+}
+
+#[test]
+fn sort_names_match_the_query_parameter() {
+    for (name, sort) in [
+        ("newest", ArticleSort::Newest),
+        ("most_liked", ArticleSort::MostLiked),
+        ("most_commented", ArticleSort::MostCommented),
+        ("most_favorited", ArticleSort::MostFavorited),
+    ] {
+        assert_eq!(serde_json::from_str::<ArticleSort>(&format!("\"{}\"", name)).unwrap(), sort);
+    }
+}
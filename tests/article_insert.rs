@@ -0,0 +1,110 @@
+// 爬虫写入文章：多行 INSERT 中每篇的结果、同一批中重复的 URL，以及整批失败时逐篇写入。
+// 这些测试需要 PostgreSQL，运行方式见 common/mod.rs
+
+mod common;
+
+use uuid::Uuid;
+
+use news_recommendation_system::db::article_queries::{insert_article, insert_articles, InsertOutcome, RecrawlMode};
+use news_recommendation_system::db::connection::DbPool;
+use news_recommendation_system::models::article::Article;
+use news_recommendation_system::services::crawler_service::insert_crawled_articles;
+
+use common::test_pool;
+
+// 每个测试用自己的 URL 前缀，结束时删除
+struct TestArticles {
+    prefix: String,
+}
+
+impl TestArticles {
+    fn new() -> Self {
+        TestArticles { prefix: format!("https://example.com/insert-test/{}/", Uuid::new_v4()) }
+    }
+
+    fn article(&self, path: &str, content: &str) -> Article {
+        common::article(format!("{}{}", self.prefix, path), &format!("标题 {}", path), content)
+    }
+
+    async fn clean_up(&self, pool: &DbPool) {
+        let client = pool.get().await.unwrap();
+        client
+            .execute("DELETE FROM articles WHERE url LIKE $1", &[&format!("{}%", self.prefix)])
+            .await
+            .unwrap();
+    }
+}
+
+fn kind(outcome: &InsertOutcome) -> &'static str {
+    match outcome {
+        InsertOutcome::Inserted(_) => "inserted",
+        InsertOutcome::AlreadyExisted(_) => "existed",
+        InsertOutcome::Updated(_) => "updated",
+    }
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn outcomes_follow_input_order() {
+    let pool = test_pool().await;
+    let test = TestArticles::new();
+    let client = pool.get().await.unwrap();
+
+    let first = test.article("a", "正文 A");
+    let second = test.article("b", "正文 B");
+    let outcomes = insert_articles(&client, &[first.clone(), second.clone()], RecrawlMode::Scheduled).await.unwrap();
+    assert_eq!(outcomes.iter().map(kind).collect::<Vec<_>>(), ["inserted", "inserted"]);
+    assert_eq!(outcomes[0].article().id, first.id);
+    assert_eq!(outcomes[1].article().id, second.id);
+
+    // 再次抓取：A 没变，B 的正文变了，C 是新文章
+    let again = [test.article("a", "正文 A"), test.article("b", "更正后的正文 B"), test.article("c", "正文 C")];
+    let outcomes = insert_articles(&client, &again, RecrawlMode::Scheduled).await.unwrap();
+    assert_eq!(outcomes.iter().map(kind).collect::<Vec<_>>(), ["existed", "updated", "inserted"]);
+    // 已有文章带回的是库中的文章，而不是这次传入的新 id
+    assert_eq!(outcomes[0].article().id, first.id);
+    assert_eq!(outcomes[1].article().id, second.id);
+    assert_eq!(outcomes[1].article().content, "更正后的正文 B");
+    assert_eq!(outcomes[2].article().id, again[2].id);
+
+    test.clean_up(&pool).await;
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn duplicate_urls_in_one_batch() {
+    let pool = test_pool().await;
+    let test = TestArticles::new();
+    let client = pool.get().await.unwrap();
+
+    // 同一批里同一篇文章出现两次，第三篇是同一 URL 的新版本
+    let batch = [test.article("a", "正文"), test.article("a", "正文"), test.article("a", "新的正文")];
+    let outcomes = insert_articles(&client, &batch, RecrawlMode::Scheduled).await.unwrap();
+    assert_eq!(outcomes.iter().map(kind).collect::<Vec<_>>(), ["inserted", "existed", "updated"]);
+    assert!(outcomes.iter().all(|outcome| outcome.article().id == batch[0].id));
+    assert_eq!(outcomes[2].article().content, "新的正文");
+
+    // 单篇写入同样每次都有一个结果
+    let outcome = insert_article(&client, &test.article("a", "新的正文"), RecrawlMode::Scheduled).await.unwrap();
+    assert_eq!(kind(&outcome), "existed");
+    assert_eq!(outcome.article().id, batch[0].id);
+
+    test.clean_up(&pool).await;
+}
+
+#[actix_web::test]
+#[ignore = "requires TEST_DATABASE_URL"]
+async fn failed_batch_is_retried_one_by_one() {
+    let pool = test_pool().await;
+    let test = TestArticles::new();
+
+    // PostgreSQL 的文本不能含 NUL，这一篇会让整条 INSERT 失败
+    let articles = [test.article("a", "正文 A"), test.article("bad", "正文\0"), test.article("c", "正文 C")];
+    let results = insert_crawled_articles(&pool, &articles, RecrawlMode::Scheduled).await.unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(matches!(&results[0], Ok(InsertOutcome::Inserted(a)) if a.id == articles[0].id));
+    assert!(results[1].is_err());
+    assert!(matches!(&results[2], Ok(InsertOutcome::Inserted(c)) if c.id == articles[2].id));
+
+    test.clean_up(&pool).await;
+}
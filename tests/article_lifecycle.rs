@@ -0,0 +1,158 @@
+// 管理员编辑文章时新旧版本的合并，再次抓取到已有文章时是否更新，以及管理接口的返回格式
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use news_recommendation_system::db::article_queries::{ArticleContent, RecrawlMode};
+use news_recommendation_system::errors::ServiceError;
+use news_recommendation_system::models::article::{Article, ManagedArticle, RevisionSource, UpdateArticleSchema};
+use news_recommendation_system::services::article_service::apply_edit;
+
+const URL: &str = "https://example.com/news/1.html";
+
+fn current() -> ArticleContent {
+    ArticleContent {
+        title: "原标题".to_string(),
+        content: "原来的正文。".to_string(),
+        content_html: Some("<p>原来的正文。</p>".to_string()),
+        summary: Some("原来的摘要".to_string()),
+    }
+}
+
+fn edit() -> UpdateArticleSchema {
+    UpdateArticleSchema { title: None, content: None, content_html: None, summary: None }
+}
+
+#[test]
+fn empty_edit_is_rejected() {
+    assert!(matches!(apply_edit(&current(), &edit(), URL), Err(ServiceError::BadRequest(_))));
+}
+
+#[test]
+fn blank_title_is_rejected() {
+    let edit = UpdateArticleSchema { title: Some("  ".to_string()), ..edit() };
+    assert!(matches!(apply_edit(&current(), &edit, URL), Err(ServiceError::BadRequest(_))));
+}
+
+#[test]
+fn title_only_edit_keeps_the_body() {
+    let edit = UpdateArticleSchema { title: Some(" 更正后的标题 ".to_string()), ..edit() };
+    let revised = apply_edit(&current(), &edit, URL).unwrap();
+    assert_eq!(revised.title, "更正后的标题");
+    assert_eq!(revised.content, current().content);
+    assert_eq!(revised.content_html, current().content_html);
+    assert_eq!(revised.summary, current().summary);
+}
+
+#[test]
+fn new_content_drops_stale_html_and_regenerates_the_summary() {
+    let edit = UpdateArticleSchema { content: Some("更正后的正文。补充了新的细节。".to_string()), ..edit() };
+    let revised = apply_edit(&current(), &edit, URL).unwrap();
+    assert_eq!(revised.content_html, None);
+    assert!(revised.summary.as_deref().is_some_and(|s| s.contains("更正后的正文")));
+}
+
+#[test]
+fn given_html_is_sanitized() {
+    let edit = UpdateArticleSchema {
+        content: Some("新正文".to_string()),
+        content_html: Some("<p>新正文</p><script>alert(1)</script>".to_string()),
+        ..edit()
+    };
+    let revised = apply_edit(&current(), &edit, URL).unwrap();
+    let html = revised.content_html.unwrap();
+    assert!(html.contains("新正文"));
+    assert!(!html.contains("script"));
+}
+
+#[test]
+fn unchanged_content_keeps_html_and_summary() {
+    let edit = UpdateArticleSchema { content: Some(current().content), ..edit() };
+    assert_eq!(apply_edit(&current(), &edit, URL).unwrap(), current());
+}
+
+// 重新抓取到的版本：正文相同，HTML 和摘要的生成结果不同
+fn recrawled_with_new_markup() -> ArticleContent {
+    ArticleContent {
+        content_html: Some("<div><p>原来的正文。</p></div>".to_string()),
+        summary: Some("重新生成的摘要".to_string()),
+        ..current()
+    }
+}
+
+fn recrawled_with_new_body() -> ArticleContent {
+    ArticleContent { content: "更正后的正文。".to_string(), ..current() }
+}
+
+#[test]
+fn scheduled_crawl_ignores_markup_and_summary_changes() {
+    for last_revision in [None, Some(RevisionSource::Crawl)] {
+        assert!(!RecrawlMode::Scheduled.should_update(&current(), last_revision, &current()));
+        assert!(!RecrawlMode::Scheduled.should_update(&current(), last_revision, &recrawled_with_new_markup()));
+    }
+}
+
+#[test]
+fn scheduled_crawl_updates_a_changed_title_or_body() {
+    let retitled = ArticleContent { title: "更正后的标题".to_string(), ..current() };
+    for last_revision in [None, Some(RevisionSource::Crawl)] {
+        assert!(RecrawlMode::Scheduled.should_update(&current(), last_revision, &recrawled_with_new_body()));
+        assert!(RecrawlMode::Scheduled.should_update(&current(), last_revision, &retitled));
+    }
+}
+
+#[test]
+fn scheduled_crawl_keeps_admin_edits() {
+    let edited = ArticleContent { title: "编辑改过的标题".to_string(), ..current() };
+    let admin = Some(RevisionSource::Admin);
+    assert!(!RecrawlMode::Scheduled.should_update(&edited, admin, &current()));
+    assert!(!RecrawlMode::Scheduled.should_update(&edited, admin, &recrawled_with_new_body()));
+}
+
+#[test]
+fn requested_recrawl_overwrites_any_difference() {
+    let edited = ArticleContent { title: "编辑改过的标题".to_string(), ..current() };
+    assert!(RecrawlMode::Requested.should_update(&edited, Some(RevisionSource::Admin), &current()));
+    assert!(RecrawlMode::Requested.should_update(&current(), None, &recrawled_with_new_markup()));
+    assert!(!RecrawlMode::Requested.should_update(&current(), Some(RevisionSource::Admin), &current()));
+}
+
+#[test]
+fn revision_sources_round_trip() {
+    for source in [RevisionSource::Crawl, RevisionSource::Admin] {
+        assert_eq!(RevisionSource::parse(source.as_str()), Some(source));
+    }
+    assert_eq!(RevisionSource::parse("robot"), None);
+}
+
+#[test]
+fn managed_article_flattens_the_article_fields() {
+    let managed = ManagedArticle {
+        article: Article {
+            id: Uuid::new_v4(),
+            title: "标题".to_string(),
+            content: String::new(),
+            content_html: None,
+            summary: None,
+            url: URL.to_string(),
+            source_name: None,
+            published_at: None,
+            crawled_at: Utc.with_ymd_and_hms(2024, 6, 2, 9, 30, 0).unwrap(),
+            categories: None,
+            source_category: None,
+            like_count: None,
+            comment_count: None,
+            favorite_count: None,
+            cover_image_id: None,
+            tags: Vec::new(),
+        },
+        updated_at: None,
+        hidden_at: Some(Utc.with_ymd_and_hms(2024, 6, 3, 0, 0, 0).unwrap()),
+        deleted_at: None,
+    };
+    assert!(!managed.is_deleted());
+    let json = serde_json::to_value(&managed).unwrap();
+    assert_eq!(json["title"], "标题");
+    assert_eq!(json["hidden_at"], "2024-06-03T00:00:00Z");
+    assert!(json.get("article").is_none());
+}
@@ -0,0 +1,109 @@
+// 文章列表的字段投影（fields 参数）和响应压缩
+
+use actix_web::http::header;
+use actix_web::middleware::Compress;
+use actix_web::test::{call_service, init_service, TestRequest};
+use actix_web::{web, App, HttpResponse};
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use news_recommendation_system::api::news_handler::{article_list_item, ArticleFieldsQuery};
+use news_recommendation_system::db::article_queries::ArticleCursor;
+use news_recommendation_system::models::article::{Article, ArticleFields, ArticleListItem, ArticleSort, ArticleSummary};
+
+fn article() -> Article {
+    Article {
+        id: Uuid::new_v4(),
+        title: "标题".to_string(),
+        content: "第一句正文。第二句正文。".repeat(50),
+        content_html: Some("<p>正文</p>".to_string()),
+        summary: None,
+        url: "https://example.com/a".to_string(),
+        source_name: Some("人民网".to_string()),
+        published_at: Some(Utc.with_ymd_and_hms(2024, 6, 1, 8, 0, 0).unwrap()),
+        crawled_at: Utc.with_ymd_and_hms(2024, 6, 2, 9, 30, 0).unwrap(),
+        categories: Some(vec!["国际".to_string()]),
+        source_category: None,
+        like_count: Some(3),
+        comment_count: Some(2),
+        favorite_count: Some(1),
+        cover_image_id: Some(42),
+        tags: Vec::new(),
+    }
+}
+
+#[test]
+fn summary_drops_the_body_and_links_the_thumbnail() {
+    let summary = ArticleSummary::from(article());
+    assert_eq!(summary.thumbnail_url.as_deref(), Some("/api/v1/images/42?w=640"));
+
+    let json = serde_json::to_value(ArticleListItem::Summary(summary)).unwrap();
+    let object = json.as_object().unwrap();
+    for key in ["content", "content_html", "tags", "cover_image_id", "source_category"] {
+        assert!(!object.contains_key(key), "{}", key);
+    }
+    for key in ["id", "title", "summary", "source_name", "published_at", "categories", "like_count", "thumbnail_url"] {
+        assert!(object.contains_key(key), "{}", key);
+    }
+}
+
+#[test]
+fn article_without_images_has_no_thumbnail() {
+    let summary = ArticleSummary::from(Article { cover_image_id: None, ..article() });
+    assert_eq!(summary.thumbnail_url, None);
+}
+
+#[test]
+fn list_items_get_a_summary_in_both_projections() {
+    let ArticleListItem::Summary(summary) = article_list_item(article(), ArticleFields::Summary) else {
+        panic!("expected a summary item");
+    };
+    assert!(summary.summary.is_some_and(|s| !s.is_empty()));
+
+    let ArticleListItem::Full(full) = article_list_item(article(), ArticleFields::Full) else {
+        panic!("expected a full item");
+    };
+    assert!(full.summary.is_some());
+    assert!(!full.content.is_empty());
+    assert!(full.content_html.is_some());
+}
+
+#[test]
+fn fields_parameter_defaults_to_summary() {
+    let query = web::Query::<ArticleFieldsQuery>::from_query("").unwrap();
+    assert_eq!(query.fields.unwrap_or_default(), ArticleFields::Summary);
+    let query = web::Query::<ArticleFieldsQuery>::from_query("fields=full").unwrap();
+    assert_eq!(query.fields, Some(ArticleFields::Full));
+    assert!(web::Query::<ArticleFieldsQuery>::from_query("fields=everything").is_err());
+}
+
+#[test]
+fn summary_and_full_items_produce_the_same_cursor() {
+    let full = article();
+    let summary = ArticleSummary::from(full.clone());
+    for sort in [ArticleSort::Newest, ArticleSort::MostLiked, ArticleSort::MostCommented, ArticleSort::MostFavorited] {
+        assert_eq!(ArticleCursor::for_summary(&summary, sort), ArticleCursor::for_article(&full, sort));
+    }
+}
+
+#[actix_web::test]
+async fn json_responses_are_compressed_when_the_client_accepts_it() {
+    let app = init_service(
+        App::new()
+            .wrap(Compress::default())
+            .route("/list", web::get().to(|| async { HttpResponse::Ok().json(vec![ArticleSummary::from(article()); 20]) })),
+    )
+    .await;
+
+    for encoding in ["gzip", "br"] {
+        let request = TestRequest::get()
+            .uri("/list")
+            .insert_header((header::ACCEPT_ENCODING, encoding))
+            .to_request();
+        let response = call_service(&app, request).await;
+        assert_eq!(response.headers().get(header::CONTENT_ENCODING).unwrap(), encoding);
+    }
+
+    let response = call_service(&app, TestRequest::get().uri("/list").to_request()).await;
+    assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+}
@@ -0,0 +1,45 @@
+// 全文检索：索引词、检索词、标题高亮和摘录
+
+use news_recommendation_system::services::search_service::{highlight, index_terms, query_terms, snippet};
+
+#[test]
+fn title_terms_are_weighted_and_long_words_also_index_their_parts() {
+    let (terms, length) = index_terms("央行降准", "中国人民银行宣布降准。");
+    let tf = |word: &str| terms.iter().find(|(term, _)| term == word).map(|(_, tf)| *tf);
+    assert_eq!(tf("央行"), Some(3));
+    assert_eq!(tf("降准"), Some(4)); // 标题 3 + 正文 1
+    assert_eq!(tf("中国人民银行"), Some(1));
+    assert_eq!(tf("银行"), Some(1));
+    assert_eq!(length, terms.iter().map(|(_, tf)| tf).sum::<i32>());
+}
+
+#[test]
+fn query_terms_are_deduplicated_and_skip_stopwords() {
+    let terms = query_terms("人民银行 我们 人民银行 AI");
+    assert_eq!(terms, vec!["人民", "银行", "人民银行", "ai"]);
+    assert!(query_terms("的 了 。").is_empty());
+}
+
+#[test]
+fn highlight_escapes_html_and_prefers_the_longest_match() {
+    let terms = vec!["银行".to_string(), "人民银行".to_string(), "ai".to_string()];
+    assert_eq!(
+        highlight("<b>中国人民银行</b>和 AI 银行", &terms),
+        "&lt;b&gt;中国<em>人民银行</em>&lt;/b&gt;和 <em>AI</em> <em>银行</em>"
+    );
+}
+
+#[test]
+fn snippet_starts_near_the_first_match() {
+    let text = format!("{}新能源汽车出口增长{}", "前文".repeat(40), "后文".repeat(100));
+    let terms = vec!["出口".to_string()];
+    let excerpt = snippet(&text, &terms);
+    assert!(excerpt.starts_with('…') && excerpt.ends_with('…'));
+    assert!(excerpt.contains("新能源汽车<em>出口</em>增长"));
+    // 命中词前保留 30 个字：“前文”25 个字加“新能源汽车”
+    let lead = excerpt.trim_start_matches('…').split("新能源").next().unwrap();
+    assert_eq!(lead.chars().count(), 25);
+
+    // 没有命中时取开头
+    assert_eq!(snippet("短新闻", &terms), "短新闻");
+}
@@ -0,0 +1,38 @@
+// 抽取式摘要：中文分句和 TextRank 选句
+
+use news_recommendation_system::services::summary_service::{split_sentences, summarize};
+
+#[test]
+fn sentences_split_on_chinese_terminators_and_keep_closing_quotes() {
+    let text = "他说：“会议取得成功。”随后离开！真的吗？？\n下一段没有句号";
+    assert_eq!(
+        split_sentences(text),
+        vec!["他说：“会议取得成功。”", "随后离开！", "真的吗？？", "下一段没有句号"]
+    );
+    assert!(split_sentences("。。\n\n ").is_empty());
+}
+
+#[test]
+fn summary_picks_central_sentences_in_original_order() {
+    let content = "新能源汽车销量持续增长。\
+                   今天天气晴朗。\
+                   新能源汽车出口规模扩大，电池技术进步明显。\
+                   小区门口新开了一家面包店。\
+                   业内人士认为，电池技术和新能源汽车产业链将继续受益。";
+    let summary = summarize(content);
+    assert!(summary.starts_with("新能源汽车销量持续增长。"), "{}", summary);
+    assert!(summary.contains("电池技术进步明显"));
+    assert!(!summary.contains("面包店"));
+    assert!(!summary.contains("天气"));
+}
+
+#[test]
+fn summary_is_bounded_and_short_texts_are_kept_whole() {
+    assert_eq!(summarize("一句话的新闻。"), "一句话的新闻。");
+    assert_eq!(summarize(""), "");
+
+    let long_sentence = format!("{}。", "很长的句子".repeat(100));
+    let summary = summarize(&long_sentence);
+    assert_eq!(summary.chars().count(), 200);
+    assert!(summary.ends_with('…'));
+}
@@ -0,0 +1,86 @@
+// 文章标签：TextRank 关键词、人名 / 机构 / 地名抽取
+
+use news_recommendation_system::models::article::TagKind;
+use news_recommendation_system::services::tagging_service::{extract_entities, extract_keywords, extract_tags};
+use news_recommendation_system::utils::textrank::weighted_pagerank;
+
+fn words(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs.iter().map(|(word, pos)| (word.to_string(), pos.to_string())).collect()
+}
+
+fn names(tags: &[news_recommendation_system::services::tagging_service::ExtractedTag], kind: TagKind) -> Vec<String> {
+    tags.iter().filter(|t| t.kind == kind).map(|t| t.tag.clone()).collect()
+}
+
+#[test]
+fn pagerank_ranks_the_hub_of_a_star_highest() {
+    // 0 与 1、2、3 相连，1、2、3 之间不相连
+    let edges = vec![
+        vec![(1, 1.0), (2, 1.0), (3, 1.0)],
+        vec![(0, 1.0)],
+        vec![(0, 1.0)],
+        vec![(0, 1.0)],
+    ];
+    let scores = weighted_pagerank(&edges);
+    assert!(scores[1..].iter().all(|&s| s < scores[0]));
+    assert!((scores[1] - scores[2]).abs() < 1e-9);
+    assert!(weighted_pagerank(&[]).is_empty());
+}
+
+#[test]
+fn keywords_favor_words_that_co_occur_with_many_others() {
+    let sequence = words(&[
+        ("芯片", "n"), ("产业", "n"), ("的", "uj"), ("芯片", "n"), ("制造", "vn"), ("。", "x"),
+        ("芯片", "n"), ("出口", "vn"), ("我们", "r"), ("芯片", "n"), ("设计", "vn"), ("表示", "v"),
+    ]);
+    let keywords = extract_keywords(&sequence, 3);
+    assert_eq!(keywords.len(), 3);
+    assert_eq!(keywords[0].tag, "芯片");
+    assert_eq!(keywords[0].weight, 1.0);
+    assert!(keywords.iter().all(|t| t.kind == TagKind::Keyword));
+    // 停用词、动词和标点不是候选词
+    let all = names(&extract_keywords(&sequence, 10), TagKind::Keyword);
+    assert!(!all.iter().any(|w| w == "我们" || w == "表示" || w == "的"));
+}
+
+#[test]
+fn entities_come_from_pos_tags_gazetteer_and_suffix_rules() {
+    let sequence = words(&[
+        ("张伟", "nr"), ("在", "p"), ("北京", "ns"), ("大学", "n"), ("演讲", "vn"), ("，", "x"),
+        ("世卫组织", "n"), ("和", "c"), ("华为公司", "nz"), ("派员", "v"), ("出席", "v"), ("。", "x"),
+        ("张伟", "nr"), ("此前", "t"), ("在", "p"), ("上海", "ns"), ("工作", "vn"),
+        ("欧阳", "nr"), ("诸葛孔明先生", "nr"),
+    ]);
+    let entities = extract_entities(&sequence, 5);
+    // 人名只保留 2-4 个汉字，按出现次数排序
+    assert_eq!(names(&entities, TagKind::Person), vec!["张伟", "欧阳"]);
+    let persons: Vec<f64> = entities.iter().filter(|t| t.kind == TagKind::Person).map(|t| t.weight).collect();
+    assert_eq!(persons, vec![1.0, 0.5]);
+    // 地名 + 机构后缀合并，机构名表和后缀规则补充 jieba 的标注
+    assert_eq!(names(&entities, TagKind::Organization), vec!["北京大学", "世卫组织", "华为公司"]);
+    assert_eq!(names(&entities, TagKind::Place), vec!["上海"]);
+}
+
+#[test]
+fn extracted_tags_cover_keywords_and_every_entity_kind() {
+    let title = "联合国秘书长古特雷斯访问中国";
+    let content = "国家主席习近平在北京会见联合国秘书长古特雷斯。习近平指出，联合国是最具普遍性的国际组织。\
+                   清华大学和中国人民银行的专家参加了座谈，上海和深圳的代表也出席。";
+    let tags = extract_tags(title, content);
+    assert!(names(&tags, TagKind::Keyword).contains(&"联合国".to_string()));
+    assert!(names(&tags, TagKind::Person).contains(&"习近平".to_string()));
+    assert_eq!(names(&tags, TagKind::Organization)[0], "联合国");
+    assert!(names(&tags, TagKind::Organization).contains(&"清华大学".to_string()));
+    assert!(names(&tags, TagKind::Place).contains(&"上海".to_string()));
+    assert!(tags.iter().all(|t| t.weight > 0.0 && t.weight <= 1.0));
+    assert!(extract_tags("", "").is_empty());
+}
+
+#[test]
+fn tag_kinds_round_trip_through_their_names() {
+    for kind in [TagKind::Keyword, TagKind::Person, TagKind::Organization, TagKind::Place] {
+        assert_eq!(TagKind::parse(kind.as_str()), Some(kind));
+        assert_eq!(serde_json::to_string(&kind).unwrap(), format!("\"{}\"", kind.as_str()));
+    }
+    assert_eq!(TagKind::parse("topic"), None);
+}
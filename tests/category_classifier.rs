@@ -0,0 +1,101 @@
+// 文章类别分类器：关键词兜底规则、在小语料上训练、模型保存与加载、与栏目类别的一致率
+
+use news_recommendation_system::db::category_queries::LabeledExample;
+use news_recommendation_system::services::classifier_service::{
+    measure_agreement, Classifier, ClassifierModel, UNCATEGORIZED,
+};
+
+fn example(title: &str, content: &str, categories: &[&str]) -> LabeledExample {
+    LabeledExample {
+        article_id: uuid::Uuid::new_v4(),
+        title: title.to_string(),
+        content: content.to_string(),
+        categories: categories.iter().map(|c| c.to_string()).collect(),
+    }
+}
+
+fn corpus() -> Vec<LabeledExample> {
+    vec![
+        example("国足晋级世界杯预选赛下一轮", "足球比赛中国家队两球取胜，球员表现出色，主教练赛后接受采访。", &["体育"]),
+        example("篮球联赛总决赛开打", "篮球联赛总决赛首场比赛，主队球员连续得分，球迷热情高涨。", &["体育"]),
+        example("马拉松比赛吸引万名选手", "比赛当天选手冲过终点，冠军打破赛会纪录，体育部门表示满意。", &["体育"]),
+        example("央行下调存款准备金率", "央行宣布降准，释放长期资金，股市应声上涨，银行信贷将继续扩张。", &["经济"]),
+        example("前三季度外贸进出口增长", "海关数据显示进出口总值增长，贸易顺差扩大，出口企业订单回暖。", &["经济"]),
+        example("股市成交额再破万亿", "股市成交活跃，券商板块领涨，投资者对经济复苏预期增强，资金流入。", &["经济"]),
+        example("国产芯片取得新突破", "芯片企业发布新一代处理器，算力大幅提升，人工智能应用将受益。", &["科技"]),
+        example("量子计算机实现新纪录", "科研团队发布量子计算成果，芯片比特数刷新纪录，算力远超传统计算机。", &["科技"]),
+        example("人工智能大模型开放使用", "科技公司发布人工智能大模型，开发者可以调用接口，算力需求随之增长。", &["科技", "经济"]),
+    ]
+}
+
+#[test]
+fn keyword_rules_count_shared_keywords_for_every_category() {
+    // “外交”同时属于政治和国际，两个类别都计数；命中数相同时按关键词表顺序取政治
+    let scores = Classifier::Keywords.classify("外交部发言人答记者问", "");
+    assert_eq!(scores[0].category, "政治");
+    assert_eq!(scores[0].confidence, 0.5);
+
+    let scores = Classifier::Keywords.classify("联合国大会开幕", "各国外交官出席");
+    assert_eq!(scores[0].category, "国际");
+
+    let scores = Classifier::Keywords.classify("今日天气晴", "");
+    assert_eq!(scores[0].category, UNCATEGORIZED);
+}
+
+#[test]
+fn trained_model_scores_unseen_articles() {
+    let model = ClassifierModel::train(&corpus()).unwrap();
+    assert_eq!(model.category_names(), vec!["体育", "科技", "经济"]);
+
+    let scores = model.classify("足球联赛揭幕", "球员在比赛中表现出色，球迷为主队加油。");
+    assert_eq!(scores[0].category, "体育");
+    assert!(scores[0].confidence > 0.5);
+    // 置信度从高到低排列
+    assert!(scores.windows(2).all(|pair| pair[0].confidence >= pair[1].confidence));
+
+    let scores = model.classify("新款芯片发布", "处理器算力提升，人工智能应用更快。");
+    assert_eq!(scores[0].category, "科技");
+
+    // 没有任何已知词时归为未分类
+    let scores = model.classify("", "");
+    assert_eq!(scores[0].category, UNCATEGORIZED);
+}
+
+#[test]
+fn model_survives_a_save_and_load_round_trip() {
+    let model = ClassifierModel::train(&corpus()).unwrap();
+    let path = std::env::temp_dir().join(format!("category-classifier-{}.json", uuid::Uuid::new_v4()));
+    model.save(&path).unwrap();
+
+    let loaded = ClassifierModel::load(&path).unwrap();
+    let text = ("降准释放资金", "银行信贷扩张，股市上涨。");
+    assert_eq!(loaded.classify(text.0, text.1), model.classify(text.0, text.1));
+    assert_eq!(loaded.training_examples, 9);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn training_requires_labeled_articles() {
+    assert!(ClassifierModel::train(&[]).is_err());
+    assert!(ClassifierModel::train(&[example("标题", "正文", &[])]).is_err());
+}
+
+#[test]
+fn agreement_counts_top_labels_matching_the_source_section() {
+    let examples = vec![
+        example("股市大涨", "金融板块领涨，贸易数据向好", &["经济"]),
+        example("降息落地", "银行下调贷款利率", &["经济"]),
+        example("卫星发射成功", "人工智能与量子通信载荷入轨", &["科技"]),
+    ];
+    let report = measure_agreement(&Classifier::Keywords, &examples);
+
+    assert_eq!(report.articles, 3);
+    assert_eq!(report.agreed, 2);
+    let economy = &report.by_source_category[0];
+    assert_eq!(economy.source_category, "经济");
+    assert_eq!((economy.articles, economy.agreed), (2, 1));
+    // 关键词规则没有命中的文章落到“其他”
+    let predicted: Vec<_> = economy.predicted.iter().map(|p| (p.category.as_str(), p.count)).collect();
+    assert_eq!(predicted, vec![("其他", 1), ("经济", 1)]);
+}
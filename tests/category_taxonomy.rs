@@ -0,0 +1,72 @@
+// 类别表：名称 / slug / 别名解析、层级和合并前后的解析结果
+
+use news_recommendation_system::models::category::Category;
+use news_recommendation_system::services::category_service::CategoryTaxonomy;
+
+fn category(id: i32, slug: &str, name_zh: &str, name_en: &str, parent_id: Option<i32>, aliases: &[&str]) -> Category {
+    Category {
+        id,
+        slug: slug.to_string(),
+        name_zh: name_zh.to_string(),
+        name_en: name_en.to_string(),
+        parent_id,
+        position: id,
+        icon: None,
+        aliases: aliases.iter().map(|a| a.to_string()).collect(),
+    }
+}
+
+fn taxonomy() -> CategoryTaxonomy {
+    CategoryTaxonomy::new(vec![
+        category(1, "economy", "经济", "Economy", None, &["财经", "金融"]),
+        category(2, "world", "国际", "World", None, &[]),
+        category(3, "military", "军事", "Military", Some(2), &[]),
+        category(4, "weapons", "武器", "Weapons", Some(3), &[]),
+        category(5, "other", "其他", "Other", None, &["未分类"]),
+    ])
+}
+
+#[test]
+fn names_slugs_ids_and_aliases_resolve_to_the_same_category() {
+    let taxonomy = taxonomy();
+    for name in ["经济", "economy", "Economy", " ECONOMY ", "1", "财经", "金融"] {
+        assert_eq!(taxonomy.resolve(name).map(|c| c.id), Some(1), "{:?} should resolve", name);
+    }
+    assert!(taxonomy.resolve("娱乐").is_none());
+}
+
+#[test]
+fn canonicalize_deduplicates_and_reports_unknown_names() {
+    let taxonomy = taxonomy();
+    let (resolved, unknown) = taxonomy.canonicalize(["财经", "military", "经济", "娱乐"]);
+    assert_eq!(resolved, vec!["经济", "军事"]);
+    assert_eq!(unknown, vec!["娱乐"]);
+
+    // 分类器输出全部无法解析时归为未分类
+    assert_eq!(taxonomy.resolve_labels(["娱乐"]), vec!["其他"]);
+}
+
+#[test]
+fn hierarchy_rolls_up_to_the_top_level() {
+    let taxonomy = taxonomy();
+    let weapons = taxonomy.resolve("武器").unwrap();
+    let world = taxonomy.resolve("国际").unwrap();
+
+    assert_eq!(taxonomy.root_of(weapons).name_zh, "国际");
+    assert_eq!(taxonomy.descendant_names(world), vec!["国际", "军事", "武器"]);
+    assert!(taxonomy.is_descendant(weapons, world));
+    assert!(!taxonomy.is_descendant(world, weapons));
+
+    let tree = taxonomy.tree();
+    let names: Vec<_> = tree.iter().map(|node| node.category.name_zh.as_str()).collect();
+    assert_eq!(names, vec!["经济", "国际", "其他"]);
+    assert_eq!(tree[1].children[0].category.name_zh, "军事");
+    assert_eq!(tree[1].children[0].children[0].category.name_zh, "武器");
+}
+
+#[test]
+fn merged_names_keep_resolving_through_aliases() {
+    // 合并后“财经”类别被删除，它的名称成为“经济”的别名
+    let taxonomy = CategoryTaxonomy::new(vec![category(1, "economy", "经济", "Economy", None, &["财经", "金融"])]);
+    assert_eq!(taxonomy.resolve_labels(["财经", "经济"]), vec!["经济"]);
+}
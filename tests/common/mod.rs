@@ -0,0 +1,39 @@
+// 需要 PostgreSQL 的集成测试共用的连接池和测试文章。
+// 这些测试标记为 #[ignore]：用 TEST_DATABASE_URL 指定一个测试库（会执行迁移并写入测试数据）后 cargo test -- --ignored
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use news_recommendation_system::db::connection::{create_pool, DbPool};
+use news_recommendation_system::db::migrations::run_migrations;
+use news_recommendation_system::models::article::Article;
+
+pub async fn test_pool() -> DbPool {
+    let url = std::env::var("TEST_DATABASE_URL").expect("TEST_DATABASE_URL must point to a test database");
+    let pool = create_pool(&url).await.unwrap();
+    let mut client = pool.get().await.unwrap();
+    run_migrations(&mut client).await.unwrap();
+    pool
+}
+
+// 刚抓取、尚未入库的文章，计数和封面都为空
+pub fn article(url: String, title: &str, content: &str) -> Article {
+    Article {
+        id: Uuid::new_v4(),
+        title: title.to_string(),
+        content: content.to_string(),
+        content_html: Some(format!("<p>{}</p>", content)),
+        summary: Some(content.to_string()),
+        url,
+        source_name: Some("test".to_string()),
+        published_at: None,
+        crawled_at: Utc::now(),
+        categories: None,
+        source_category: None,
+        like_count: None,
+        comment_count: None,
+        favorite_count: None,
+        cover_image_id: None,
+        tags: Vec::new(),
+    }
+}
@@ -0,0 +1,195 @@
+// 使用 tests/fixtures/crawler 下录制的页面离线验证各新闻源的选择器和解析结果。
+// 站点改版后重新录制页面并更新 manifest.json，这里的断言会指出哪个选择器失效了。
+
+use news_recommendation_system::services::crawler_service::{
+    fetch_article, fetch_article_urls, get_news_sources, NewsSource, ParsedArticle,
+};
+use news_recommendation_system::services::http_fetcher::{FetchError, FixtureFetcher};
+
+fn fixtures() -> FixtureFetcher {
+    FixtureFetcher::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/crawler"))
+        .expect("crawler fixtures should load")
+}
+
+fn source(name: &str) -> NewsSource {
+    get_news_sources()
+        .into_iter()
+        .find(|s| s.name == name)
+        .unwrap_or_else(|| panic!("news source {} is not configured", name))
+}
+
+fn image_urls(article: &ParsedArticle) -> Vec<&str> {
+    article.images.iter().map(|image| image.url.as_str()).collect()
+}
+
+async fn parse(name: &str, url: &str) -> ParsedArticle {
+    fetch_article(&fixtures(), url, &source(name))
+        .await
+        .expect("fixture should be fetched")
+        .unwrap_or_else(|| panic!("{} should yield a title and content", url))
+}
+
+#[tokio::test]
+async fn every_configured_listing_selector_finds_links() {
+    let fetcher = fixtures();
+    for source in get_news_sources() {
+        let urls = fetch_article_urls(&fetcher, &source)
+            .await
+            .unwrap_or_else(|e| panic!("listing for {} failed: {}", source.name, e));
+        assert!(
+            !urls.is_empty(),
+            "link selector {:?} of {} matched nothing",
+            source.link_selector,
+            source.name
+        );
+        for url in &urls {
+            assert!(url.starts_with("http://") || url.starts_with("https://"), "{} is not absolute", url);
+        }
+    }
+}
+
+#[tokio::test]
+async fn listing_links_are_resolved_against_the_listing_url() {
+    let fetcher = fixtures();
+
+    let mut people_urls = Vec::new();
+    for source in get_news_sources().into_iter().filter(|s| s.name == "people") {
+        people_urls.extend(fetch_article_urls(&fetcher, &source).await.unwrap());
+    }
+    assert!(people_urls.contains(&"http://www.people.com.cn/n1/2024/0601/c1007-40248301.html".to_string()));
+
+    let huanqiu_urls = fetch_article_urls(&fetcher, &source("huanqiu")).await.unwrap();
+    assert_eq!(
+        huanqiu_urls,
+        vec![
+            "https://china.huanqiu.com/article/4HxYzAbCdEf".to_string(),
+            "https://world.huanqiu.com/article/4HxYzGhIjKl".to_string(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn lianhezaobao_article_is_parsed_with_selectors() {
+    let article = parse("lianhezaobao", "https://www.zaobao.com/finance/china/story20240601-3801234").await;
+
+    assert_eq!(article.title, "中国5月制造业采购经理指数回升");
+    assert!(article.content.contains("5月制造业采购经理指数回升至50.4"));
+    assert!(article.content.contains("出口订单改善"));
+    assert!(!article.content.contains("版权所有"));
+    assert_eq!(
+        image_urls(&article),
+        vec!["https://www.zaobao.com/media/photos/2024/06/01/pmi-factory.jpg"]
+    );
+    assert_eq!(article.images[0].caption.as_deref(), Some("工厂生产线"));
+    assert_eq!(article.categories[0].category, "经济");
+    // 摘要由正文中的句子组成，不超过 200 字
+    assert!(!article.summary.is_empty());
+    assert!(article.summary.chars().count() <= 200);
+    assert!(article.content.contains(article.summary.split('。').next().unwrap()));
+
+    let html = article.content_html.expect("rich content should be kept");
+    assert!(html.contains("<h2>出口订单改善</h2>"));
+    assert!(html.contains(r#"href="https://www.zaobao.com/finance/china/story20240530-3790000""#));
+    assert!(!html.contains("<article"));
+}
+
+#[tokio::test]
+async fn lianhezaobao_article_keeps_every_banner_image_in_order() {
+    let article = parse("lianhezaobao", "https://www.zaobao.com/sports/story20240601-3805678").await;
+
+    assert_eq!(article.title, "中国女排三局横扫对手夺得联赛冠军");
+    assert_eq!(
+        image_urls(&article),
+        vec![
+            "https://cassette.sphdigital.com.sg/image/zaobao/volleyball-final.jpg",
+            "https://cassette.sphdigital.com.sg/image/zaobao/volleyball-team.jpg",
+        ]
+    );
+    let captions: Vec<_> = article.images.iter().map(|image| image.caption.as_deref()).collect();
+    assert_eq!(captions, vec![Some("颁奖仪式"), Some("全队合影")]);
+    assert_eq!(article.categories[0].category, "体育");
+    assert!(article.content_html.unwrap().contains("<li>第二局：25比18</li>"));
+}
+
+#[tokio::test]
+async fn people_article_resolves_relative_image_urls() {
+    let article = parse("people", "http://www.people.com.cn/n1/2024/0601/c1007-40248301.html").await;
+
+    assert_eq!(article.title, "人工智能赋能千行百业");
+    assert!(article.content.starts_with("近年来，人工智能技术加速落地"));
+    assert!(!article.content.contains("责编"));
+    assert_eq!(
+        image_urls(&article),
+        vec!["http://www.people.com.cn/NMediaFile/2024/0601/MAIN1717201234567.jpg"]
+    );
+    assert_eq!(article.images[0].caption, None);
+    assert_eq!(article.categories[0].category, "科技");
+}
+
+#[tokio::test]
+async fn people_article_falls_back_to_readability_when_selectors_miss() {
+    let article = parse("people", "http://finance.people.com.cn/n1/2024/0601/c1004-40248201.html").await;
+
+    assert_eq!(article.title, "一季度消费市场稳中有升");
+    let paragraphs: Vec<_> = article.content.lines().collect();
+    assert_eq!(paragraphs.len(), 3);
+    assert!(paragraphs[0].starts_with("商务部最新数据显示"));
+    assert!(!article.content.contains("相关新闻"));
+    assert!(!article.content.contains("广告服务"));
+    assert!(article.images.is_empty());
+    assert_eq!(article.categories[0].category, "经济");
+}
+
+#[tokio::test]
+async fn people_section_selectors_carry_a_source_category() {
+    let sources = get_news_sources();
+    let finance = sources
+        .iter()
+        .find(|s| s.name == "people" && s.link_selector.contains(".Finance."))
+        .expect("people finance section should be configured");
+    let url = "http://finance.people.com.cn/n1/2024/0601/c1004-40248201.html";
+
+    let article = fetch_article(&fixtures(), url, finance).await.unwrap().unwrap();
+    assert_eq!(article.source_category.as_deref(), Some("经济"));
+
+    // 首页评论栏目没有明确的类别
+    let article = parse("people", url).await;
+    assert_eq!(article.source_category, None);
+
+    for source in &sources {
+        if source.link_selector.contains(".science.") {
+            assert_eq!(source.category_hint.as_deref(), Some("科技"));
+        }
+        if source.link_selector.contains(".International_Military.") {
+            assert_eq!(source.category_hint.as_deref(), Some("国际"));
+        }
+    }
+}
+
+#[tokio::test]
+async fn huanqiu_article_is_extracted_by_readability() {
+    let article = parse("huanqiu", "https://china.huanqiu.com/article/4HxYzAbCdEf").await;
+
+    assert_eq!(article.title, "多地推进生态修复 城市空气质量持续改善");
+    assert!(article.content.starts_with("记者从生态环境部门获悉"));
+    assert!(article.content.contains("实现碳中和目标的关键路径"));
+    assert!(!article.content.contains("热点推荐"));
+    assert!(!article.content.contains("版权所有"));
+    assert!(!article.content.contains("__INITIAL_STATE__"));
+    assert_eq!(article.categories[0].category, "环境");
+    assert_eq!(image_urls(&article), vec!["https://img.huanqiu.com/pic/2024/06/river.jpg"]);
+    assert_eq!(article.images[0].caption.as_deref(), Some("修复后的长江岸线"));
+
+    let html = article.content_html.unwrap();
+    assert!(html.contains(r#"src="https://img.huanqiu.com/pic/2024/06/river.jpg""#));
+    assert!(html.contains("<figcaption>修复后的长江岸线</figcaption>"));
+}
+
+#[tokio::test]
+async fn unrecorded_urls_fail_like_http_errors() {
+    let err = fetch_article(&fixtures(), "https://www.zaobao.com/not-recorded", &source("lianhezaobao"))
+        .await
+        .expect_err("unrecorded URL should not be fetched");
+
+    assert!(matches!(err.downcast_ref::<FetchError>(), Some(FetchError::MissingFixture(_))));
+}
@@ -0,0 +1,84 @@
+// 键集翻页游标的编码、解码和分页切分
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use news_recommendation_system::db::article_queries::ArticleCursor;
+use news_recommendation_system::errors::ServiceError;
+use news_recommendation_system::models::article::{Article, ArticleSort};
+use news_recommendation_system::utils::cursor::{decode_cursor, encode_cursor, parse_cursor, split_page, TimeCursor};
+
+fn article(like_count: Option<i32>, published: bool) -> Article {
+    Article {
+        id: Uuid::new_v4(),
+        title: "标题".to_string(),
+        content: String::new(),
+        content_html: None,
+        summary: None,
+        url: "https://example.com/a".to_string(),
+        source_name: None,
+        published_at: published.then(|| Utc.with_ymd_and_hms(2024, 6, 1, 8, 0, 0).unwrap()),
+        crawled_at: Utc.with_ymd_and_hms(2024, 6, 2, 9, 30, 0).unwrap(),
+        categories: None,
+        source_category: None,
+        like_count,
+        comment_count: None,
+        favorite_count: None,
+        cover_image_id: None,
+        tags: Vec::new(),
+    }
+}
+
+#[test]
+fn cursor_round_trips_and_is_url_safe() {
+    let key = TimeCursor {
+        at: Utc.with_ymd_and_hms(2024, 6, 1, 8, 0, 0).unwrap() + chrono::Duration::microseconds(123_456),
+        id: Uuid::new_v4(),
+    };
+    let cursor = encode_cursor(&key);
+    assert!(cursor.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    assert_eq!(decode_cursor::<TimeCursor>(&cursor).unwrap(), key);
+}
+
+#[test]
+fn malformed_cursors_are_bad_requests() {
+    for cursor in ["", "not a cursor", "e30", &encode_cursor(&"hello")] {
+        assert!(matches!(decode_cursor::<TimeCursor>(cursor), Err(ServiceError::BadRequest(_))), "{:?}", cursor);
+    }
+}
+
+#[test]
+fn blank_cursor_means_first_page() {
+    assert_eq!(parse_cursor::<TimeCursor>(None).unwrap(), None);
+    assert_eq!(parse_cursor::<TimeCursor>(Some(" ")).unwrap(), None);
+    assert!(parse_cursor::<TimeCursor>(Some("x")).is_err());
+}
+
+#[test]
+fn split_page_only_returns_a_cursor_when_there_is_more() {
+    let (rows, next) = split_page(vec![1, 2, 3], 3, |n| *n);
+    assert_eq!(rows, vec![1, 2, 3]);
+    assert!(next.is_none());
+
+    let (rows, next) = split_page(vec![1, 2, 3, 4], 3, |n| *n);
+    assert_eq!(rows, vec![1, 2, 3]);
+    assert_eq!(decode_cursor::<i32>(&next.unwrap()).unwrap(), 3);
+}
+
+#[test]
+fn article_cursor_uses_the_sort_keys() {
+    let liked = article(Some(7), true);
+    let cursor = ArticleCursor::for_article(&liked, ArticleSort::MostLiked);
+    assert_eq!(cursor.count, 7);
+    assert_eq!(cursor.at, liked.published_at.unwrap());
+    assert_eq!(cursor.id, liked.id);
+
+    // 按最新排序时不用计数；没有发布时间的文章按入库时间
+    let unpublished = article(None, false);
+    let cursor = ArticleCursor::for_article(&unpublished, ArticleSort::Newest);
+    assert_eq!(cursor.count, 0);
+    assert_eq!(cursor.at, unpublished.crawled_at);
+
+    let decoded: ArticleCursor = decode_cursor(&encode_cursor(&cursor)).unwrap();
+    assert_eq!(decoded, cursor);
+}
@@ -0,0 +1,78 @@
+// 下载图片的校验规则：只按文件头识别格式，拒绝非图片、超限和过小的图片，SVG 去掉脚本后保存。
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, RgbImage};
+use news_recommendation_system::services::image_service::{
+    sniff_image_kind, validate_image, ImageKind, ImageRejection, MAX_IMAGE_BYTES,
+};
+use news_recommendation_system::utils::html_sanitizer::sanitize_svg;
+
+fn encode(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::new(width, height));
+    let mut buffer = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, format).unwrap();
+    buffer.into_inner()
+}
+
+#[test]
+fn raster_images_are_identified_by_magic_bytes() {
+    let png = validate_image(encode(64, 48, ImageFormat::Png)).expect("png should be accepted");
+    assert_eq!(png.kind, ImageKind::Png);
+    assert_eq!((png.width, png.height), (Some(64), Some(48)));
+
+    let jpeg = validate_image(encode(32, 32, ImageFormat::Jpeg)).expect("jpeg should be accepted");
+    assert_eq!(jpeg.kind.content_type(), "image/jpeg");
+}
+
+#[test]
+fn html_error_pages_are_not_images() {
+    let page = b"<!DOCTYPE html><html><body>403 Forbidden</body></html>".to_vec();
+    assert_eq!(sniff_image_kind(&page), None);
+    assert_eq!(validate_image(page).err(), Some(ImageRejection::NotAnImage));
+}
+
+#[test]
+fn truncated_images_are_undecodable() {
+    let mut png = encode(64, 64, ImageFormat::Png);
+    png.truncate(40);
+    let rejection = validate_image(png).err().expect("truncated png should be rejected");
+    assert_eq!(rejection.reason(), "undecodable");
+}
+
+#[test]
+fn tracking_pixels_and_oversized_payloads_are_rejected() {
+    assert_eq!(
+        validate_image(encode(1, 1, ImageFormat::Gif)).err(),
+        Some(ImageRejection::TooSmall { width: 1, height: 1 })
+    );
+    assert_eq!(
+        validate_image(vec![0xFF; MAX_IMAGE_BYTES + 1]).err(),
+        Some(ImageRejection::TooLarge { limit: MAX_IMAGE_BYTES })
+    );
+}
+
+#[test]
+fn svg_scripts_and_handlers_are_stripped() {
+    let svg = r#"<?xml version="1.0"?>
+<!-- logo -->
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 10 10" onload="alert(1)">
+  <script>alert(document.cookie)</script>
+  <foreignObject><iframe src="https://evil.example"></iframe></foreignObject>
+  <a xlink:href="javascript:alert(1)"><rect width="10" height="10" onclick="steal()"/></a>
+  <text>A &amp; B</text>
+</svg>"#;
+    assert_eq!(sniff_image_kind(svg.as_bytes()), Some(ImageKind::Svg));
+
+    let cleaned = sanitize_svg(svg).expect("svg root should be found");
+    assert!(cleaned.starts_with(r#"<svg viewBox="0 0 10 10" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="#));
+    assert!(cleaned.contains("<rect"));
+    assert!(cleaned.contains("A &amp; B"));
+    for forbidden in ["script", "alert", "onload", "onclick", "foreignObject", "iframe", "javascript:"] {
+        assert!(!cleaned.contains(forbidden), "{} survived: {}", forbidden, cleaned);
+    }
+
+    let validated = validate_image(svg.as_bytes().to_vec()).expect("svg should be accepted");
+    assert_eq!(validated.kind.content_type(), "image/svg+xml");
+    assert_eq!(validated.bytes, cleaned.into_bytes());
+}
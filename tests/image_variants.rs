@@ -0,0 +1,31 @@
+// /images/{id} 的格式协商规则
+
+use news_recommendation_system::services::image_service::{negotiate_format, FitMode, OutputFormat, VariantRequest};
+
+const CHROME_ACCEPT: &str = "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8";
+const SAFARI_15_ACCEPT: &str = "image/webp,image/png,image/svg+xml,image/*;q=0.8,*/*;q=0.5";
+
+fn resized(width: u32) -> VariantRequest {
+    VariantRequest { width: Some(width), height: None, fit: FitMode::Contain }
+}
+
+#[test]
+fn best_format_listed_in_accept_wins() {
+    assert_eq!(negotiate_format(Some(CHROME_ACCEPT), "image/jpeg", &resized(640)), OutputFormat::Avif);
+    assert_eq!(negotiate_format(Some(SAFARI_15_ACCEPT), "image/jpeg", &resized(640)), OutputFormat::WebP);
+    assert_eq!(negotiate_format(Some("image/avif;q=0,image/webp"), "image/jpeg", &resized(640)), OutputFormat::WebP);
+}
+
+#[test]
+fn wildcards_do_not_opt_into_modern_formats() {
+    assert_eq!(negotiate_format(Some("*/*"), "image/jpeg", &resized(640)), OutputFormat::Jpeg);
+    assert_eq!(negotiate_format(None, "image/png", &resized(640)), OutputFormat::Png);
+    assert_eq!(negotiate_format(None, "image/jpeg", &VariantRequest::default()), OutputFormat::Original);
+}
+
+#[test]
+fn svg_and_unresized_gif_are_served_as_stored() {
+    assert_eq!(negotiate_format(Some(CHROME_ACCEPT), "image/svg+xml", &resized(240)), OutputFormat::Original);
+    assert_eq!(negotiate_format(Some(CHROME_ACCEPT), "image/gif", &VariantRequest::default()), OutputFormat::Original);
+    assert_eq!(negotiate_format(Some(CHROME_ACCEPT), "image/gif", &resized(240)), OutputFormat::Avif);
+}
@@ -0,0 +1,87 @@
+// 列表接口共用的分页参数校验和返回格式
+
+use actix_web::{test::TestRequest, FromRequest};
+
+use news_recommendation_system::errors::ServiceError;
+use news_recommendation_system::models::pagination::{Pagination, PaginationQuery, DEFAULT_PAGE_SIZE, MAX_PAGE, MAX_PAGE_SIZE};
+use news_recommendation_system::utils::cursor::encode_cursor;
+
+async fn extract(query: &str) -> Result<Pagination, ServiceError> {
+    let req = TestRequest::with_uri(&format!("/items?{}", query)).to_http_request();
+    Pagination::extract(&req).await
+}
+
+#[actix_web::test]
+async fn defaults_to_the_first_page() {
+    let pagination = extract("").await.unwrap();
+    assert_eq!(pagination, Pagination::default());
+    assert_eq!(pagination.page(), 1);
+    assert_eq!(pagination.limit(), DEFAULT_PAGE_SIZE);
+    assert_eq!(pagination.offset(), 0);
+    assert_eq!(pagination.fetch_limit(), DEFAULT_PAGE_SIZE + 1);
+}
+
+#[actix_web::test]
+async fn pages_are_one_based() {
+    let pagination = extract("page=3&limit=10&category=world").await.unwrap();
+    assert_eq!(pagination.page(), 3);
+    assert_eq!(pagination.offset(), 20);
+}
+
+#[actix_web::test]
+async fn out_of_range_values_are_rejected() {
+    for query in [
+        "page=0".to_string(),
+        "page=-1".to_string(),
+        format!("page={}", MAX_PAGE + 1),
+        "limit=0".to_string(),
+        format!("limit={}", MAX_PAGE_SIZE + 1),
+        "limit=100000".to_string(),
+        "page=abc".to_string(),
+    ] {
+        assert!(matches!(extract(&query).await, Err(ServiceError::BadRequest(_))), "{}", query);
+    }
+    assert_eq!(extract(&format!("limit={}", MAX_PAGE_SIZE)).await.unwrap().limit(), MAX_PAGE_SIZE);
+}
+
+#[actix_web::test]
+async fn cursor_replaces_the_page_number() {
+    let cursor = encode_cursor(&42);
+    let pagination = extract(&format!("cursor={}&limit=5", cursor)).await.unwrap();
+    assert_eq!(pagination.offset(), 0);
+    assert_eq!(pagination.cursor::<i32>().unwrap(), Some(42));
+    assert!(pagination.reject_cursor().is_err());
+
+    assert!(matches!(extract(&format!("cursor={}&page=2", cursor)).await, Err(ServiceError::BadRequest(_))));
+    // 空游标等于没给
+    assert_eq!(extract("cursor=&page=2").await.unwrap().page(), 2);
+}
+
+#[test]
+fn pages_report_whether_more_rows_follow() {
+    let pagination = PaginationQuery { limit: Some(2), ..PaginationQuery::default() }.validate().unwrap();
+
+    let page = pagination.page_of(vec![1, 2, 3], 3, |n| *n);
+    assert_eq!(page.items, vec![1, 2]);
+    assert!(page.has_more);
+    assert!(page.next_cursor.is_some());
+    assert_eq!(page.total, 3);
+
+    let page = pagination.page_of(vec![1, 2], 2, |n| *n);
+    assert!(!page.has_more);
+    assert!(page.next_cursor.is_none());
+
+    let page = pagination.page_without_cursor(vec![1, 2, 3], 3).map(|n| n * 10);
+    assert_eq!(page.items, vec![10, 20]);
+    assert!(page.has_more);
+    assert!(page.next_cursor.is_none());
+}
+
+#[test]
+fn envelope_uses_the_shared_field_names() {
+    let page = Pagination::default().page_of(vec!["a"], 1, |s| s.to_string());
+    let json = serde_json::to_value(&page).unwrap();
+    let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    keys.sort();
+    assert_eq!(keys, ["has_more", "items", "limit", "next_cursor", "page", "total"]);
+}
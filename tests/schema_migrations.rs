@@ -0,0 +1,84 @@
+// 编译进程序的表结构迁移：版本顺序、文件登记、校验和以及与 openGauss 的兼容写法
+
+use chrono::Utc;
+
+use news_recommendation_system::db::migrations::{pending_migrations, AppliedMigration, Migration, MIGRATIONS};
+
+fn applied(migration: &Migration) -> AppliedMigration {
+    AppliedMigration {
+        version: migration.version,
+        name: migration.name.to_string(),
+        checksum: migration.checksum(),
+        applied_at: Utc::now(),
+    }
+}
+
+#[test]
+fn versions_start_at_one_and_have_no_gaps() {
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
+        assert_eq!(migration.version as usize, index + 1);
+        assert!(migration.name.starts_with(&format!("{:04}_", migration.version)), "{}", migration.name);
+    }
+}
+
+#[test]
+fn every_file_in_the_migrations_directory_is_embedded() {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+    let mut files: Vec<String> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.ends_with(".sql"))
+        .collect();
+    files.sort();
+    let embedded: Vec<String> = MIGRATIONS.iter().map(Migration::file_name).collect();
+    assert_eq!(files, embedded);
+}
+
+#[test]
+fn migrations_avoid_syntax_opengauss_lacks() {
+    for migration in MIGRATIONS {
+        let sql = migration.sql.to_ascii_uppercase();
+        for unsupported in ["ADD COLUMN IF NOT EXISTS", "EXECUTE FUNCTION"] {
+            assert!(!sql.contains(unsupported), "{} uses {}", migration.file_name(), unsupported);
+        }
+    }
+}
+
+#[test]
+fn checksum_ignores_line_endings() {
+    let migration = &MIGRATIONS[0];
+    let crlf = Migration {
+        version: migration.version,
+        name: migration.name,
+        sql: Box::leak(migration.sql.replace('\n', "\r\n").into_boxed_str()),
+    };
+    assert_eq!(crlf.checksum(), migration.checksum());
+    assert_eq!(migration.checksum().len(), 64);
+}
+
+#[test]
+fn fresh_database_runs_every_migration() {
+    let pending = pending_migrations(&[]).unwrap();
+    assert_eq!(pending.len(), MIGRATIONS.len());
+}
+
+#[test]
+fn applied_migrations_are_skipped() {
+    let first = applied(&MIGRATIONS[0]);
+    let pending = pending_migrations(&[first]).unwrap();
+    assert!(pending.iter().all(|m| m.version != 1));
+    assert_eq!(pending.len(), MIGRATIONS.len() - 1);
+
+    let all: Vec<AppliedMigration> = MIGRATIONS.iter().map(applied).collect();
+    assert!(pending_migrations(&all).unwrap().is_empty());
+}
+
+#[test]
+fn edited_or_unknown_migrations_are_rejected() {
+    let edited = AppliedMigration { checksum: "0".repeat(64), ..applied(&MIGRATIONS[0]) };
+    let error = pending_migrations(&[edited]).unwrap_err().to_string();
+    assert!(error.contains("0001_initial_schema.sql"), "{}", error);
+
+    let newer = AppliedMigration { version: 9999, name: "9999_from_the_future".to_string(), ..applied(&MIGRATIONS[0]) };
+    assert!(pending_migrations(&[newer]).is_err());
+}
@@ -0,0 +1,50 @@
+// 搜索自动补全的前缀树
+
+use news_recommendation_system::models::article::{SearchSuggestion, SuggestionKind};
+use news_recommendation_system::services::suggest_service::{SuggestionTrie, MAX_SUGGESTIONS};
+
+fn suggestion(text: &str, kind: SuggestionKind, score: f64) -> SearchSuggestion {
+    SearchSuggestion { text: text.to_string(), kind, score }
+}
+
+fn texts(suggestions: &[SearchSuggestion]) -> Vec<&str> {
+    suggestions.iter().map(|s| s.text.as_str()).collect()
+}
+
+#[test]
+fn completions_are_ranked_by_score_across_sources() {
+    let trie = SuggestionTrie::build(vec![
+        suggestion("新能源汽车出口增长", SuggestionKind::Title, 1.0),
+        suggestion("新能源", SuggestionKind::Tag, 4.0),
+        suggestion("新能源汽车", SuggestionKind::Query, 9.0),
+        suggestion("央行降准", SuggestionKind::Title, 1.0),
+    ]);
+    assert_eq!(texts(&trie.complete("新", 10)), vec!["新能源汽车", "新能源", "新能源汽车出口增长"]);
+    assert_eq!(texts(&trie.complete("新能源汽", 10)), vec!["新能源汽车", "新能源汽车出口增长"]);
+    assert_eq!(texts(&trie.complete("新", 1)), vec!["新能源汽车"]);
+    assert!(trie.complete("体育", 10).is_empty());
+    assert!(trie.complete("  ", 10).is_empty());
+}
+
+#[test]
+fn matching_ignores_case_and_extra_whitespace_and_merges_duplicates() {
+    let trie = SuggestionTrie::build(vec![
+        suggestion("OpenAI  发布", SuggestionKind::Query, 3.0),
+        suggestion("openai 发布", SuggestionKind::Query, 6.0),
+        suggestion("OpenAI", SuggestionKind::Tag, 2.0),
+    ]);
+    assert_eq!(trie.len(), 2);
+    let completions = trie.complete("open", 10);
+    // 重复的搜索词得分累加，文本取得分较高的写法
+    assert_eq!(completions[0], suggestion("openai 发布", SuggestionKind::Query, 9.0));
+    assert_eq!(completions[1].text, "OpenAI");
+    assert_eq!(texts(&trie.complete("OPENAI 发", 10)), vec!["openai 发布"]);
+}
+
+#[test]
+fn each_prefix_keeps_at_most_the_configured_number_of_completions() {
+    let trie = SuggestionTrie::build((0..50).map(|i| suggestion(&format!("标题{:02}", i), SuggestionKind::Title, i as f64)));
+    let completions = trie.complete("标题", 100);
+    assert_eq!(completions.len(), MAX_SUGGESTIONS);
+    assert_eq!(completions[0].text, "标题49");
+}
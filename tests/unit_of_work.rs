@@ -0,0 +1,35 @@
+// 服务层事务：隔离级别对应的 START TRANSACTION 语句，以及取不到连接时的错误
+
+use news_recommendation_system::db::connection::create_pool;
+use news_recommendation_system::db::unit_of_work::{Isolation, UnitOfWork};
+use news_recommendation_system::errors::ServiceError;
+
+#[test]
+fn start_statement_sets_isolation_and_access_mode() {
+    assert_eq!(
+        Isolation::ReadCommitted.start_statement(false),
+        "START TRANSACTION ISOLATION LEVEL READ COMMITTED, READ WRITE"
+    );
+    assert_eq!(
+        Isolation::RepeatableRead.start_statement(true),
+        "START TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY"
+    );
+}
+
+#[actix_web::test]
+async fn unreachable_database_is_a_connection_error() {
+    // 端口 1 上没有数据库，连接会立即被拒绝
+    let pool = create_pool("postgresql://nobody@127.0.0.1:1/none").await.unwrap();
+    for read_only in [false, true] {
+        let result = if read_only {
+            UnitOfWork::read_only(&pool, Isolation::RepeatableRead).await
+        } else {
+            UnitOfWork::begin(&pool, Isolation::ReadCommitted).await
+        };
+        match result {
+            Err(ServiceError::InternalServerError(message)) => assert_eq!(message, "Database connection error"),
+            Err(other) => panic!("unexpected error: {}", other),
+            Ok(_) => panic!("expected a connection error"),
+        }
+    }
+}